@@ -2,82 +2,407 @@ use std::sync::{Arc, Mutex};
 
 use egui::{Color32, RichText};
 
-use crate::config::AppConfig;
+use crate::config::{
+    AppConfig, AuthMethod, CustomHeader, FolderLayout, RetentionAction, StartTab, WindowGeometry,
+    DEFAULT_MY_ISSUES_JQL, DEFAULT_USER_AGENT,
+};
 use crate::downloader::{format_size, DownloadItem, DownloadManager, FileState};
-use crate::jira::{parse_issue_key, IssueInfo, IssueSummary, JiraClient};
-use crate::storage::{ControlFile, IncidentFolder, StorageManager};
+use crate::export::{export_history, export_inventory, export_manifest, zip_folder, ExportFormat};
+use crate::history::{self, HistoryEntry};
+use crate::jira::{
+    check_jira_url, issue_browse_url, load_ca_bundle, parse_header, parse_issue_key,
+    parse_issue_keys, rate_limit_remaining, AttachmentKind, AttachmentSource, FetchIssueError,
+    IssueInfo, IssueSummary, JiraClient,
+};
+use crate::reconcile::{reconcile, ReconcileReport};
+use crate::sizing::SizingJob;
+use crate::status_poll::{StatusPoller, StatusUpdates};
+use crate::retention::{self, RetentionTask};
+use crate::storage::{
+    free_space, layout_subfolder, normal_path, validate_filename_template, ControlFile,
+    IncidentFolder, IncidentId, StorageManager, UnmanagedFolder,
+};
+use crate::taskbar::{Taskbar, TaskbarProgress};
+use crate::thumbnails::ThumbnailCache;
+use crate::updater::{check_for_update, UpdateInfo};
+use crate::watch::ConnectionState;
+
+/// "Delete All Marked" asks for a typed confirmation above either limit.
+const BULK_DELETE_MAX_COUNT: usize = 10;
+const BULK_DELETE_MAX_BYTES: u64 = 10 * 1_073_741_824;
+
+/// Filled by a background task, taken by the next frame that finds it set.
+type Slot<T> = Arc<Mutex<Option<T>>>;
+
+/// Issue fetches as they finish, each with what was asked for.
+type IssueFetches<K = String> = Arc<Mutex<Vec<(K, Result<IssueInfo, String>)>>>;
 
 #[derive(Debug, Clone, PartialEq)]
 enum Tab {
     Settings,
     Incident,
     IncidentsManager,
+    History,
+}
+
+impl Tab {
+    fn from_start(start: StartTab) -> Option<Tab> {
+        match start {
+            StartTab::Incident => Some(Tab::Incident),
+            StartTab::IncidentsManager => Some(Tab::IncidentsManager),
+            StartTab::History => Some(Tab::History),
+            StartTab::Settings => Some(Tab::Settings),
+            StartTab::Auto | StartTab::LastUsed => None,
+        }
+    }
+
+    fn as_start(&self) -> StartTab {
+        match self {
+            Tab::Incident => StartTab::Incident,
+            Tab::IncidentsManager => StartTab::IncidentsManager,
+            Tab::History => StartTab::History,
+            Tab::Settings => StartTab::Settings,
+        }
+    }
+}
+
+const SHORTCUT_FOCUS_INPUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::L);
+const SHORTCUT_SELECT_ALL: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::A);
+const SHORTCUT_DOWNLOAD: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::D);
+const SHORTCUT_SETTINGS: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Comma);
+
+/// Column the attachments grid is sorted by.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AttachmentSort {
+    Name,
+    Size,
+    Date,
+}
+
+/// Column the Incidents Manager grid is sorted by.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum IncidentSort {
+    Key,
+    Status,
+    Size,
+    LastChecked,
+}
+
+/// Open "Compare with JIRA" report for one incident folder.
+struct ReconcileView {
+    root: std::path::PathBuf,
+    issue_key: String,
+    report: ReconcileReport,
+    /// Download rows for missing and size-mismatched attachments.
+    items: Vec<DownloadItem>,
+    /// Orphan paths flagged so far, in this view or earlier ones (relative to
+    /// the issue folder).
+    flagged: Vec<String>,
+}
+
+/// A started batch, watched until every one of its files has finished.
+struct BatchWatch {
+    /// Names the batch in the notification, e.g. the issue key.
+    label: String,
+    states: Vec<Arc<Mutex<FileState>>>,
 }
 
 pub struct App {
     runtime: Arc<tokio::runtime::Runtime>,
     tab: Tab,
+    taskbar: Taskbar,
+    update_available: Arc<Mutex<Option<UpdateInfo>>>,
+    /// Every batch started from any tab, until each has finished.
+    batch_watches: Vec<BatchWatch>,
+    /// Further issue keys from a multi-item drop, offered as one-click fetches.
+    dropped_keys: Vec<String>,
+    /// Set by Ctrl+L; the Incident tab focuses its input on the next frame.
+    focus_incident_input: bool,
+    /// Latest normal-window placement, saved on exit.
+    window_geometry: Option<WindowGeometry>,
+    /// Background auto-cleanup, running only while enabled in Settings.
+    retention: Option<RetentionTask>,
+    /// Background status refresh, running only while enabled in Settings.
+    status_poller: Option<StatusPoller>,
+    /// Whether the background loops can reach JIRA after the machine wakes;
+    /// shared by auto-cleanup and the status refresh.
+    background_connection: Arc<Mutex<ConnectionState>>,
+    /// "Zip" from either tab: whether one is running and its last outcome.
+    zip_pending: bool,
+    zip_status: Arc<Mutex<Option<Result<String, String>>>>,
+    /// Outcome of the last "Export Manifest" in the Incident tab.
+    manifest_status: Option<Result<String, String>>,
 
     // Settings tab
     config: AppConfig,
     config_saved_msg: Option<String>,
+    closed_statuses_input: String,
     connection_status: Arc<Mutex<Option<Result<String, String>>>>,
+    /// Why the picked CA bundle can't be used, checked once when it is picked.
+    ca_bundle_error: Option<String>,
+    /// Why the download directory can't take files, from the last check.
+    download_dir_warning: Arc<Mutex<Option<String>>>,
+    /// Shows the API token in clear text until Settings is left.
+    show_token: bool,
+    /// Whether the saved config holds a token for the active profile.
+    token_saved: bool,
 
     // Incident tab
     incident_input: String,
-    fetch_status: Arc<Mutex<Option<Result<IssueInfo, String>>>>,
+    /// Fetch results are tagged with the generation that requested them so a
+    /// late answer to a superseded or cancelled fetch is dropped.
+    fetch_status: Slot<(u64, Result<IssueInfo, FetchIssueError>)>,
+    fetch_generation: u64,
+    fetch_task: Option<tokio::task::AbortHandle>,
+    fetch_interrupted: bool,
+    /// Key the last fetch asked for; JIRA answers with the new key for moved issues.
+    fetch_requested_key: String,
+    /// (requested key, key JIRA answered with) when the issue has moved.
+    moved_issue: Option<(String, String)>,
+    relocate_error: Option<String>,
     current_issue: Option<IssueInfo>,
     download_items: Vec<DownloadItem>,
+    /// One-off download folder for the fetched issue instead of `download_dir`.
+    save_to: Option<std::path::PathBuf>,
+    /// Keeps `save_to` across fetches.
+    save_to_pinned: bool,
+    /// Shown when an "Open" click finds the saved file gone.
+    open_notice: Option<String>,
     download_manager: DownloadManager,
+    thumbnails_only: bool,
+    /// Shows the attachments in one section per upload day.
+    group_by_date: bool,
+    /// Also list the attachments of the issue's subtasks / linked issues.
+    include_subtasks: bool,
+    include_linked: bool,
+    /// Keys of those related issues once known, then each one as it's fetched.
+    /// Both are replaced on every fetch so an older issue's results land nowhere.
+    related_keys: Slot<Result<Vec<String>, String>>,
+    related_pending: usize,
+    related_results: IssueFetches,
+    related_issues: Vec<(IssueInfo, Vec<DownloadItem>)>,
+    related_errors: Vec<String>,
+    /// (bytes needed, bytes free) when a batch won't fit and awaits confirmation.
+    space_warning: Option<(u64, u64)>,
+    previews: ThumbnailCache,
+    /// Case-insensitive filename filter for the attachments grid.
+    attachment_filter: String,
+    /// Active sort column and whether it's ascending; `None` keeps JIRA's order.
+    attachment_sort: Option<(AttachmentSort, bool)>,
+    /// Kinds unticked in the auto-select filter.
+    excluded_kinds: std::collections::BTreeSet<AttachmentKind>,
+    /// Auto-select size bounds in MB; a max of 0 means no upper limit.
+    size_filter_mb: (f64, f64),
 
     // My Cases panel
     my_issues: Vec<IssueSummary>,
-    my_issues_status: Arc<Mutex<Option<Result<Vec<IssueSummary>, String>>>>,
+    my_issues_status: Slot<Result<Vec<IssueSummary>, String>>,
     my_issues_loading: bool,
+    my_issues_loaded: bool,
     my_issues_error: Option<String>,
+    /// "Download All My Cases": issue fetches still to come, their results,
+    /// and every file queued so far (for the aggregate progress bar).
+    bulk_pending: usize,
+    bulk_results: IssueFetches,
+    bulk_items: Vec<DownloadItem>,
+    bulk_errors: Vec<String>,
+    /// "Fetch several issues": the pasted keys, fetches still to come, their
+    /// results, and each fetched issue with its rows, in the order pasted.
+    multi_input: String,
+    multi_pending: usize,
+    multi_results: IssueFetches,
+    multi_issues: Vec<(IssueInfo, Vec<DownloadItem>)>,
+    multi_errors: Vec<String>,
 
     // Incidents Manager tab
     incidents: Vec<IncidentFolder>,
     incidents_scan_status: String,
-    check_status: Arc<Mutex<Vec<(String, Result<String, String>)>>>,
-    delete_confirm: Option<String>,
+    check_status: StatusUpdates,
+    /// View-side filters for the incidents grid; `incidents` itself is never trimmed.
+    incident_filter: String,
+    incident_closed_only: bool,
+    incident_open_only: bool,
+    incident_marked_only: bool,
+    incident_tag_filter: Option<String>,
+    /// Text typed into a row's "add tag" menu.
+    tag_input: String,
+    /// Active sort column and whether it's ascending.
+    incident_sort: (IncidentSort, bool),
+    delete_confirm: Option<IncidentId>,
+    /// Folders picked by "Delete All Marked", each with whether it's still ticked.
+    bulk_delete_confirm: Option<Vec<(IncidentId, bool)>>,
+    bulk_delete_typed: String,
+    /// Folders the recycle bin refused, awaiting a go-ahead to delete them permanently.
+    permanent_delete_confirm: Option<Vec<IncidentId>>,
+    sizing: Option<SizingJob>,
+    unmanaged: Vec<UnmanagedFolder>,
+    repair_results: IssueFetches<UnmanagedFolder>,
+    reconcile_result: Slot<(IncidentId, Result<IssueInfo, String>)>,
+    reconcile_view: Option<ReconcileView>,
+
+    // History tab
+    history: Vec<HistoryEntry>,
+    history_search: String,
+    history_status: String,
+    /// Newest entries shown in the History tab; 0 shows all.
+    history_limit: usize,
+    history_clear_confirm: bool,
+    /// Free bytes on the download drive and when it was last queried.
+    free_space: Option<(std::time::Instant, Option<u64>)>,
+    /// Bytes downloaded so far at the last speed sample.
+    speed_sample: Option<(std::time::Instant, u64)>,
+    download_speed: f64,
 }
 
 impl App {
-    pub fn new(_cc: &eframe::CreationContext<'_>, runtime: Arc<tokio::runtime::Runtime>) -> Self {
+    pub fn new(cc: &eframe::CreationContext<'_>, runtime: Arc<tokio::runtime::Runtime>) -> Self {
+        crate::fonts::install_fallback_fonts(&cc.egui_ctx);
+
         let config = AppConfig::load();
-        let dm = DownloadManager::new(Arc::clone(&runtime));
-        let start_tab = if config.jira_url.is_empty() {
-            Tab::Settings
-        } else {
-            Tab::Incident
+        let dm = DownloadManager::new(Arc::clone(&runtime), config.max_concurrent_downloads);
+        let token_saved = config.has_saved_token();
+        let preferred = match config.start_tab {
+            StartTab::LastUsed => config.last_tab.and_then(Tab::from_start),
+            other => Tab::from_start(other),
+        };
+        // Without credentials nothing else is usable, so Settings always wins.
+        let start_tab = match preferred {
+            _ if config.jira_url.is_empty() => Tab::Settings,
+            Some(tab) => tab,
+            None => Tab::Incident,
         };
 
         let my_issues_status = Arc::new(Mutex::new(None));
 
+        let previews = ThumbnailCache::new(Arc::clone(&runtime));
+
         let mut app = Self {
             runtime,
             tab: start_tab,
+            taskbar: Taskbar::new(),
+            update_available: Arc::new(Mutex::new(None)),
+            batch_watches: Vec::new(),
+            dropped_keys: Vec::new(),
+            focus_incident_input: false,
+            window_geometry: config.window,
+            retention: None,
+            status_poller: None,
+            background_connection: Arc::new(Mutex::new(ConnectionState::Online)),
+            zip_pending: false,
+            zip_status: Arc::new(Mutex::new(None)),
+            manifest_status: None,
+            closed_statuses_input: config.closed_statuses.join(", "),
             config,
             config_saved_msg: None,
             connection_status: Arc::new(Mutex::new(None)),
+            ca_bundle_error: None,
+            download_dir_warning: Arc::new(Mutex::new(None)),
+            show_token: false,
+            token_saved,
             incident_input: String::new(),
             fetch_status: Arc::new(Mutex::new(None)),
+            fetch_generation: 0,
+            fetch_task: None,
+            fetch_interrupted: false,
+            fetch_requested_key: String::new(),
+            moved_issue: None,
+            relocate_error: None,
             current_issue: None,
             download_items: Vec::new(),
+            save_to: None,
+            save_to_pinned: false,
+            open_notice: None,
             download_manager: dm,
+            thumbnails_only: false,
+            group_by_date: false,
+            include_subtasks: false,
+            include_linked: false,
+            related_keys: Arc::new(Mutex::new(None)),
+            related_pending: 0,
+            related_results: Arc::new(Mutex::new(Vec::new())),
+            related_issues: Vec::new(),
+            related_errors: Vec::new(),
+            space_warning: None,
+            previews,
+            attachment_filter: String::new(),
+            attachment_sort: None,
+            excluded_kinds: std::collections::BTreeSet::new(),
+            size_filter_mb: (0.0, 0.0),
             my_issues: Vec::new(),
             my_issues_status,
             my_issues_loading: false,
+            my_issues_loaded: false,
             my_issues_error: None,
+            bulk_pending: 0,
+            bulk_results: Arc::new(Mutex::new(Vec::new())),
+            bulk_items: Vec::new(),
+            bulk_errors: Vec::new(),
+            multi_input: String::new(),
+            multi_pending: 0,
+            multi_results: Arc::new(Mutex::new(Vec::new())),
+            multi_issues: Vec::new(),
+            multi_errors: Vec::new(),
             incidents: Vec::new(),
             incidents_scan_status: String::new(),
             check_status: Arc::new(Mutex::new(Vec::new())),
+            incident_filter: String::new(),
+            incident_closed_only: false,
+            incident_open_only: false,
+            incident_marked_only: false,
+            incident_tag_filter: None,
+            tag_input: String::new(),
+            incident_sort: (IncidentSort::Key, true),
             delete_confirm: None,
+            bulk_delete_confirm: None,
+            permanent_delete_confirm: None,
+            bulk_delete_typed: String::new(),
+            sizing: None,
+            unmanaged: Vec::new(),
+            repair_results: Arc::new(Mutex::new(Vec::new())),
+            reconcile_result: Arc::new(Mutex::new(None)),
+            reconcile_view: None,
+            history: Vec::new(),
+            history_search: String::new(),
+            history_status: String::new(),
+            history_limit: 200,
+            history_clear_confirm: false,
+            free_space: None,
+            speed_sample: None,
+            download_speed: 0.0,
         };
 
+        if app.config.check_for_updates {
+            let source = app.config.update_check_url.clone();
+            let slot = Arc::clone(&app.update_available);
+            let ctx = cc.egui_ctx.clone();
+            app.runtime.spawn(async move {
+                // Failures are silent — an update banner is never worth an error dialog.
+                if let Ok(Some(info)) = check_for_update(&source).await {
+                    *slot.lock().unwrap() = Some(info);
+                    ctx.request_repaint();
+                }
+            });
+        }
+
+        app.restart_retention(&cc.egui_ctx);
+        app.restart_status_poll(&cc.egui_ctx);
+        app.check_download_dir(&cc.egui_ctx);
+
+        // Pick up where the last session left off; a failed re-fetch (e.g.
+        // offline) still leaves the key in the input.
+        if let Some(key) = app.config.last_issue.clone() {
+            app.incident_input = key;
+            if app.config.reopen_last_issue && app.config.has_credentials() {
+                app.do_fetch(&cc.egui_ctx);
+            }
+        }
+
         // Auto-load my issues if credentials are already saved
-        if !app.config.jira_url.is_empty() && !app.config.email.is_empty() {
+        if app.config.has_credentials() {
             // We can't pass ctx here, loading will trigger on first Incident tab render
             app.my_issues_loading = false; // will be triggered in render
         }
@@ -91,20 +416,248 @@ impl App {
         ui.heading("Settings");
         ui.add_space(8.0);
 
+        let mut profile_changed = false;
+
         egui::Grid::new("settings_grid")
             .num_columns(2)
             .spacing([12.0, 8.0])
             .show(ui, |ui| {
+                ui.label("Profile:");
+                ui.horizontal(|ui| {
+                    let mut changed = false;
+                    let active = self.config.active_profile;
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.config.profiles[active].name)
+                            .desired_width(160.0),
+                    );
+                    if ui.button("Add Profile").clicked() {
+                        let name = format!("Profile {}", self.config.profiles.len() + 1);
+                        self.config.add_profile(&name);
+                        changed = true;
+                    }
+                    if ui
+                        .add_enabled(self.config.profiles.len() > 1, egui::Button::new("Remove"))
+                        .clicked()
+                    {
+                        self.config.remove_active_profile();
+                        changed = true;
+                    }
+                    profile_changed = changed;
+                });
+                ui.end_row();
+
                 ui.label("JIRA URL:");
-                ui.text_edit_singleline(&mut self.config.jira_url);
+                ui.vertical(|ui| {
+                    let response = ui.text_edit_singleline(&mut self.config.jira_url);
+                    let typed = self.config.jira_url.trim().trim_end_matches('/').to_string();
+                    if typed.is_empty() {
+                        return;
+                    }
+                    match check_jira_url(&typed) {
+                        Ok(base) => {
+                            if response.lost_focus() && !typed.contains("://") {
+                                self.config.jira_url = format!("https://{typed}");
+                            }
+                            if base != typed {
+                                ui.label(RichText::new(format!("Will connect to {base}")).weak());
+                            }
+                        }
+                        Err(e) => {
+                            ui.colored_label(Color32::from_rgb(200, 60, 60), e);
+                        }
+                    }
+                });
+                ui.end_row();
+
+                ui.label("Authentication:");
+                egui::ComboBox::from_id_salt("auth_method")
+                    .selected_text(self.config.auth_method.label())
+                    .show_ui(ui, |ui| {
+                        for method in [AuthMethod::Basic, AuthMethod::Bearer] {
+                            ui.selectable_value(&mut self.config.auth_method, method, method.label());
+                        }
+                    });
+                ui.end_row();
+
+                if self.config.auth_method == AuthMethod::Basic {
+                    ui.label("Email:");
+                    ui.text_edit_singleline(&mut self.config.email);
+                    ui.end_row();
+                }
+
+                ui.label(match self.config.auth_method {
+                    AuthMethod::Basic => "API Token:",
+                    AuthMethod::Bearer => "Access Token:",
+                });
+                ui.vertical(|ui| {
+                    ui.horizontal(|ui| {
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.config.api_token)
+                                .password(!self.show_token),
+                        );
+                        ui.toggle_value(&mut self.show_token, "👁")
+                            .on_hover_text("Show the token while you check it");
+                        if ui
+                            .add_enabled(!self.config.api_token.is_empty(), egui::Button::new("Clear"))
+                            .clicked()
+                        {
+                            self.config.api_token.clear();
+                        }
+                        let saved = if self.token_saved {
+                            "🔒 A token is saved"
+                        } else {
+                            "No token saved"
+                        };
+                        ui.label(RichText::new(saved).weak());
+                    });
+                    if self.config.api_token.trim() != self.config.api_token {
+                        ui.colored_label(
+                            Color32::from_rgb(200, 120, 0),
+                            "Leading or trailing spaces will be removed on save.",
+                        );
+                    }
+                });
+                ui.end_row();
+
+                ui.label("API Path Prefix:");
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.config.api_prefix)
+                        .hint_text("/rest")
+                        .desired_width(200.0),
+                );
                 ui.end_row();
 
-                ui.label("Email:");
-                ui.text_edit_singleline(&mut self.config.email);
+                ui.label("Proxy:");
+                ui.vertical(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.config.proxy_url)
+                            .hint_text("http://proxy.example.com:8080 — empty uses HTTPS_PROXY")
+                            .desired_width(300.0),
+                    );
+                    ui.horizontal(|ui| {
+                        ui.label("User:");
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.config.proxy_username)
+                                .desired_width(120.0),
+                        );
+                        ui.label("Password:");
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.config.proxy_password)
+                                .password(true)
+                                .desired_width(120.0),
+                        );
+                    });
+                });
+                ui.end_row();
+
+                ui.label("User-Agent:");
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.config.user_agent)
+                            .hint_text(DEFAULT_USER_AGENT)
+                            .desired_width(300.0),
+                    );
+                    ui.colored_label(Color32::GRAY, "Empty sends the default shown.");
+                });
+                ui.end_row();
+
+                ui.label("Certificates:");
+                ui.vertical(|ui| {
+                    ui.horizontal(|ui| {
+                        let shown = self
+                            .config
+                            .ca_bundle
+                            .as_ref()
+                            .map(|p| p.display().to_string())
+                            .unwrap_or_else(|| "System roots only".to_string());
+                        ui.label(shown);
+                        if ui.button("CA Bundle…").clicked() {
+                            if let Some(path) = rfd::FileDialog::new()
+                                .add_filter("PEM", &["pem", "crt", "cer"])
+                                .pick_file()
+                            {
+                                self.ca_bundle_error = load_ca_bundle(&path).err();
+                                self.config.ca_bundle = Some(path);
+                            }
+                        }
+                        if self.config.ca_bundle.is_some() && ui.small_button("Clear").clicked() {
+                            self.config.ca_bundle = None;
+                            self.ca_bundle_error = None;
+                        }
+                    });
+                    if let Some(e) = &self.ca_bundle_error {
+                        ui.colored_label(Color32::from_rgb(200, 60, 60), e);
+                    }
+                    ui.checkbox(
+                        &mut self.config.accept_invalid_certs,
+                        "Accept invalid certificates",
+                    );
+                    if self.config.accept_invalid_certs {
+                        ui.colored_label(
+                            Color32::from_rgb(200, 60, 60),
+                            "⚠ Certificate checks are OFF: anyone on the network path can read your \
+                             API token and files. Use a CA bundle instead if at all possible.",
+                        );
+                    }
+                });
+                ui.end_row();
+
+                ui.label("Timeouts:");
+                ui.horizontal(|ui| {
+                    ui.label("Connect");
+                    ui.add(
+                        egui::DragValue::new(&mut self.config.connect_timeout_secs)
+                            .range(1..=300)
+                            .suffix(" s"),
+                    );
+                    ui.label("Request");
+                    ui.add(
+                        egui::DragValue::new(&mut self.config.request_timeout_secs)
+                            .range(5..=3600)
+                            .suffix(" s"),
+                    );
+                    ui.colored_label(Color32::GRAY, "Downloads only time out when stalled this long.");
+                });
                 ui.end_row();
 
-                ui.label("API Token:");
-                ui.add(egui::TextEdit::singleline(&mut self.config.api_token).password(true));
+                ui.label("Custom Headers:");
+                ui.vertical(|ui| {
+                    let mut remove: Option<usize> = None;
+                    for (idx, header) in self.config.custom_headers.iter_mut().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.add(
+                                egui::TextEdit::singleline(&mut header.name)
+                                    .hint_text("Header")
+                                    .desired_width(140.0),
+                            );
+                            ui.add(
+                                egui::TextEdit::singleline(&mut header.value)
+                                    .hint_text("Value")
+                                    .desired_width(220.0),
+                            );
+                            if ui.small_button("✖").clicked() {
+                                remove = Some(idx);
+                            }
+                            let blank = header.name.trim().is_empty() && header.value.trim().is_empty();
+                            if let Err(e) = parse_header(&header.name, &header.value) {
+                                if !blank {
+                                    ui.colored_label(Color32::RED, e);
+                                }
+                            } else if header.name.trim().eq_ignore_ascii_case("authorization") {
+                                ui.colored_label(
+                                    Color32::from_rgb(200, 120, 0),
+                                    "Replaces the API token authorization",
+                                );
+                            }
+                        });
+                    }
+                    if let Some(idx) = remove {
+                        self.config.custom_headers.remove(idx);
+                    }
+                    if ui.button("Add Header").clicked() {
+                        self.config.custom_headers.push(CustomHeader::default());
+                    }
+                });
                 ui.end_row();
 
                 ui.label("Download Directory:");
@@ -115,10 +668,334 @@ impl App {
                             self.config.download_dir = path;
                         }
                     }
+                    if let Some(warning) = self.download_dir_warning.lock().unwrap().as_ref() {
+                        ui.colored_label(Color32::from_rgb(200, 60, 60), format!("⚠ {warning}"));
+                    }
+                });
+                ui.end_row();
+
+                ui.label("Also Scan:");
+                ui.vertical(|ui| {
+                    let mut remove: Option<usize> = None;
+                    for (idx, dir) in self.config.additional_scan_dirs.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.label(dir.to_string_lossy().as_ref());
+                            if ui.small_button("✖").clicked() {
+                                remove = Some(idx);
+                            }
+                        });
+                    }
+                    if let Some(idx) = remove {
+                        self.config.additional_scan_dirs.remove(idx);
+                    }
+                    if ui.button("Add Folder...").clicked() {
+                        if let Some(path) = rfd::FileDialog::new().pick_folder() {
+                            if !self.config.additional_scan_dirs.contains(&path) {
+                                self.config.additional_scan_dirs.push(path);
+                            }
+                        }
+                    }
+                });
+                ui.end_row();
+
+                ui.label("Archive Directory:");
+                ui.vertical(|ui| {
+                    ui.horizontal(|ui| {
+                        match &self.config.archive_dir {
+                            Some(dir) => ui.label(dir.to_string_lossy().as_ref()),
+                            None => ui.colored_label(Color32::GRAY, "Not set — closed folders can only be deleted"),
+                        };
+                        if ui.button("Browse...").clicked() {
+                            if let Some(path) = rfd::FileDialog::new().pick_folder() {
+                                self.config.archive_dir = Some(path);
+                            }
+                        }
+                        if self.config.archive_dir.is_some() && ui.small_button("✖").clicked() {
+                            self.config.archive_dir = None;
+                        }
+                    });
+                    ui.add_enabled(
+                        self.config.archive_dir.is_some(),
+                        egui::Checkbox::new(
+                            &mut self.config.scan_archive,
+                            "Include archived folders in Incidents Manager scans",
+                        ),
+                    );
+                });
+                ui.end_row();
+
+                ui.label("Auto Cleanup:");
+                ui.vertical(|ui| {
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut self.config.retention_enabled, "Automatically");
+                        ui.add_enabled_ui(self.config.retention_enabled, |ui| {
+                            egui::ComboBox::from_id_salt("retention_action")
+                                .selected_text(self.config.retention_action.label().to_lowercase())
+                                .show_ui(ui, |ui| {
+                                    for action in [RetentionAction::Archive, RetentionAction::Delete] {
+                                        ui.selectable_value(
+                                            &mut self.config.retention_action,
+                                            action,
+                                            action.label().to_lowercase(),
+                                        );
+                                    }
+                                });
+                            ui.label("closed incidents not checked for");
+                            ui.add(egui::DragValue::new(&mut self.config.retention_days).range(1..=3650));
+                            ui.label("days");
+                        });
+                    });
+                    if self.config.retention_enabled
+                        && self.config.retention_action == RetentionAction::Archive
+                        && self.config.archive_dir.is_none()
+                    {
+                        ui.colored_label(Color32::from_rgb(200, 120, 0), "Set an archive directory above first.");
+                    }
+                    ui.horizontal(|ui| {
+                        ui.colored_label(
+                            Color32::GRAY,
+                            "Runs on startup and every 6 hours after Save; statuses are re-checked first.",
+                        );
+                        if ui.small_button("Open Log").clicked() {
+                            StorageManager::open_path(&retention::log_path());
+                        }
+                    });
+                });
+                ui.end_row();
+
+                ui.label("Status Refresh:");
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.config.status_poll_enabled, "Re-check incident statuses every");
+                    ui.add_enabled(
+                        self.config.status_poll_enabled,
+                        egui::DragValue::new(&mut self.config.status_poll_minutes).range(1..=1440),
+                    );
+                    ui.label("minutes");
+                });
+                ui.end_row();
+
+                ui.label("Folder Layout:");
+                ui.vertical(|ui| {
+                    egui::ComboBox::from_id_salt("folder_layout")
+                        .selected_text(self.config.folder_layout.label())
+                        .show_ui(ui, |ui| {
+                            for layout in FolderLayout::ALL {
+                                ui.selectable_value(&mut self.config.folder_layout, layout, layout.label());
+                            }
+                        });
+                    ui.add_enabled(
+                        self.config.folder_layout == FolderLayout::Date,
+                        egui::Checkbox::new(
+                            &mut self.config.collapse_single_date,
+                            "Only create date subfolders when attachments span multiple dates",
+                        ),
+                    );
+                    ui.colored_label(
+                        Color32::GRAY,
+                        "Applies to new downloads; files already saved are still recognized.",
+                    );
+                });
+                ui.end_row();
+
+                ui.label("File Names:");
+                ui.vertical(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.config.filename_template)
+                            .hint_text("{key}_{date}_{name}.{ext}")
+                            .desired_width(300.0),
+                    );
+                    match validate_filename_template(&self.config.filename_template) {
+                        Ok(()) => {
+                            ui.colored_label(
+                                Color32::GRAY,
+                                "Tokens: {key} {date} {name} {ext} {id}. Leave empty to keep JIRA's file names.",
+                            );
+                        }
+                        Err(e) => {
+                            ui.colored_label(
+                                Color32::from_rgb(200, 120, 0),
+                                format!("Invalid template ({e}); files keep JIRA's names."),
+                            );
+                        }
+                    }
+                });
+                ui.end_row();
+
+                ui.label("Issue Shortcut:");
+                ui.checkbox(
+                    &mut self.config.write_issue_shortcut,
+                    "Add a link to the JIRA issue in each issue folder",
+                );
+                ui.end_row();
+
+                ui.label("Duplicates:");
+                ui.checkbox(
+                    &mut self.config.skip_identical_downloads,
+                    "Don't keep a second copy when the downloaded file is identical to one on disk",
+                );
+                ui.end_row();
+
+                ui.label("File Times:");
+                ui.checkbox(
+                    &mut self.config.preserve_upload_time,
+                    "Date saved files by their JIRA upload time instead of the download time",
+                );
+                ui.end_row();
+
+                ui.label("Delay Between Files:");
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::DragValue::new(&mut self.config.inter_file_delay_ms)
+                            .range(0..=60_000)
+                            .suffix(" ms"),
+                    );
+                    ui.colored_label(
+                        Color32::GRAY,
+                        "Wait this long before starting each next download (0 = no delay).",
+                    );
+                });
+                ui.end_row();
+
+                ui.label("Bandwidth Limit:");
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::DragValue::new(&mut self.config.max_download_rate_kib)
+                            .range(0..=1_000_000)
+                            .suffix(" KiB/s"),
+                    );
+                    ui.colored_label(
+                        Color32::GRAY,
+                        "Combined speed of all downloads (0 = unlimited).",
+                    );
+                });
+                ui.end_row();
+
+                ui.label("Parallel Downloads:");
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::DragValue::new(&mut self.config.max_concurrent_downloads)
+                            .range(1..=16),
+                    );
+                    ui.colored_label(
+                        Color32::GRAY,
+                        "Files downloaded at once; the rest wait as Queued.",
+                    );
+                });
+                ui.end_row();
+
+                ui.label("My Cases Query:");
+                ui.vertical(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.config.my_issues_title)
+                            .hint_text("Panel title")
+                            .desired_width(200.0),
+                    );
+                    ui.add(
+                        egui::TextEdit::multiline(&mut self.config.my_issues_jql)
+                            .code_editor()
+                            .desired_rows(2)
+                            .desired_width(400.0),
+                    );
+                    if ui.small_button("Reset to default").clicked() {
+                        self.config.my_issues_jql = DEFAULT_MY_ISSUES_JQL.to_string();
+                    }
+                });
+                ui.end_row();
+
+                ui.label("My Cases:");
+                ui.vertical(|ui| {
+                    ui.checkbox(&mut self.config.auto_load_my_cases, "Load My Cases automatically");
+                    ui.checkbox(
+                        &mut self.config.my_cases_attachment_counts,
+                        "Show attachment counts (slower to load)",
+                    );
+                });
+                ui.end_row();
+
+                ui.label("New Attachments:");
+                ui.checkbox(&mut self.config.select_new_attachments, "Selected for download by default");
+                ui.end_row();
+
+                ui.label("Tab Switching:");
+                ui.checkbox(
+                    &mut self.config.cancel_fetch_on_tab_switch,
+                    "Cancel a pending issue fetch when leaving the Incident tab (restarts on return)",
+                );
+                ui.end_row();
+
+                ui.label("Taskbar:");
+                ui.checkbox(&mut self.config.taskbar_progress, "Show download progress on the taskbar button");
+                ui.end_row();
+
+                ui.label("Notifications:");
+                ui.checkbox(
+                    &mut self.config.notify_on_batch_complete,
+                    "Show a desktop notification when a batch of downloads finishes",
+                );
+                ui.end_row();
+
+                ui.label("Updates:");
+                ui.checkbox(
+                    &mut self.config.check_for_updates,
+                    "Check for a newer version on startup (at most once a day)",
+                );
+                ui.end_row();
+
+                ui.label("Debug Logging:");
+                ui.checkbox(
+                    &mut self.config.debug_logging,
+                    "Log every HTTP request and record per-download timing metrics (download_metrics.jsonl)",
+                );
+                ui.end_row();
+
+                ui.label("Log File:");
+                ui.horizontal(|ui| {
+                    ui.label(crate::logging::log_path().to_string_lossy().as_ref());
+                    if ui.button("Open Log").clicked() {
+                        log::logger().flush();
+                        StorageManager::open_path(&crate::logging::log_path());
+                    }
                 });
                 ui.end_row();
+
+                ui.label("Start Tab:");
+                egui::ComboBox::from_id_salt("start_tab")
+                    .selected_text(self.config.start_tab.label())
+                    .show_ui(ui, |ui| {
+                        for choice in StartTab::ALL {
+                            ui.selectable_value(&mut self.config.start_tab, choice, choice.label());
+                        }
+                    });
+                ui.end_row();
+
+                ui.label("Startup:");
+                ui.checkbox(
+                    &mut self.config.reopen_last_issue,
+                    "Reopen the last fetched issue (otherwise its key is only pre-filled)",
+                );
+                ui.end_row();
+
+                ui.label("Closed Statuses:");
+                let resp = ui.add(
+                    egui::TextEdit::singleline(&mut self.closed_statuses_input)
+                        .hint_text("e.g. Done, Cancelled, Won't Fix — empty uses the default")
+                        .desired_width(300.0),
+                );
+                if resp.changed() {
+                    self.config.closed_statuses = self
+                        .closed_statuses_input
+                        .split(',')
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                        .collect();
+                }
+                ui.end_row();
             });
 
+        if profile_changed {
+            self.on_profile_changed();
+        }
+
         ui.add_space(16.0);
         ui.separator();
         ui.add_space(8.0);
@@ -163,15 +1040,52 @@ impl App {
         ui.add_space(12.0);
 
         // Buttons — capture clicks as booleans, apply actions after closures
-        let (save_clicked, test_clicked) = ui
-            .horizontal(|ui| (ui.button("Save").clicked(), ui.button("Test Connection").clicked()))
+        let (save_clicked, test_clicked, export_clicked, import_clicked) = ui
+            .horizontal(|ui| {
+                (
+                    ui.button("Save").clicked(),
+                    ui.button("Test Connection").clicked(),
+                    ui.button("Export Config").clicked(),
+                    ui.button("Import Config").clicked(),
+                )
+            })
             .inner;
 
+        if export_clicked {
+            if let Some(path) = rfd::FileDialog::new()
+                .set_file_name("jira-downloader-config.json")
+                .add_filter("JSON", &["json"])
+                .save_file()
+            {
+                self.config_saved_msg = Some(match self.config.export_to(&path) {
+                    Ok(()) => format!("Exported to {} (without the API token).", path.display()),
+                    Err(e) => format!("Error: {e}"),
+                });
+            }
+        }
+
+        if import_clicked {
+            if let Some(path) = rfd::FileDialog::new().add_filter("JSON", &["json"]).pick_file() {
+                self.config_saved_msg = Some(match self.config.import_from(&path) {
+                    Ok(()) => "Imported. Re-enter your API token, then click Save.".to_string(),
+                    Err(e) => format!("Error: {e}"),
+                });
+            }
+        }
+
         if save_clicked {
+            self.config.api_token = self.config.api_token.trim().to_string();
             match self.config.save() {
-                Ok(_) => self.config_saved_msg = Some("Configuration saved.".to_string()),
+                Ok(_) => {
+                    self.token_saved = self.config.has_saved_token();
+                    self.config_saved_msg = Some("Configuration saved.".to_string());
+                }
                 Err(e) => self.config_saved_msg = Some(format!("Error: {e}")),
             }
+            self.restart_retention(ui.ctx());
+            self.restart_status_poll(ui.ctx());
+            self.check_download_dir(ui.ctx());
+            crate::logging::set_debug(self.config.debug_logging);
         }
 
         if test_clicked {
@@ -201,20 +1115,238 @@ impl App {
             }
             None => {}
         }
+
+        if let Some(version) = crate::jira::detected_api_version(&self.config) {
+            ui.horizontal(|ui| {
+                ui.label(RichText::new(format!("Using REST API v{version} for this server.")).weak());
+                if ui
+                    .small_button("Re-detect")
+                    .on_hover_text("Forget the detected version; the next request tries v3, then v2")
+                    .clicked()
+                {
+                    crate::jira::reset_detected_api_version(&self.config);
+                }
+            });
+        }
     }
 
-    fn load_my_issues(&mut self, ctx: &egui::Context) {
-        if self.my_issues_loading { return; }
-        self.my_issues_loading = true;
-        *self.my_issues_status.lock().unwrap() = None;
+    /// Stars or unstars an issue and saves the favorites list right away.
+    fn toggle_favorite(&mut self, key: &str, summary: &str) {
+        self.config.toggle_favorite(key, summary);
+        let _ = self.config.save_ui_state();
+    }
 
-        let config = self.config.clone();
-        let status = Arc::clone(&self.my_issues_status);
-        let ctx = ctx.clone();
+    /// Watches the selected rows, i.e. the ones `start_all_downloads` was given.
+    fn watch_batch<'a>(&mut self, label: String, items: impl IntoIterator<Item = &'a DownloadItem>) {
+        let states: Vec<_> = items
+            .into_iter()
+            .filter(|i| i.selected)
+            .map(|i| Arc::clone(&i.state))
+            .collect();
+        if !states.is_empty() {
+            self.batch_watches.push(BatchWatch { label, states });
+        }
+    }
 
-        self.runtime.spawn(async move {
-            let client = JiraClient::new(config);
-            let result = client.fetch_my_issues().await;
+    /// Notifies once every file of a watched batch has finished, whichever
+    /// tab is open.
+    fn check_batch_complete(&mut self) {
+        let notify = self.config.notify_on_batch_complete;
+        self.batch_watches.retain(|watch| {
+            let batch: Vec<FileState> = watch.states.iter().map(|s| s.lock().unwrap().clone()).collect();
+            if batch
+                .iter()
+                .any(|s| matches!(s, FileState::Pending | FileState::Queued | FileState::Downloading { .. }))
+            {
+                return true;
+            }
+            if notify {
+                let count = |f: fn(&FileState) -> bool| batch.iter().filter(|s| f(s)).count();
+                let done = count(|s| matches!(s, FileState::Done | FileState::ThumbnailDone));
+                let failed = count(|s| matches!(s, FileState::Error(_)));
+                let skipped = count(|s| matches!(s, FileState::AlreadyOnDisk));
+                let cancelled = count(|s| matches!(s, FileState::Cancelled));
+                let mut body = format!("{}: {done} downloaded, {failed} failed", watch.label);
+                if skipped > 0 {
+                    body.push_str(&format!(", {skipped} already on disk"));
+                }
+                if cancelled > 0 {
+                    body.push_str(&format!(", {cancelled} cancelled"));
+                }
+                crate::notify::show("Downloads finished", &body);
+            }
+            false
+        });
+    }
+
+    /// Fetches the first issue found in dropped links or folders and keeps
+    /// any others as one-click chips under the input.
+    fn handle_drop(&mut self, files: &[egui::DroppedFile], ctx: &egui::Context) {
+        let mut keys: Vec<String> = Vec::new();
+        for key in files.iter().filter_map(dropped_issue_key) {
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+        self.tab = Tab::Incident;
+        if keys.is_empty() {
+            *self.fetch_status.lock().unwrap() = Some((
+                self.fetch_generation,
+                Err("Nothing dropped looked like a JIRA issue link or issue folder".to_string().into()),
+            ));
+            return;
+        }
+
+        let first = keys.remove(0);
+        self.dropped_keys.retain(|k| *k != first && !keys.contains(k));
+        self.dropped_keys.extend(keys);
+        self.incident_input = first;
+        self.do_fetch(ctx);
+    }
+
+    /// Remembers the window's normal size and position; while maximized or
+    /// minimized only the maximized flag changes.
+    fn track_window_geometry(&mut self, ctx: &egui::Context) {
+        let (outer, inner, maximized, minimized, scale) = ctx.input(|i| {
+            let vp = i.viewport();
+            (
+                vp.outer_rect,
+                vp.inner_rect,
+                vp.maximized.unwrap_or(false),
+                vp.minimized.unwrap_or(false),
+                vp.native_pixels_per_point.unwrap_or(1.0),
+            )
+        });
+        if minimized {
+            return;
+        }
+        if maximized {
+            if let Some(geometry) = &mut self.window_geometry {
+                geometry.maximized = true;
+            }
+            return;
+        }
+        if let (Some(outer), Some(inner)) = (outer, inner) {
+            self.window_geometry = Some(WindowGeometry {
+                x: outer.min.x,
+                y: outer.min.y,
+                width: inner.width(),
+                height: inner.height(),
+                maximized: false,
+                scale,
+            });
+        }
+    }
+
+    /// Asks where to save `<key>.zip`, then zips the issue folder on the
+    /// blocking pool.
+    fn zip_issue_folder(&mut self, root: std::path::PathBuf, issue_key: &str, ctx: &egui::Context) {
+        let src = StorageManager::new(root.clone()).issue_dir(issue_key);
+        if !src.exists() {
+            *self.zip_status.lock().unwrap() = Some(Err(format!("No folder for {issue_key} yet.")));
+            return;
+        }
+        let Some(dest) = rfd::FileDialog::new()
+            .set_directory(&root)
+            .set_file_name(format!("{issue_key}.zip"))
+            .add_filter("Zip", &["zip"])
+            .save_file()
+        else {
+            return;
+        };
+
+        self.zip_pending = true;
+        *self.zip_status.lock().unwrap() = None;
+        let status = Arc::clone(&self.zip_status);
+        let key = issue_key.to_string();
+        let ctx = ctx.clone();
+        self.runtime.spawn_blocking(move || {
+            let result = zip_folder(&src, &key, &dest)
+                .map(|n| format!("Zipped {n} file(s) from {key} to {}.", dest.display()));
+            *status.lock().unwrap() = Some(result);
+            ctx.request_repaint();
+        });
+    }
+
+    fn render_zip_status(&mut self, ui: &mut egui::Ui) {
+        let status = self.zip_status.lock().unwrap().clone();
+        match status {
+            None if self.zip_pending => {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("Creating zip…");
+                });
+            }
+            None => {}
+            Some(result) => {
+                self.zip_pending = false;
+                match result {
+                    Ok(msg) => ui.colored_label(Color32::GREEN, msg),
+                    Err(e) => ui.colored_label(Color32::RED, format!("Zip failed: {e}")),
+                };
+            }
+        }
+    }
+
+    /// Checks off the UI thread that the download directory exists and takes
+    /// files; a disconnected network drive can take a while to answer.
+    fn check_download_dir(&self, ctx: &egui::Context) {
+        let storage = StorageManager::new(self.config.download_dir.clone());
+        let warning = Arc::clone(&self.download_dir_warning);
+        let ctx = ctx.clone();
+        self.runtime.spawn_blocking(move || {
+            let result = storage.check_writable().err();
+            if let Some(e) = &result {
+                log::warn!("Download directory unusable: {e}");
+            }
+            *warning.lock().unwrap() = result;
+            ctx.request_repaint();
+        });
+    }
+
+    /// (Re)starts the auto-cleanup loop with the current settings, or stops it
+    /// when disabled.
+    fn restart_retention(&mut self, ctx: &egui::Context) {
+        if let Some(task) = self.retention.take() {
+            task.stop();
+        }
+        if self.config.retention_enabled {
+            self.retention = Some(RetentionTask::start(
+                &self.runtime,
+                self.config.clone(),
+                Arc::clone(&self.background_connection),
+                ctx.clone(),
+            ));
+        }
+    }
+
+    fn restart_status_poll(&mut self, ctx: &egui::Context) {
+        if let Some(poller) = self.status_poller.take() {
+            poller.stop();
+        }
+        if self.config.status_poll_enabled {
+            self.status_poller = Some(StatusPoller::start(
+                &self.runtime,
+                self.config.clone(),
+                Arc::clone(&self.check_status),
+                Arc::clone(&self.background_connection),
+                ctx.clone(),
+            ));
+        }
+    }
+
+    fn load_my_issues(&mut self, ctx: &egui::Context) {
+        if self.my_issues_loading { return; }
+        self.my_issues_loading = true;
+        *self.my_issues_status.lock().unwrap() = None;
+
+        let config = self.config.clone();
+        let status = Arc::clone(&self.my_issues_status);
+        let ctx = ctx.clone();
+
+        self.runtime.spawn(async move {
+            let client = JiraClient::new(config);
+            let result = client.fetch_my_issues().await;
             *status.lock().unwrap() = Some(result);
             ctx.request_repaint();
         });
@@ -223,12 +1355,12 @@ impl App {
     // ─── Incident ──────────────────────────────────────────────────────────────
 
     fn render_incident(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
-        // Auto-load my issues on first render if credentials are present and no error yet
-        if !self.my_issues_loading
-            && self.my_issues.is_empty()
+        // Auto-load my issues on first render if enabled, credentials are present and no error yet
+        if self.config.auto_load_my_cases
+            && !self.my_issues_loading
+            && !self.my_issues_loaded
             && self.my_issues_error.is_none()
-            && !self.config.jira_url.is_empty()
-            && !self.config.email.is_empty()
+            && self.config.has_credentials()
         {
             self.load_my_issues(ctx);
         }
@@ -239,17 +1371,53 @@ impl App {
             Some(Ok(issues)) => {
                 self.my_issues = issues;
                 self.my_issues_loading = false;
+                self.my_issues_loaded = true;
                 self.my_issues_error = None;
             }
             Some(Err(e)) => {
                 self.my_issues_loading = false;
+                self.my_issues_loaded = true;
                 self.my_issues_error = Some(e);
             }
             None => {}
         }
 
+        if let Some(warning) = self.download_dir_warning.lock().unwrap().clone() {
+            ui.colored_label(
+                Color32::from_rgb(200, 60, 60),
+                format!("⚠ Downloads can't be saved: {warning}\nCheck the Download Directory in Settings."),
+            );
+            ui.add_space(4.0);
+        }
+
+        // ── Favorites ────────────────────────────────────────────────────────
+        let mut favorite_picked: Option<String> = None;
+        let mut unstar: Option<String> = None;
+        if !self.config.favorites.is_empty() {
+            ui.horizontal_wrapped(|ui| {
+                ui.label(RichText::new("★").color(Color32::GOLD));
+                for fav in &self.config.favorites {
+                    let resp = ui.button(&fav.key).on_hover_text(&fav.summary);
+                    if resp.clicked() {
+                        favorite_picked = Some(fav.key.clone());
+                    }
+                    resp.context_menu(|ui| {
+                        if ui.button("Unstar").clicked() {
+                            unstar = Some(fav.key.clone());
+                            ui.close_menu();
+                        }
+                    });
+                }
+            });
+            ui.add_space(4.0);
+        }
+        if let Some(key) = unstar {
+            self.toggle_favorite(&key, "");
+        }
+
         // ── Incident input row (top) ──────────────────────────────────────────
-        let fetch_triggered = ui
+        let mut related_toggled = false;
+        let (mut fetch_triggered, recent_picked) = ui
             .horizontal(|ui| {
                 ui.label(RichText::new("Incident:").strong());
                 let resp = ui.add(
@@ -257,22 +1425,99 @@ impl App {
                         .hint_text("PROJ-123 or full JIRA URL")
                         .desired_width(300.0),
                 );
+                if std::mem::take(&mut self.focus_incident_input) {
+                    resp.request_focus();
+                }
                 let enter = resp.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
-                ui.button("Fetch").clicked() || enter
+                let fetch = ui.button("Fetch").clicked() || enter;
+                let sub = ui
+                    .checkbox(&mut self.include_subtasks, "+ Subtasks")
+                    .on_hover_text("Also list the attachments of this issue's subtasks")
+                    .changed();
+                let linked = ui
+                    .checkbox(&mut self.include_linked, "+ Linked issues")
+                    .on_hover_text("Also list the attachments of issues linked to this one")
+                    .changed();
+                related_toggled = sub || linked;
+
+                let mut recent = None;
+                if !self.config.recent_issues.is_empty() {
+                    egui::ComboBox::from_id_salt("recent_issues")
+                        .selected_text("Recent")
+                        .show_ui(ui, |ui| {
+                            for key in &self.config.recent_issues {
+                                if ui.selectable_label(false, key).clicked() {
+                                    recent = Some(key.clone());
+                                }
+                            }
+                        });
+                }
+                (fetch, recent)
             })
             .inner;
 
+        if let Some(key) = recent_picked.or(favorite_picked) {
+            self.incident_input = key;
+            fetch_triggered = true;
+        }
         if fetch_triggered {
             self.do_fetch(ctx);
+        } else if related_toggled {
+            if let Some(key) = self.current_issue.as_ref().map(|i| i.key.clone()) {
+                self.fetch_related(&key, ctx);
+            }
+        }
+
+        if !self.dropped_keys.is_empty() {
+            let (picked, clear) = ui
+                .horizontal_wrapped(|ui| {
+                    ui.label("Dropped:");
+                    let mut picked = None;
+                    for key in &self.dropped_keys {
+                        if ui.small_button(key).clicked() {
+                            picked = Some(key.clone());
+                        }
+                    }
+                    (picked, ui.small_button("✖").on_hover_text("Clear").clicked())
+                })
+                .inner;
+            if let Some(key) = picked {
+                self.dropped_keys.retain(|k| *k != key);
+                self.incident_input = key;
+                self.do_fetch(ctx);
+            }
+            if clear {
+                self.dropped_keys.clear();
+            }
         }
 
+        egui::CollapsingHeader::new("Fetch several issues")
+            .default_open(false)
+            .show(ui, |ui| {
+                ui.add(
+                    egui::TextEdit::multiline(&mut self.multi_input)
+                        .hint_text("Paste keys or URLs, one per line or comma-separated")
+                        .desired_rows(3)
+                        .desired_width(400.0),
+                );
+                let fetch = ui
+                    .add_enabled(self.multi_pending == 0, egui::Button::new("Fetch All"))
+                    .clicked();
+                if fetch {
+                    self.fetch_multiple(ctx);
+                }
+                self.render_multi_fetch(ui, ctx);
+            });
+
         ui.add_space(4.0);
 
         // ── My Cases panel ────────────────────────────────────────────────────
         let mut selected_key: Option<String> = None;
+        let mut star_toggled: Option<(String, String)> = None;
 
         egui::CollapsingHeader::new(
-            RichText::new(format!("My Open Cases ({})", self.my_issues.len())).strong(),
+            RichText::new(format!("{} ({})", self.config.my_issues_title, self.my_issues.len()))
+                .strong(),
         )
         .default_open(true)
         .show(ui, |ui| {
@@ -285,14 +1530,36 @@ impl App {
                     self.my_issues_loading = false;
                     self.load_my_issues(ctx);
                 }
+            } else if !self.my_issues_loaded {
+                ui.horizontal(|ui| {
+                    ui.colored_label(Color32::GRAY, "My Cases not loaded.");
+                    if ui.small_button("Load").clicked() {
+                        self.load_my_issues(ctx);
+                    }
+                });
             } else if self.my_issues.is_empty() {
-                ui.colored_label(Color32::GRAY, "No open cases assigned to you.");
+                ui.colored_label(Color32::GRAY, "No cases match the My Cases query.");
             } else {
-                let refresh = ui.small_button("↻ Refresh").clicked();
+                let (refresh, download_all) = ui
+                    .horizontal(|ui| {
+                        (
+                            ui.small_button("↻ Refresh").clicked(),
+                            ui.add_enabled(
+                                self.bulk_pending == 0,
+                                egui::Button::new("⬇ Download All My Cases").small(),
+                            )
+                            .clicked(),
+                        )
+                    })
+                    .inner;
                 if refresh {
                     self.my_issues_loading = false;
                     self.load_my_issues(ctx);
                 }
+                if download_all {
+                    self.download_all_my_cases(ctx);
+                }
+                self.render_bulk_progress(ui);
 
                 ui.add_space(4.0);
                 egui::ScrollArea::vertical()
@@ -300,7 +1567,7 @@ impl App {
                     .max_height(160.0)
                     .show(ui, |ui| {
                         egui::Grid::new("my_cases_grid")
-                            .num_columns(3)
+                            .num_columns(5)
                             .spacing([12.0, 4.0])
                             .striped(true)
                             .show(ui, |ui| {
@@ -317,19 +1584,28 @@ impl App {
                                         RichText::new(&issue.key).strong()
                                     };
 
+                                    if star_button(ui, self.config.is_favorite(&issue.key)).clicked() {
+                                        star_toggled = Some((issue.key.clone(), issue.summary.clone()));
+                                    }
                                     if ui.button(key_text).clicked() {
                                         selected_key = Some(issue.key.clone());
                                     }
 
-                                    let summary = if issue.summary.len() > 50 {
-                                        format!("{}...", &issue.summary[..50])
-                                    } else {
-                                        issue.summary.clone()
+                                    // Cases known to have nothing to download are dimmed.
+                                    let summary = RichText::new(truncate_chars(&issue.summary, 50));
+                                    match issue.attachment_count {
+                                        Some(0) => ui.label(summary.weak()),
+                                        _ => ui.label(summary),
                                     };
-                                    ui.label(summary);
 
                                     let sc = status_color(&issue.status);
                                     ui.colored_label(sc, &issue.status);
+
+                                    match issue.attachment_count {
+                                        Some(0) => ui.label(RichText::new("📎 0").weak()),
+                                        Some(n) => ui.label(format!("📎 {n}")),
+                                        None => ui.label(""),
+                                    };
                                     ui.end_row();
                                 }
                             });
@@ -337,6 +1613,9 @@ impl App {
             }
         });
 
+        if let Some((key, summary)) = star_toggled {
+            self.toggle_favorite(&key, &summary);
+        }
         if let Some(key) = selected_key {
             self.incident_input = key;
             self.do_fetch(ctx);
@@ -346,142 +1625,513 @@ impl App {
         ui.add_space(4.0);
 
         // Process fetch result — update self before any rendering borrows
-        let fetch_result = self.fetch_status.lock().unwrap().clone();
+        let pending = self.fetch_status.lock().unwrap().clone();
+        let fetch_result = match pending {
+            Some((generation, result)) if generation == self.fetch_generation => Some(result),
+            Some(_) => {
+                *self.fetch_status.lock().unwrap() = None;
+                None
+            }
+            None => None,
+        };
         match fetch_result {
             Some(Ok(issue)) => {
-                let storage = StorageManager::new(self.config.download_dir.clone());
-                let ctrl = ControlFile::new(&issue.key, &issue.summary, &issue.status);
-                let _ = storage.save_control_file(&ctrl);
-                self.download_items = issue
-                    .attachments
-                    .iter()
-                    .map(|a| {
-                        let on_disk = storage.attachment_exists(&issue.key, a);
-                        let mut item = DownloadItem::new(a.clone());
-                        if on_disk {
-                            item.selected = false;
-                            *item.state.lock().unwrap() = FileState::AlreadyOnDisk;
-                        }
-                        item
-                    })
-                    .collect();
+                if !issue.key.eq_ignore_ascii_case(&self.fetch_requested_key) {
+                    self.moved_issue = Some((self.fetch_requested_key.clone(), issue.key.clone()));
+                }
+                self.download_items = self.prepare_issue(&issue, &self.incident_config(), ctx);
+                self.open_notice = None;
+                self.manifest_status = None;
+                sort_items(&mut self.download_items, self.attachment_sort);
                 self.current_issue = Some(issue);
                 *self.fetch_status.lock().unwrap() = None;
+                self.remember_session();
             }
             Some(Err(e)) => {
                 ui.colored_label(Color32::RED, format!("Error: {e}"));
+                if matches!(e, FetchIssueError::Unparseable(_)) && ui.button("Retry with API v2").clicked() {
+                    self.do_fetch_with(ctx, true);
+                }
             }
             None => {}
         }
 
         // Extract display data as owned values so no borrow on self.current_issue remains
-        let issue_data: Option<(String, String, String)> = self
+        let issue_data: Option<(String, String, String, &'static str)> = self
             .current_issue
             .as_ref()
-            .map(|i| (i.key.clone(), i.summary.clone(), i.status.clone()));
+            .map(|i| (i.key.clone(), i.summary.clone(), i.status.clone(), i.api_version));
 
-        if let Some((issue_key, summary, status)) = issue_data {
-            let open_folder = ui
+        if let Some((issue_key, summary, status, api_version)) = issue_data {
+            let (open_folder, zip_clicked, manifest_clicked, star_clicked) = ui
                 .horizontal(|ui| {
+                    let star = star_button(ui, self.config.is_favorite(&issue_key)).clicked();
                     ui.label(RichText::new(&issue_key).strong());
                     ui.label("—");
                     ui.label(&summary);
                     ui.label("|");
                     ui.label(RichText::new(&status).italics());
-                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        ui.button("📁 Open Folder").clicked()
-                    })
-                    .inner
+                    ui.label(RichText::new(format!("API v{api_version}")).small().color(Color32::GRAY));
+                    let (open, zip, manifest) = ui
+                        .with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            let open = ui.button("📁 Open Folder").clicked();
+                            let zip = ui
+                                .add_enabled(!self.zip_pending, egui::Button::new("🗜 Export as Zip"))
+                                .clicked();
+                            let manifest = ui
+                                .button("📋 Export Manifest")
+                                .on_hover_text("Save the attachment list as JSON in the issue folder")
+                                .clicked();
+                            (open, zip, manifest)
+                        })
+                        .inner;
+                    (open, zip, manifest, star)
                 })
                 .inner;
 
+            if star_clicked {
+                self.toggle_favorite(&issue_key, &summary);
+            }
+
             if open_folder {
-                let storage = StorageManager::new(self.config.download_dir.clone());
-                let path = storage.latest_date_folder(&issue_key);
+                let storage = StorageManager::new(self.incident_download_dir());
+                let layout = storage
+                    .load_control_file(&issue_key)
+                    .and_then(|ctrl| ctrl.layout)
+                    .unwrap_or(self.config.folder_layout);
+                let path = storage.latest_date_folder(&issue_key, layout);
                 StorageManager::open_path(&path);
             }
+            if zip_clicked {
+                self.zip_issue_folder(self.incident_download_dir(), &issue_key, ctx);
+            }
+            self.render_zip_status(ui);
+            if manifest_clicked {
+                if let Some(issue) = &self.current_issue {
+                    let dir = StorageManager::new(self.incident_download_dir()).issue_dir(&issue.key);
+                    self.manifest_status = Some(
+                        export_manifest(issue, &dir)
+                            .map(|path| format!("Manifest saved to {}", normal_path(&path).display())),
+                    );
+                }
+            }
+            match &self.manifest_status {
+                Some(Ok(msg)) => {
+                    ui.colored_label(Color32::GREEN, msg);
+                }
+                Some(Err(e)) => {
+                    ui.colored_label(Color32::RED, format!("Manifest export failed: {e}"));
+                }
+                None => {}
+            }
+
+            self.render_save_to(ui);
+
+            if let Some((old_key, new_key)) = self.moved_issue.clone() {
+                let storage = StorageManager::new(self.incident_download_dir())
+                    .with_filename_template(&self.config.filename_template);
+                let old_exists = storage.issue_dir(&old_key).exists();
+                let relocate = ui
+                    .horizontal(|ui| {
+                        ui.colored_label(
+                            Color32::from_rgb(200, 120, 0),
+                            format!("{old_key} is now {new_key} — files are saved under {new_key}."),
+                        );
+                        old_exists
+                            && ui
+                                .button(format!("Move {old_key} folder to {new_key}"))
+                                .clicked()
+                    })
+                    .inner;
+                if relocate {
+                    match storage.relocate_issue(&old_key, &new_key) {
+                        Ok(()) => {
+                            let saved_files = storage
+                                .load_control_file(&new_key)
+                                .map(|ctrl| ctrl.saved_files)
+                                .unwrap_or_default();
+                            for item in &self.download_items {
+                                let existing =
+                                    storage.existing_attachment(&new_key, &item.attachment, &saved_files);
+                                let mut state = item.state.lock().unwrap();
+                                if existing.is_some() && matches!(*state, FileState::Pending) {
+                                    *state = FileState::AlreadyOnDisk;
+                                    *item.saved_path.lock().unwrap() = existing;
+                                }
+                            }
+                            self.moved_issue = None;
+                            self.relocate_error = None;
+                            self.space_warning = None;
+                        }
+                        Err(e) => {
+                            self.relocate_error = Some(format!("Could not move {old_key}: {e}"));
+                        }
+                    }
+                }
+                if let Some(e) = &self.relocate_error {
+                    ui.colored_label(Color32::RED, e);
+                }
+            }
 
             ui.separator();
 
             let count = self.download_items.len();
             ui.label(format!("Attachments ({count}):"));
+            if count > 0 {
+                ui.colored_label(Color32::GRAY, kind_summary(&self.download_items));
+            }
+            ui.horizontal(|ui| {
+                ui.label("Filter:");
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.attachment_filter)
+                        .hint_text("e.g. heapdump or .log")
+                        .desired_width(200.0),
+                );
+                if !self.attachment_filter.is_empty() {
+                    let shown = self
+                        .download_items
+                        .iter()
+                        .filter(|i| matches_filter(i, &self.attachment_filter))
+                        .count();
+                    ui.colored_label(Color32::GRAY, format!("{shown} of {count} shown"));
+                    if ui.small_button("✖").clicked() {
+                        self.attachment_filter.clear();
+                    }
+                }
+                ui.add_space(8.0);
+                ui.checkbox(&mut self.group_by_date, "Group by upload date");
+            });
             ui.add_space(4.0);
 
+            let mut selection_changed = false;
+            let mut auto_select_changed = false;
+            egui::CollapsingHeader::new("Auto-select by type and size")
+                .id_salt("auto_select_filter")
+                .show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        for kind in AttachmentKind::ALL {
+                            let mut included = !self.excluded_kinds.contains(&kind);
+                            if ui.checkbox(&mut included, kind.label(2)).changed() {
+                                if included {
+                                    self.excluded_kinds.remove(&kind);
+                                } else {
+                                    self.excluded_kinds.insert(kind);
+                                }
+                                auto_select_changed = true;
+                            }
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Size from");
+                        auto_select_changed |= ui
+                            .add(
+                                egui::DragValue::new(&mut self.size_filter_mb.0)
+                                    .range(0.0..=100_000.0)
+                                    .speed(1.0)
+                                    .suffix(" MB"),
+                            )
+                            .changed();
+                        ui.label("to");
+                        auto_select_changed |= ui
+                            .add(
+                                egui::DragValue::new(&mut self.size_filter_mb.1)
+                                    .range(0.0..=100_000.0)
+                                    .speed(1.0)
+                                    .suffix(" MB"),
+                            )
+                            .changed();
+                        ui.colored_label(Color32::GRAY, "(0 = no limit)");
+                    });
+                });
+            if auto_select_changed {
+                // Files outside the filter are deselected but stay listed and toggleable.
+                for item in &mut self.download_items {
+                    if !matches!(item.current_state(), FileState::AlreadyOnDisk) {
+                        item.selected =
+                            passes_auto_select(item, &self.excluded_kinds, self.size_filter_mb);
+                    }
+                }
+                selection_changed = true;
+            }
+
+            // Overall progress of the current batch: every row that has been
+            // started, whether still queued, running or finished.
+            let (started, finished, failed, downloaded, total) = batch_totals(
+                self.download_items
+                    .iter()
+                    .filter(|i| !matches!(i.current_state(), FileState::Pending | FileState::AlreadyOnDisk)),
+            );
+            if started > 0 {
+                let frac = if total > 0 { downloaded as f32 / total as f32 } else { 0.0 };
+                ui.add(
+                    egui::ProgressBar::new(frac).text(format!(
+                        "{finished} of {started} files, {} of {}{}",
+                        format_size(downloaded),
+                        format_size(total),
+                        failed_suffix(failed)
+                    )),
+                );
+                ui.add_space(4.0);
+            }
+
+            let mut sort_clicked = None;
+            let mut clicks = RowClicks::default();
+            let filter = self.attachment_filter.clone();
+            let sort = self.attachment_sort;
+
             egui::ScrollArea::vertical()
                 .max_height(300.0)
                 .show(ui, |ui| {
-                    egui::Grid::new("attachments_grid")
-                        .num_columns(6)
-                        .spacing([8.0, 4.0])
-                        .striped(true)
-                        .show(ui, |ui| {
-                            for item in &mut self.download_items {
-                                let state = item.current_state();
-                                ui.checkbox(&mut item.selected, "");
-                                ui.label(&item.attachment.filename);
-                                ui.label(format_size(item.attachment.size));
-                                ui.label(
-                                    item.attachment.created.format("%Y-%m-%d").to_string(),
-                                );
-                                let frac = state.progress_fraction().unwrap_or(0.0);
-                                ui.add(
-                                    egui::ProgressBar::new(frac)
-                                        .desired_width(120.0)
-                                        .show_percentage(),
-                                );
-                                let label = state.label();
-                                match &state {
-                                    FileState::Done | FileState::AlreadyOnDisk => {
-                                        ui.colored_label(Color32::from_rgb(60, 180, 60), &label);
-                                    }
-                                    FileState::Error(_) => {
-                                        ui.colored_label(Color32::from_rgb(200, 60, 60), &label);
+                    if !self.group_by_date {
+                        egui::Grid::new("attachments_grid")
+                            .num_columns(7)
+                            .spacing([8.0, 4.0])
+                            .striped(true)
+                            .show(ui, |ui| {
+                                sort_clicked = sort_clicked.or(attachment_grid_header(ui, sort));
+                                for item in &mut self.download_items {
+                                    if matches_filter(item, &filter) {
+                                        attachment_row(ui, item, &mut self.previews, &self.config, &mut clicks);
                                     }
-                                    _ => {
-                                        ui.label(&label);
+                                }
+                            });
+                        return;
+                    }
+
+                    // One section per upload day, named like the date folders on disk.
+                    let day = |item: &DownloadItem| layout_subfolder(FolderLayout::Date, &item.attachment);
+                    let mut days: Vec<String> = self
+                        .download_items
+                        .iter()
+                        .filter(|i| matches_filter(i, &filter))
+                        .filter_map(day)
+                        .collect();
+                    days.sort();
+                    days.dedup();
+                    if matches!(sort, Some((AttachmentSort::Date, false))) {
+                        days.reverse();
+                    }
+                    for date in days {
+                        let in_day = |item: &DownloadItem| {
+                            matches_filter(item, &filter) && day(item).as_ref() == Some(&date)
+                        };
+                        let (files, bytes, all_selected) = self
+                            .download_items
+                            .iter()
+                            .filter(|i| in_day(i))
+                            .fold((0, 0, true), |(n, b, all), i| {
+                                (n + 1, b + i.attachment.size, all && i.selected)
+                            });
+                        egui::CollapsingHeader::new(format!("{date} — {files} file(s), {}", format_size(bytes)))
+                            .id_salt(("attachments_day", &date))
+                            .default_open(true)
+                            .show(ui, |ui| {
+                                let mut all = all_selected;
+                                if ui.checkbox(&mut all, "Select all from this day").changed() {
+                                    for item in self.download_items.iter_mut().filter(|i| in_day(i)) {
+                                        item.selected = all;
                                     }
-                                };
-                                ui.end_row();
-                            }
-                        });
+                                    clicks.selection_changed = true;
+                                }
+                                egui::Grid::new(("attachments_grid", &date))
+                                    .num_columns(7)
+                                    .spacing([8.0, 4.0])
+                                    .striped(true)
+                                    .show(ui, |ui| {
+                                        sort_clicked = sort_clicked.or(attachment_grid_header(ui, sort));
+                                        for item in &mut self.download_items {
+                                            if in_day(item) {
+                                                attachment_row(ui, item, &mut self.previews, &self.config, &mut clicks);
+                                            }
+                                        }
+                                    });
+                            });
+                    }
                 });
+            sort_clicked = sort_clicked.or(self.render_related_issues(ui, ctx, &filter, sort, &mut clicks));
+            selection_changed |= clicks.selection_changed;
+            let open_clicked = clicks.open;
+            let redownload_clicked = clicks.redownload;
+
+            if let Some(path) = open_clicked {
+                if path.exists() {
+                    self.open_notice = None;
+                    StorageManager::open_path(&path);
+                } else {
+                    self.open_notice = Some(format!(
+                        "{} was moved or deleted since it was saved.",
+                        normal_path(&path).display()
+                    ));
+                }
+            }
+            if let Some(id) = redownload_clicked {
+                self.redownload(&id, &issue_key, ctx);
+            }
+            if let Some(notice) = &self.open_notice {
+                ui.colored_label(Color32::from_rgb(200, 120, 0), notice);
+            }
 
             ui.add_space(8.0);
 
-            // All action buttons in one row: Download Selected | Download All | Select All | Deselect All
-            let (dl_selected, dl_all, select_all, deselect_all) = ui
+            let any_missing = self
+                .download_items
+                .iter()
+                .chain(self.related_issues.iter().flat_map(|(_, items)| items))
+                .any(|i| matches_filter(i, &filter) && is_missing(&i.current_state()));
+
+            let busy = self.download_manager.is_busy();
+
+            // All action buttons in one row: Download Selected | Download All |
+            // Download Missing | Cancel All | Select All | Deselect All
+            let (dl_selected, dl_all, dl_missing, cancel_all, select_all, deselect_all) = ui
                 .horizontal(|ui| {
                     let ds = ui.button("Download Selected").clicked();
                     let da = ui.button("Download All").clicked();
+                    let dm = ui
+                        .add_enabled(any_missing, egui::Button::new("Download Missing Only"))
+                        .on_hover_text("Files shown that aren't on disk yet or failed earlier")
+                        .clicked();
+                    let ca = ui
+                        .add_enabled(busy, egui::Button::new("⏹ Cancel All"))
+                        .on_hover_text("Stop every queued and running download; finished files stay")
+                        .clicked();
                     ui.add_space(8.0);
                     let sa = ui.button("Select All").clicked();
                     let de = ui.button("Deselect All").clicked();
-                    (ds, da, sa, de)
+                    ui.add_space(8.0);
+                    ui.checkbox(&mut self.thumbnails_only, "Thumbnails only (images)")
+                        .on_hover_text("Fetch JIRA's small preview instead of the full image, into a thumbnails/ folder");
+                    (ds, da, dm, ca, sa, de)
                 })
                 .inner;
 
-            if select_all {
-                for item in &mut self.download_items {
+            if cancel_all {
+                self.download_manager.cancel_all();
+                self.batch_watches.clear();
+            }
+
+            // Ctrl+A / Ctrl+D act on the grid only while no text field has focus,
+            // so they still select text / do nothing inside the inputs.
+            let (key_select_all, key_download) = if ctx.wants_keyboard_input() {
+                (false, false)
+            } else {
+                ctx.input_mut(|i| {
+                    (
+                        i.consume_shortcut(&SHORTCUT_SELECT_ALL),
+                        i.consume_shortcut(&SHORTCUT_DOWNLOAD),
+                    )
+                })
+            };
+            let select_all = select_all || key_select_all;
+            let dl_selected = dl_selected || key_download;
+
+            let related_items = self.related_issues.iter_mut().flat_map(|(_, items)| items);
+            for item in self.download_items.iter_mut().chain(related_items) {
+                item.thumbnail = self.thumbnails_only && item.attachment.thumbnail.is_some();
+            }
+
+            if let Some(column) = sort_clicked {
+                // Same column flips direction; a new column starts ascending.
+                let ascending = match self.attachment_sort {
+                    Some((c, asc)) if c == column => !asc,
+                    _ => true,
+                };
+                self.attachment_sort = Some((column, ascending));
+                sort_items(&mut self.download_items, self.attachment_sort);
+                for (_, items) in &mut self.related_issues {
+                    sort_items(items, self.attachment_sort);
+                }
+            }
+
+            // The buttons act on the related issues' rows as well.
+            let related_items = self.related_issues.iter_mut().flat_map(|(_, items)| items);
+            for item in self.download_items.iter_mut().chain(related_items) {
+                // Select/Deselect All only touch the rows the filter leaves visible.
+                if (select_all || deselect_all) && matches_filter(item, &filter) {
+                    item.selected = select_all;
+                }
+                if dl_all {
                     item.selected = true;
                 }
+                // Only the visible rows still waiting or failed, so nothing else restarts.
+                if dl_missing {
+                    item.selected = matches_filter(item, &filter) && is_missing(&item.current_state());
+                }
             }
-            if deselect_all {
-                for item in &mut self.download_items {
-                    item.selected = false;
+            if self.config.debug_logging {
+                if let Some((speed, files)) = self.download_manager.metrics.recent_average() {
+                    ui.colored_label(
+                        Color32::GRAY,
+                        format!("avg {}/s over last {files} file(s)", format_size(speed)),
+                    );
                 }
             }
-            if dl_all {
-                for item in &mut self.download_items {
-                    item.selected = true;
+
+            if selection_changed || select_all || deselect_all || dl_all || dl_missing {
+                self.persist_selections(&issue_key);
+            }
+            let mut start = false;
+            if dl_selected || dl_all || dl_missing {
+                let needed: u64 = self
+                    .download_items
+                    .iter()
+                    .chain(self.related_issues.iter().flat_map(|(_, items)| items))
+                    .filter(|i| i.selected && !matches!(i.current_state(), FileState::AlreadyOnDisk))
+                    .map(|i| i.attachment.size)
+                    .sum();
+                match free_space(&self.incident_download_dir()) {
+                    Some(free) if needed > free => self.space_warning = Some((needed, free)),
+                    _ => start = true,
+                }
+            }
+
+            if let Some((needed, free)) = self.space_warning {
+                let (anyway, cancel) = ui
+                    .horizontal(|ui| {
+                        ui.colored_label(
+                            Color32::from_rgb(200, 60, 60),
+                            format!(
+                                "Not enough disk space: need {}, have {} free.",
+                                format_size(needed),
+                                format_size(free)
+                            ),
+                        );
+                        (ui.button("Download Anyway").clicked(), ui.button("Cancel").clicked())
+                    })
+                    .inner;
+                if anyway || cancel {
+                    self.space_warning = None;
+                    start = anyway;
                 }
             }
-            if dl_selected || dl_all {
+
+            if start {
                 self.download_manager.start_all_downloads(
                     &self.download_items,
                     &issue_key,
-                    &self.config,
+                    &self.incident_config(),
                     ctx.clone(),
                 );
+                // Each related issue's files go into its own issue folder,
+                // created now that something of it is downloaded.
+                for (issue, items) in &self.related_issues {
+                    if items.iter().any(|i| i.selected) {
+                        self.record_issue(issue, &self.incident_config());
+                    }
+                    self.download_manager.start_all_downloads(
+                        items,
+                        &issue.key,
+                        &self.incident_config(),
+                        ctx.clone(),
+                    );
+                }
+                let items: Vec<DownloadItem> = self
+                    .download_items
+                    .iter()
+                    .chain(self.related_issues.iter().flat_map(|(_, items)| items))
+                    .cloned()
+                    .collect();
+                self.watch_batch(issue_key.clone(), &items);
             }
         } else {
             ui.add_space(20.0);
@@ -494,31 +2144,806 @@ impl App {
         }
     }
 
-    fn do_fetch(&mut self, ctx: &egui::Context) {
-        let input = self.incident_input.trim().to_string();
-        let key = match parse_issue_key(&input) {
-            Some(k) => k,
-            None => {
-                *self.fetch_status.lock().unwrap() =
-                    Some(Err("Invalid issue key or URL".to_string()));
-                return;
+    /// The subtasks / linked issues of the fetched issue, one section per key
+    /// with the same columns as the main grid. Returns a clicked sort column.
+    fn render_related_issues(
+        &mut self,
+        ui: &mut egui::Ui,
+        ctx: &egui::Context,
+        filter: &str,
+        sort: Option<(AttachmentSort, bool)>,
+        clicks: &mut RowClicks,
+    ) -> Option<AttachmentSort> {
+        let keys = self.related_keys.lock().unwrap().take();
+        match keys {
+            Some(Ok(keys)) => {
+                self.related_pending = keys.len();
+                self.fetch_in_sequence(keys, Arc::clone(&self.related_results), ctx);
             }
-        };
+            Some(Err(e)) => self
+                .related_errors
+                .push(format!("Related issues: {}", e.lines().next().unwrap_or(""))),
+            None => {}
+        }
+        let fetched: Vec<_> = self.related_results.lock().unwrap().drain(..).collect();
+        for (key, result) in fetched {
+            self.related_pending = self.related_pending.saturating_sub(1);
+            match result {
+                Ok(issue) => {
+                    let mut items = self.list_issue(&issue, &self.incident_config(), ctx);
+                    sort_items(&mut items, self.attachment_sort);
+                    self.related_issues.push((issue, items));
+                }
+                Err(e) => self.related_errors.push(format!("{key}: {}", e.lines().next().unwrap_or(""))),
+            }
+        }
 
-        self.current_issue = None;
-        self.download_items.clear();
-        *self.fetch_status.lock().unwrap() = None;
+        if self.related_pending > 0 {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label(format!("{} related issue(s) still loading", self.related_pending));
+            });
+        }
+        for err in &self.related_errors {
+            ui.colored_label(Color32::from_rgb(200, 60, 60), err);
+        }
 
-        let config = self.config.clone();
-        let status = Arc::clone(&self.fetch_status);
-        let ctx = ctx.clone();
+        let mut sort_clicked = None;
+        for (issue, items) in &mut self.related_issues {
+            let title = format!(
+                "{} — {} ({} attachment(s))",
+                issue.key,
+                truncate_chars(&issue.summary, 50),
+                items.len()
+            );
+            egui::CollapsingHeader::new(RichText::new(title).strong())
+                .id_salt(("related_issue", &issue.key))
+                .default_open(!items.is_empty())
+                .show(ui, |ui| {
+                    if items.is_empty() {
+                        ui.colored_label(Color32::GRAY, "No attachments.");
+                        return;
+                    }
+                    egui::ScrollArea::vertical()
+                        .id_salt(("related_scroll", &issue.key))
+                        .max_height(200.0)
+                        .show(ui, |ui| {
+                            egui::Grid::new(("related_grid", &issue.key))
+                                .num_columns(7)
+                                .spacing([8.0, 4.0])
+                                .striped(true)
+                                .show(ui, |ui| {
+                                    sort_clicked = sort_clicked.or(attachment_grid_header(ui, sort));
+                                    for item in items.iter_mut() {
+                                        if matches_filter(item, filter) {
+                                            attachment_row(ui, item, &mut self.previews, &self.config, clicks);
+                                        }
+                                    }
+                                });
+                        });
+                });
+        }
+        sort_clicked
+    }
 
+    /// Fetches every case in My Cases one after another and queues all of
+    /// their missing attachments; the download cap applies across issues.
+    fn download_all_my_cases(&mut self, ctx: &egui::Context) {
+        let keys: Vec<String> = self.my_issues.iter().map(|i| i.key.clone()).collect();
+        self.bulk_pending = keys.len();
+        self.bulk_items.clear();
+        self.bulk_errors.clear();
+        self.fetch_in_sequence(keys, Arc::clone(&self.bulk_results), ctx);
+    }
+
+    /// Fetches the issues one at a time, pushing each result as it arrives.
+    fn fetch_in_sequence(
+        &self,
+        keys: Vec<String>,
+        results: IssueFetches,
+        ctx: &egui::Context,
+    ) {
+        let config = self.config.clone();
+        let ctx = ctx.clone();
         self.runtime.spawn(async move {
             let client = JiraClient::new(config);
-            let result = client.fetch_issue(&key).await;
-            *status.lock().unwrap() = Some(result);
+            for key in keys {
+                let result = client.fetch_issue(&key).await.map_err(String::from);
+                results.lock().unwrap().push((key, result));
+                ctx.request_repaint();
+            }
+        });
+    }
+
+    fn fetch_multiple(&mut self, ctx: &egui::Context) {
+        let (keys, invalid) = parse_issue_keys(&self.multi_input);
+        self.multi_errors = invalid
+            .into_iter()
+            .map(|entry| format!("{entry}: not an issue key or URL"))
+            .collect();
+        self.multi_issues.clear();
+        self.multi_pending = keys.len();
+        self.fetch_in_sequence(keys, Arc::clone(&self.multi_results), ctx);
+    }
+
+    /// The fetched issues as collapsible sections, each with its own rows.
+    fn render_multi_fetch(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        let fetched: Vec<_> = self.multi_results.lock().unwrap().drain(..).collect();
+        for (key, result) in fetched {
+            self.multi_pending = self.multi_pending.saturating_sub(1);
+            match result {
+                Ok(issue) => {
+                    let items = self.prepare_issue(&issue, &self.config, ctx);
+                    self.multi_issues.push((issue, items));
+                }
+                Err(e) => self.multi_errors.push(format!("{key}: {}", e.lines().next().unwrap_or(""))),
+            }
+        }
+
+        if self.multi_pending > 0 {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label(format!("{} issue(s) still loading", self.multi_pending));
+            });
+        }
+        for err in &self.multi_errors {
+            ui.colored_label(Color32::from_rgb(200, 60, 60), err);
+        }
+        if self.multi_issues.is_empty() {
+            return;
+        }
+
+        let (_, finished, failed, downloaded, total) =
+            batch_totals(self.multi_issues.iter().flat_map(|(_, items)| items));
+        let selected = self
+            .multi_issues
+            .iter()
+            .flat_map(|(_, items)| items)
+            .filter(|i| i.selected)
+            .count();
+        let (download, clear) = ui
+            .horizontal(|ui| {
+                let download = ui
+                    .add_enabled(selected > 0, egui::Button::new(format!("Download Selected ({selected})")))
+                    .clicked();
+                let clear = ui.button("Clear").clicked();
+                if total > 0 {
+                    ui.add(
+                        egui::ProgressBar::new(downloaded as f32 / total as f32)
+                            .desired_width(160.0)
+                            .show_percentage(),
+                    );
+                    ui.label(format!("{finished} file(s) done{}", failed_suffix(failed)));
+                }
+                (download, clear)
+            })
+            .inner;
+
+        egui::ScrollArea::vertical()
+            .id_salt("multi_fetch_scroll")
+            .max_height(300.0)
+            .show(ui, |ui| {
+                for (issue, items) in &mut self.multi_issues {
+                    let title = format!(
+                        "{} — {} ({} attachment(s))",
+                        issue.key,
+                        truncate_chars(&issue.summary, 50),
+                        items.len()
+                    );
+                    egui::CollapsingHeader::new(RichText::new(title).strong())
+                        .id_salt(("multi_issue", &issue.key))
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            if items.is_empty() {
+                                ui.colored_label(Color32::GRAY, "No attachments.");
+                                return;
+                            }
+                            egui::Grid::new(("multi_grid", &issue.key))
+                                .num_columns(5)
+                                .spacing([8.0, 4.0])
+                                .striped(true)
+                                .show(ui, |ui| {
+                                    for item in items.iter_mut() {
+                                        let state = item.current_state();
+                                        ui.checkbox(&mut item.selected, "");
+                                        ui.label(&item.attachment.filename);
+                                        ui.label(format_size(item.attachment.size));
+                                        ui.add(
+                                            egui::ProgressBar::new(state.progress_fraction().unwrap_or(0.0))
+                                                .desired_width(120.0),
+                                        );
+                                        let color = match &state {
+                                            FileState::Done | FileState::ThumbnailDone | FileState::AlreadyOnDisk => {
+                                                Color32::from_rgb(60, 180, 60)
+                                            }
+                                            FileState::Error(_) => Color32::from_rgb(200, 60, 60),
+                                            FileState::Cancelled => Color32::from_rgb(200, 120, 0),
+                                            _ => ui.visuals().text_color(),
+                                        };
+                                        ui.colored_label(color, state.label());
+                                        ui.end_row();
+                                    }
+                                });
+                        });
+                }
+            });
+
+        if download {
+            for (issue, items) in &self.multi_issues {
+                self.download_manager
+                    .start_all_downloads(items, &issue.key, &self.config, ctx.clone());
+            }
+            let items: Vec<DownloadItem> =
+                self.multi_issues.iter().flat_map(|(_, items)| items).cloned().collect();
+            let label = format!("{} issues", self.multi_issues.len());
+            self.watch_batch(label, &items);
+        }
+        if clear {
+            self.multi_issues.clear();
+            self.multi_errors.clear();
+        }
+    }
+
+    /// Queues the downloads of every case "Download All My Cases" has fetched
+    /// so far. Runs every frame, so the batch moves on whichever tab is shown
+    /// and whether or not the My Cases panel is open.
+    fn process_bulk_results(&mut self, ctx: &egui::Context) {
+        let fetched: Vec<_> = self.bulk_results.lock().unwrap().drain(..).collect();
+        for (key, result) in fetched {
+            self.bulk_pending = self.bulk_pending.saturating_sub(1);
+            match result {
+                Ok(issue) if issue.attachments.is_empty() => {}
+                Ok(issue) => {
+                    let mut items = self.prepare_issue(&issue, &self.config, ctx);
+                    for item in &mut items {
+                        item.selected = !matches!(item.current_state(), FileState::AlreadyOnDisk);
+                    }
+                    self.download_manager
+                        .start_all_downloads(&items, &issue.key, &self.config, ctx.clone());
+                    self.bulk_items.extend(items.into_iter().filter(|i| i.selected));
+                }
+                Err(e) => self.bulk_errors.push(format!("{key}: {}", e.lines().next().unwrap_or(""))),
+            }
+            // The whole of "Download All My Cases" is one batch, watched once
+            // its last case has been fetched.
+            if self.bulk_pending == 0 {
+                let items = self.bulk_items.clone();
+                self.watch_batch("My cases".to_string(), &items);
+            }
+        }
+    }
+
+    fn render_bulk_progress(&self, ui: &mut egui::Ui) {
+        if self.bulk_pending == 0 && self.bulk_items.is_empty() && self.bulk_errors.is_empty() {
+            return;
+        }
+
+        let (_, finished, failed, downloaded, total) = batch_totals(&self.bulk_items);
+        let frac = if total > 0 { downloaded as f32 / total as f32 } else { 0.0 };
+
+        ui.horizontal(|ui| {
+            ui.add(egui::ProgressBar::new(frac).desired_width(160.0).show_percentage());
+            let mut text = format!("{finished}/{} files{}", self.bulk_items.len(), failed_suffix(failed));
+            if self.bulk_pending > 0 {
+                text.push_str(&format!(" · {} case(s) still loading", self.bulk_pending));
+            }
+            ui.label(text);
+        });
+        for err in &self.bulk_errors {
+            ui.colored_label(Color32::from_rgb(200, 60, 60), err);
+        }
+    }
+
+    /// Downloads one attachment again. The saved copy stays until the fresh one
+    /// has been verified and then takes its place, but only when that copy is
+    /// the one recorded for this attachment; otherwise the new file is kept
+    /// next to it. The row may also belong to one of the related issues.
+    fn redownload(&mut self, attachment_id: &str, issue_key: &str, ctx: &egui::Context) {
+        let config = self.incident_config();
+        let related = self
+            .related_issues
+            .iter_mut()
+            .map(|(issue, items)| (issue.key.as_str(), items));
+        let Some((issue_key, items, index)) = std::iter::once((issue_key, &mut self.download_items))
+            .chain(related)
+            .find_map(|(key, items)| {
+                let index = items.iter().position(|i| i.attachment.id == attachment_id)?;
+                Some((key, items, index))
+            })
+        else {
+            return;
+        };
+        let item = &mut items[index];
+        let recorded = StorageManager::new(config.download_dir.clone()).recorded_file(issue_key, attachment_id);
+        let replaces = item.saved_path().filter(|path| recorded.as_ref() == Some(path));
+        item.selected = true;
+        *item.state.lock().unwrap() = FileState::Pending;
+        self.download_manager
+            .start_download(items, index, issue_key, &config, replaces, ctx.clone());
+    }
+
+    /// Refreshes the issue's control file (and shortcut) and builds its
+    /// download rows, marking files that are already on disk. `config` is
+    /// `self.config`, or [`Self::incident_config`] for the fetched issue.
+    fn prepare_issue(&self, issue: &IssueInfo, config: &AppConfig, ctx: &egui::Context) -> Vec<DownloadItem> {
+        let ctrl = self.record_issue(issue, config);
+        self.issue_rows(issue, config, &ctrl, ctx)
+    }
+
+    /// Like [`Self::prepare_issue`], but writes nothing: for related issues,
+    /// which only get a folder once something of theirs is downloaded.
+    fn list_issue(&self, issue: &IssueInfo, config: &AppConfig, ctx: &egui::Context) -> Vec<DownloadItem> {
+        let ctrl = StorageManager::new(config.download_dir.clone())
+            .load_control_file(&issue.key)
+            .unwrap_or_else(|| ControlFile::new(&issue.key, &issue.summary, &issue.status));
+        self.issue_rows(issue, config, &ctrl, ctx)
+    }
+
+    /// Creates or refreshes the issue's control file and shortcut.
+    fn record_issue(&self, issue: &IssueInfo, config: &AppConfig) -> ControlFile {
+        let storage = StorageManager::new(config.download_dir.clone());
+        let update = |ctrl: &mut ControlFile| {
+            ctrl.issue_summary = issue.summary.clone();
+            ctrl.issue_status = issue.status.clone();
+            ctrl.last_checked = chrono::Utc::now();
+            ctrl.clone()
+        };
+        let ctrl = storage
+            .upsert_control_file(&issue.key, || ControlFile::new(&issue.key, &issue.summary, &issue.status), update)
+            .unwrap_or_else(|e| {
+                log::warn!("{}: could not save control file: {e}", issue.key);
+                storage
+                    .load_control_file(&issue.key)
+                    .unwrap_or_else(|| ControlFile::new(&issue.key, &issue.summary, &issue.status))
+            });
+        if config.write_issue_shortcut {
+            let url = issue_browse_url(&config.jira_url, &issue.key);
+            let _ = storage.write_issue_shortcut(&issue.key, &url);
+        }
+        ctrl
+    }
+
+    /// Rows for the issue's attachments. Files found on disk are marked
+    /// `AlreadyOnDisk`; those with a recorded digest are then re-hashed in the
+    /// background and turn into an error if they no longer match.
+    fn issue_rows(
+        &self,
+        issue: &IssueInfo,
+        config: &AppConfig,
+        ctrl: &ControlFile,
+        ctx: &egui::Context,
+    ) -> Vec<DownloadItem> {
+        let storage = StorageManager::new(config.download_dir.clone())
+            .with_filename_template(&config.filename_template);
+        let items: Vec<DownloadItem> = issue
+            .attachments
+            .iter()
+            .map(|a| {
+                let existing = storage.existing_attachment(&issue.key, a, &ctrl.saved_files);
+                let mut item = DownloadItem::new(a.clone());
+                // A remembered choice wins; otherwise the type and size filter applies.
+                item.selected = ctrl.selections.get(&a.id).copied().unwrap_or_else(|| {
+                    config.select_new_attachments
+                        && passes_auto_select(&item, &self.excluded_kinds, self.size_filter_mb)
+                });
+                if existing.is_some() {
+                    item.selected = false;
+                    *item.state.lock().unwrap() = FileState::AlreadyOnDisk;
+                    *item.saved_path.lock().unwrap() = existing;
+                }
+                item
+            })
+            .collect();
+
+        let checks: Vec<_> = items
+            .iter()
+            .filter_map(|item| {
+                let known = ctrl.checksums.get(&item.attachment.id)?.clone();
+                Some((Arc::clone(&item.state), item.saved_path()?, known))
+            })
+            .collect();
+        if !checks.is_empty() {
+            let key = issue.key.clone();
+            let ctx = ctx.clone();
+            self.runtime.spawn_blocking(move || {
+                for (state, path, known) in checks {
+                    if let Err(e) = known.verify(&path) {
+                        log::warn!("{key}: {}: {e}", normal_path(&path).display());
+                        let mut state = state.lock().unwrap();
+                        if matches!(*state, FileState::AlreadyOnDisk) {
+                            *state = FileState::Error(e);
+                        }
+                    }
+                }
+                ctx.request_repaint();
+            });
+        }
+        items
+    }
+
+    /// The "Save to" row: a one-off folder for this issue's downloads that
+    /// leaves `download_dir` alone. Changing it re-checks which files are on disk.
+    fn render_save_to(&mut self, ui: &mut egui::Ui) {
+        let busy = self
+            .download_items
+            .iter()
+            .chain(self.related_issues.iter().flat_map(|(_, items)| items))
+            .any(|i| matches!(i.current_state(), FileState::Queued | FileState::Downloading { .. }));
+        let (pick, reset) = ui
+            .horizontal(|ui| {
+                ui.label("Save to:");
+                match &self.save_to {
+                    Some(dir) => ui.label(RichText::new(dir.display().to_string()).strong()),
+                    None => ui.label(RichText::new(self.config.download_dir.display().to_string()).weak()),
+                };
+                let pick = ui
+                    .add_enabled(!busy, egui::Button::new("Save to…"))
+                    .on_hover_text("Download this issue into another folder, still under <issue>/<date>")
+                    .clicked();
+                let mut reset = false;
+                if self.save_to.is_some() {
+                    reset = ui.add_enabled(!busy, egui::Button::new("Use Default")).clicked();
+                    ui.checkbox(&mut self.save_to_pinned, "📌 Keep for next fetches");
+                }
+                (pick, reset)
+            })
+            .inner;
+
+        if pick {
+            let Some(dir) = rfd::FileDialog::new()
+                .set_directory(self.incident_download_dir())
+                .pick_folder()
+            else {
+                return;
+            };
+            self.save_to = Some(dir);
+        } else if reset {
+            self.save_to = None;
+            self.save_to_pinned = false;
+        } else {
+            return;
+        }
+
+        let Some(issue) = self.current_issue.clone() else {
+            return;
+        };
+        // Keep the user's ticks for files that still need downloading.
+        let ticks: std::collections::HashMap<String, bool> = self
+            .download_items
+            .iter()
+            .chain(self.related_issues.iter().flat_map(|(_, items)| items))
+            .filter(|i| !matches!(i.current_state(), FileState::AlreadyOnDisk))
+            .map(|i| (i.attachment.id.clone(), i.selected))
+            .collect();
+        let retick = |mut items: Vec<DownloadItem>| {
+            for item in &mut items {
+                if let Some(&ticked) = ticks.get(&item.attachment.id) {
+                    if !matches!(item.current_state(), FileState::AlreadyOnDisk) {
+                        item.selected = ticked;
+                    }
+                }
+            }
+            sort_items(&mut items, self.attachment_sort);
+            items
+        };
+        let config = self.incident_config();
+        let ctx = ui.ctx().clone();
+        let items = retick(self.prepare_issue(&issue, &config, &ctx));
+        let related: Vec<_> = self
+            .related_issues
+            .iter()
+            .map(|(issue, _)| (issue.clone(), retick(self.list_issue(issue, &config, &ctx))))
+            .collect();
+        self.download_items = items;
+        self.related_issues = related;
+        self.space_warning = None;
+    }
+
+    /// Where the fetched issue is saved: the "Save to" folder, if one is set.
+    fn incident_download_dir(&self) -> std::path::PathBuf {
+        self.save_to.clone().unwrap_or_else(|| self.config.download_dir.clone())
+    }
+
+    /// `self.config` with the "Save to" folder in place of `download_dir`.
+    fn incident_config(&self) -> AppConfig {
+        let mut config = self.config.clone();
+        config.download_dir = self.incident_download_dir();
+        config
+    }
+
+    /// Activates another JIRA profile and drops everything fetched from the old server.
+    fn switch_profile(&mut self, index: usize) {
+        self.config.switch_profile(index);
+        // Remember the choice without saving unrelated unsaved Settings edits.
+        let _ = self.config.save_active_profile();
+        self.on_profile_changed();
+    }
+
+    /// After the active profile was switched, added or removed: forgets
+    /// everything fetched from the previous server.
+    fn on_profile_changed(&mut self) {
+        self.cancel_fetch();
+        *self.fetch_status.lock().unwrap() = None;
+        self.current_issue = None;
+        self.download_items.clear();
+        self.related_keys = Arc::new(Mutex::new(None));
+        self.related_results = Arc::new(Mutex::new(Vec::new()));
+        self.related_pending = 0;
+        self.related_issues.clear();
+        self.related_errors.clear();
+        *self.connection_status.lock().unwrap() = None;
+        self.my_issues.clear();
+        self.my_issues_loaded = false;
+        self.my_issues_error = None;
+        self.previews.clear();
+        self.show_token = false;
+        self.token_saved = self.config.has_saved_token();
+    }
+
+    /// Leaving the Incident tab optionally cancels a pending fetch; coming back
+    /// restarts it so the tab never shows a half-finished state.
+    fn on_tab_switch(&mut self, from: &Tab, ctx: &egui::Context) {
+        self.remember_session();
+        self.show_token = false;
+        if *from == Tab::Incident && self.config.cancel_fetch_on_tab_switch && self.cancel_fetch() {
+            self.fetch_interrupted = true;
+        }
+        if self.tab == Tab::History {
+            self.reload_history();
+        }
+        if self.tab == Tab::Incident && self.fetch_interrupted {
+            self.fetch_interrupted = false;
+            self.do_fetch(ctx);
+        }
+    }
+
+    /// Writes the current tab and issue to disk right away, so they can be
+    /// restored even after a crash. Only the UI state file is written, so
+    /// unsaved Settings edits stay unsaved.
+    fn remember_session(&mut self) {
+        self.config.last_tab = Some(self.tab.as_start());
+        if let Some(key) = self.current_issue.as_ref().map(|i| i.key.clone()) {
+            self.config.push_recent_issue(&key);
+            self.config.last_issue = Some(key);
+        }
+        let _ = self.config.save_ui_state();
+    }
+
+    /// Remembers the current checkbox state of every attachment in the issue's
+    /// control file, and of each related issue's attachments in theirs.
+    fn persist_selections(&self, issue_key: &str) {
+        let storage = StorageManager::new(self.incident_download_dir());
+        let related = self.related_issues.iter().map(|(issue, items)| (issue.key.as_str(), items));
+        for (key, items) in std::iter::once((issue_key, &self.download_items)).chain(related) {
+            let _ = storage.update_control_file(key, |ctrl| {
+                for item in items {
+                    ctrl.selections.insert(item.attachment.id.clone(), item.selected);
+                }
+            });
+        }
+    }
+
+    /// Aggregate state of the items that have been started in this issue.
+    /// Every download row the app holds, whichever view started it.
+    fn all_download_items(&self) -> impl Iterator<Item = &DownloadItem> {
+        self.download_items
+            .iter()
+            .chain(self.related_issues.iter().flat_map(|(_, items)| items))
+            .chain(&self.bulk_items)
+            .chain(self.multi_issues.iter().flat_map(|(_, items)| items))
+            .chain(self.reconcile_view.iter().flat_map(|view| &view.items))
+    }
+
+    fn taskbar_progress(&self) -> TaskbarProgress {
+        let mut downloaded = 0u64;
+        let mut total = 0u64;
+        let mut active = false;
+        let mut failed = false;
+
+        for item in self.all_download_items() {
+            match item.current_state() {
+                FileState::Downloading { downloaded: d, total: t } => {
+                    active = true;
+                    downloaded += d;
+                    total += t.max(item.attachment.size);
+                }
+                FileState::Done => {
+                    downloaded += item.attachment.size;
+                    total += item.attachment.size;
+                }
+                FileState::Queued => {
+                    active = true;
+                    total += item.attachment.size;
+                }
+                FileState::Error(_) => {
+                    failed = true;
+                    total += item.attachment.size;
+                }
+                _ => {}
+            }
+        }
+
+        if active {
+            let frac = if total > 0 { downloaded as f32 / total as f32 } else { 0.0 };
+            TaskbarProgress::Normal(frac)
+        } else if failed {
+            TaskbarProgress::Error
+        } else {
+            TaskbarProgress::Idle
+        }
+    }
+
+    /// Slim bar along the bottom: connection, active downloads and free disk space.
+    fn render_status_bar(&mut self, ctx: &egui::Context) {
+        let now = std::time::Instant::now();
+
+        let mut active = 0;
+        let mut received = 0u64;
+        for item in self.all_download_items() {
+            match item.current_state() {
+                FileState::Downloading { downloaded, .. } => {
+                    active += 1;
+                    received += downloaded;
+                }
+                FileState::Done => received += item.attachment.size,
+                _ => {}
+            }
+        }
+
+        // Speed over roughly the last second; reset whenever nothing is running.
+        if active == 0 {
+            self.speed_sample = None;
+            self.download_speed = 0.0;
+        } else {
+            match self.speed_sample {
+                Some((at, bytes)) if now.duration_since(at).as_secs_f64() >= 1.0 => {
+                    let secs = now.duration_since(at).as_secs_f64();
+                    self.download_speed = received.saturating_sub(bytes) as f64 / secs;
+                    self.speed_sample = Some((now, received));
+                }
+                Some(_) => {}
+                None => self.speed_sample = Some((now, received)),
+            }
+            ctx.request_repaint_after(std::time::Duration::from_secs(1));
+        }
+
+        let stale = match self.free_space {
+            Some((at, _)) => now.duration_since(at).as_secs() >= 10,
+            None => true,
+        };
+        if stale {
+            let free = free_space(&self.config.download_dir);
+            self.free_space = Some((now, free));
+        }
+
+        let conn = self.connection_status.lock().unwrap().clone();
+        egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                let (color, text) = match &conn {
+                    Some(Ok(msg)) => (Color32::from_rgb(60, 180, 60), msg.clone()),
+                    Some(Err(_)) => (Color32::from_rgb(200, 60, 60), "Not connected".to_string()),
+                    None => (Color32::GRAY, "Connection not tested".to_string()),
+                };
+                ui.colored_label(color, "●");
+                ui.label(RichText::new(text).small());
+                ui.separator();
+
+                if active > 0 {
+                    ui.label(
+                        RichText::new(format!(
+                            "{active} downloading · {}/s",
+                            format_size(self.download_speed as u64)
+                        ))
+                        .small(),
+                    );
+                } else {
+                    ui.label(RichText::new("No active downloads").small().color(Color32::GRAY));
+                }
+                ui.separator();
+
+                if let Some(wait) = rate_limit_remaining() {
+                    ui.colored_label(
+                        Color32::from_rgb(200, 120, 0),
+                        RichText::new(format!("Rate-limited, waiting {}s", wait.as_secs() + 1)).small(),
+                    );
+                    ui.separator();
+                    ctx.request_repaint_after(std::time::Duration::from_secs(1));
+                }
+
+                let free = match self.free_space {
+                    Some((_, Some(bytes))) => format!("{} free", format_size(bytes)),
+                    _ => "Free space unknown".to_string(),
+                };
+                ui.label(RichText::new(free).small())
+                    .on_hover_text(self.config.download_dir.display().to_string());
+            });
+        });
+    }
+
+    fn do_fetch(&mut self, ctx: &egui::Context) {
+        self.do_fetch_with(ctx, false);
+    }
+
+    /// `force_v2` skips API v3 entirely, for servers whose v3 responses don't parse.
+    fn do_fetch_with(&mut self, ctx: &egui::Context, force_v2: bool) {
+        let input = self.incident_input.trim().to_string();
+        let key = match parse_issue_key(&input) {
+            Some(k) => k,
+            None => {
+                // A fetch still running for the previous input must not replace this error.
+                self.cancel_fetch();
+                *self.fetch_status.lock().unwrap() =
+                    Some((self.fetch_generation, Err("Invalid issue key or URL".to_string().into())));
+                return;
+            }
+        };
+
+        self.cancel_fetch();
+        self.current_issue = None;
+        self.download_items.clear();
+        if !self.save_to_pinned {
+            self.save_to = None;
+        }
+        self.fetch_requested_key = key.clone();
+        self.moved_issue = None;
+        *self.fetch_status.lock().unwrap() = None;
+        self.fetch_related(&key, ctx);
+
+        let generation = self.fetch_generation;
+        let config = self.config.clone();
+        let status = Arc::clone(&self.fetch_status);
+        let ctx = ctx.clone();
+
+        let handle = self.runtime.spawn(async move {
+            let client = JiraClient::new(config);
+            let result = if force_v2 {
+                client.fetch_issue_with_versions(&key, &["2"]).await
+            } else {
+                client.fetch_issue(&key).await
+            };
+            *status.lock().unwrap() = Some((generation, result));
             ctx.request_repaint();
         });
+        self.fetch_task = Some(handle.abort_handle());
+    }
+
+    /// Drops the related issues shown so far and, when either option is on,
+    /// looks up the subtasks / linked issues of `key` to fetch one by one.
+    fn fetch_related(&mut self, key: &str, ctx: &egui::Context) {
+        self.related_keys = Arc::new(Mutex::new(None));
+        self.related_results = Arc::new(Mutex::new(Vec::new()));
+        self.related_pending = 0;
+        self.related_issues.clear();
+        self.related_errors.clear();
+        if !self.include_subtasks && !self.include_linked {
+            return;
+        }
+
+        let (subtasks, links) = (self.include_subtasks, self.include_linked);
+        let key = key.to_string();
+        let config = self.config.clone();
+        let slot = Arc::clone(&self.related_keys);
+        let ctx = ctx.clone();
+        self.runtime.spawn(async move {
+            let result = JiraClient::new(config).fetch_related_keys(&key, subtasks, links).await;
+            *slot.lock().unwrap() = Some(result);
+            ctx.request_repaint();
+        });
+    }
+
+    /// Aborts any in-flight issue fetch and invalidates its result, which may
+    /// still be stored if the task was already past its last await.
+    /// Returns true if a fetch was actually still running. A fetch that had
+    /// already finished keeps its generation, so its result is still shown.
+    fn cancel_fetch(&mut self) -> bool {
+        match self.fetch_task.take() {
+            Some(task) if !task.is_finished() => {
+                task.abort();
+                self.fetch_generation += 1;
+                true
+            }
+            _ => false,
+        }
     }
 
     // ─── Incidents Manager ─────────────────────────────────────────────────────
@@ -528,22 +2953,28 @@ impl App {
         ui.add_space(8.0);
 
         // 1. Process any pending async status updates before rendering
-        let updates: Vec<(String, Result<String, String>)> = {
+        let updates: Vec<(IncidentId, Result<String, String>)> = {
             self.check_status.lock().unwrap().drain(..).collect()
         };
-        for (key, result) in updates {
+        for ((root, key), result) in updates {
             if let Some(incident) = self
                 .incidents
                 .iter_mut()
-                .find(|i| i.control.issue_key == key)
+                .find(|i| i.root == root && i.control.issue_key == key)
             {
                 match result {
                     Ok(status) => {
-                        incident.control.issue_status = status;
-                        incident.control.last_checked = chrono::Utc::now();
-                        incident.control.marked_for_deletion = incident.control.is_closed();
-                        let storage = StorageManager::new(self.config.download_dir.clone());
-                        let _ = storage.save_control_file(&incident.control);
+                        incident.control.record_status(&status, &self.config.closed_statuses);
+                        // Only the status fields change; a download may be
+                        // recording checksums in the same file meanwhile.
+                        let control = incident.control.clone();
+                        let storage = StorageManager::new(incident.root.clone());
+                        let _ = storage.update_control_file(&key, |ctrl| {
+                            ctrl.issue_status = control.issue_status;
+                            ctrl.last_checked = control.last_checked;
+                            ctrl.marked_for_deletion = control.marked_for_deletion;
+                            ctrl.closed_since = control.closed_since;
+                        });
                     }
                     Err(e) => {
                         self.incidents_scan_status = format!("Error checking {key}: {e}");
@@ -553,32 +2984,122 @@ impl App {
         }
 
         // 2. Header buttons — extract click results before touching self
-        let (scan_clicked, check_all_clicked, delete_all_clicked) = ui
+        let can_archive = self.config.archive_dir.is_some();
+        let marked_bytes = self.folders_size(&self.marked_ids());
+        let delete_all_label = if marked_bytes > 0 {
+            format!("Delete All Marked ({})", format_size(marked_bytes))
+        } else {
+            "Delete All Marked".to_string()
+        };
+        let (scan_clicked, check_all_clicked, archive_all_clicked, delete_all_clicked, export_clicked) = ui
             .horizontal(|ui| {
                 (
                     ui.button("Scan Folder").clicked(),
                     ui.button("Check All Status").clicked(),
-                    ui.button("Delete All Marked").clicked(),
+                    ui.add_enabled(can_archive, egui::Button::new("Archive All Marked"))
+                        .on_disabled_hover_text("Set an archive directory in Settings")
+                        .clicked(),
+                    ui.button(delete_all_label).clicked(),
+                    ui.add_enabled(!self.incidents.is_empty(), egui::Button::new("Export Inventory"))
+                        .clicked(),
                 )
             })
             .inner;
 
         if scan_clicked {
-            let storage = StorageManager::new(self.config.download_dir.clone());
-            self.incidents = storage.scan_incidents();
+            if let Some(job) = self.sizing.take() {
+                job.stop();
+            }
+            self.incidents = StorageManager::scan_roots(&self.config.scan_dirs());
+            self.sizing = Some(SizingJob::start(
+                &self.runtime,
+                self.incidents.iter().map(|i| i.path.clone()).collect(),
+                ctx.clone(),
+            ));
+            self.unmanaged = self
+                .config
+                .scan_dirs()
+                .into_iter()
+                .flat_map(|root| StorageManager::new(root).scan_unmanaged())
+                .collect();
             self.incidents_scan_status =
                 format!("Found {} incident(s).", self.incidents.len());
         }
         if check_all_clicked {
             self.check_all_statuses(ctx);
         }
+        self.render_sizing(ui);
+        // Re-applied every frame so rows stay in order as status checks and
+        // sizing update them.
+        sort_incidents(&mut self.incidents, self.incident_sort);
+        if archive_all_clicked {
+            let ids = self.marked_ids();
+            self.archive_folders(&ids);
+        }
         if delete_all_clicked {
-            self.delete_all_marked();
+            let ids = self.marked_ids();
+            self.bulk_delete_typed.clear();
+            self.bulk_delete_confirm = Some(ids.into_iter().map(|id| (id, true)).collect());
+        }
+        if export_clicked {
+            self.export_inventory();
         }
 
         if !self.incidents_scan_status.is_empty() {
-            ui.label(&self.incidents_scan_status.clone());
+            ui.label(&self.incidents_scan_status);
+        }
+        self.render_zip_status(ui);
+        let retention_summary = self
+            .retention
+            .as_ref()
+            .and_then(|task| task.last_summary.lock().unwrap().clone());
+        if let Some(summary) = retention_summary {
+            ui.colored_label(Color32::GRAY, summary);
+        }
+        if self.retention.is_some() || self.status_poller.is_some() {
+            match self.background_connection.lock().unwrap().clone() {
+                ConnectionState::Online => {}
+                ConnectionState::Reconnecting => {
+                    ui.colored_label(Color32::GRAY, "Reconnecting to JIRA after sleep…");
+                }
+                ConnectionState::Offline(e) => {
+                    ui.colored_label(
+                        Color32::from_rgb(200, 120, 0),
+                        format!("Background checks can't reach JIRA, retrying: {}", e.lines().next().unwrap_or("")),
+                    );
+                }
+            }
         }
+        if let Some(poller) = &self.status_poller {
+            let last = match *poller.last_run.lock().unwrap() {
+                Some(at) => format!("last finished {}", at.format("%H:%M")),
+                None => "first round pending".to_string(),
+            };
+            ui.colored_label(
+                Color32::GRAY,
+                format!("Statuses refresh every {} min · {last}", self.config.status_poll_minutes),
+            );
+        }
+        if !self.incidents.is_empty() {
+            let (mut closed, mut open) = (0u64, 0u64);
+            for incident in &self.incidents {
+                if incident.control.is_closed(&self.config.closed_statuses) {
+                    closed += incident.folder_size;
+                } else {
+                    open += incident.folder_size;
+                }
+            }
+            let approx = if self.incidents.iter().all(|i| i.size_complete) { "" } else { "≈ " };
+            ui.label(format!(
+                "Disk usage: {approx}{} in {} folder(s) — {} closed, {} open",
+                format_size(closed + open),
+                self.incidents.len(),
+                format_size(closed),
+                format_size(open),
+            ));
+        }
+
+        self.render_unmanaged(ui, ctx);
 
         ui.add_space(8.0);
         ui.separator();
@@ -586,182 +3107,1068 @@ impl App {
         if self.incidents.is_empty() {
             ui.colored_label(Color32::GRAY, "No incidents found. Click 'Scan Folder'.");
         } else {
+            ui.horizontal(|ui| {
+                ui.label("Search:");
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.incident_filter)
+                        .hint_text("Issue key or summary")
+                        .desired_width(200.0),
+                );
+                // Open and closed only exclude each other.
+                if ui.checkbox(&mut self.incident_open_only, "Open only").changed() && self.incident_open_only {
+                    self.incident_closed_only = false;
+                }
+                if ui.checkbox(&mut self.incident_closed_only, "Closed only").changed()
+                    && self.incident_closed_only
+                {
+                    self.incident_open_only = false;
+                }
+                ui.checkbox(&mut self.incident_marked_only, "Marked for deletion");
+                let all_tags = all_incident_tags(&self.incidents);
+                if !all_tags.is_empty() {
+                    egui::ComboBox::from_id_salt("incident_tag_filter")
+                        .selected_text(self.incident_tag_filter.as_deref().unwrap_or("Any tag"))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut self.incident_tag_filter, None, "Any tag");
+                            for tag in all_tags {
+                                ui.selectable_value(&mut self.incident_tag_filter, Some(tag.clone()), tag);
+                            }
+                        });
+                }
+                let shown = self
+                    .incidents
+                    .iter()
+                    .filter(|i| {
+                        incident_matches(
+                            i,
+                            &self.incident_filter,
+                            self.incident_status_filter(),
+                            self.incident_marked_only,
+                            self.incident_tag_filter.as_deref(),
+                            &self.config.closed_statuses,
+                        )
+                    })
+                    .count();
+                ui.colored_label(
+                    Color32::GRAY,
+                    format!("{shown} of {} shown", self.incidents.len()),
+                );
+                let filtered = !self.incident_filter.is_empty()
+                    || self.incident_closed_only
+                    || self.incident_open_only
+                    || self.incident_marked_only
+                    || self.incident_tag_filter.is_some();
+                if filtered && ui.small_button("✖").clicked() {
+                    self.incident_filter.clear();
+                    self.incident_closed_only = false;
+                    self.incident_open_only = false;
+                    self.incident_marked_only = false;
+                    self.incident_tag_filter = None;
+                }
+            });
+            ui.add_space(4.0);
+
             // 3. Render grid — collect action intents, don't mutate self inside closures
-            let mut to_check: Option<String> = None;
-            let mut to_open: Option<String> = None;
-            let mut to_delete: Option<String> = None;
+            let mut to_check: Option<IncidentId> = None;
+            let mut to_open: Option<IncidentId> = None;
+            let mut to_delete: Option<IncidentId> = None;
+            let mut to_archive: Option<IncidentId> = None;
+            let mut to_compare: Option<IncidentId> = None;
+            let mut to_zip: Option<IncidentId> = None;
+            // (incident, tag, true to add / false to remove)
+            let mut tag_edit: Option<(IncidentId, String, bool)> = None;
+            let known_tags = all_incident_tags(&self.incidents);
+            let status_filter = self.incident_status_filter();
+            let mut sort_clicked = None;
+            let mut shown_bytes = 0u64;
+            let mut shown_complete = true;
 
             egui::ScrollArea::vertical().show(ui, |ui| {
                 egui::Grid::new("incidents_grid")
-                    .num_columns(7)
+                    .num_columns(8)
                     .spacing([8.0, 6.0])
                     .striped(true)
                     .show(ui, |ui| {
-                        ui.label(RichText::new("Issue").strong());
+                        let mut header = |ui: &mut egui::Ui, column: IncidentSort, title: &str| {
+                            let arrow = match self.incident_sort {
+                                (c, true) if c == column => " ⏶",
+                                (c, false) if c == column => " ⏷",
+                                _ => "",
+                            };
+                            let text = RichText::new(format!("{title}{arrow}")).strong();
+                            if ui.add(egui::Button::new(text).frame(false)).clicked() {
+                                sort_clicked = Some(column);
+                            }
+                        };
+                        header(ui, IncidentSort::Key, "Issue");
                         ui.label(RichText::new("Summary").strong());
-                        ui.label(RichText::new("Status").strong());
-                        ui.label(RichText::new("Size").strong());
-                        ui.label(RichText::new("Last Checked").strong());
+                        ui.label(RichText::new("Tags").strong());
+                        header(ui, IncidentSort::Status, "Status");
+                        header(ui, IncidentSort::Size, "Size");
+                        header(ui, IncidentSort::LastChecked, "Last Checked");
                         ui.label(RichText::new("Actions").strong());
                         ui.label("");
                         ui.end_row();
 
-                        for incident in &self.incidents {
-                            let ctrl = &incident.control;
-                            let is_closed = ctrl.is_closed();
-                            let key = ctrl.issue_key.clone();
+                        for incident in &self.incidents {
+                            if !incident_matches(
+                                incident,
+                                &self.incident_filter,
+                                status_filter,
+                                self.incident_marked_only,
+                                self.incident_tag_filter.as_deref(),
+                                &self.config.closed_statuses,
+                            ) {
+                                continue;
+                            }
+                            shown_bytes += incident.folder_size;
+                            shown_complete &= incident.size_complete;
+                            let ctrl = &incident.control;
+                            let is_closed = ctrl.is_closed(&self.config.closed_statuses);
+                            let key = ctrl.issue_key.clone();
+                            let id = incident.id();
+
+                            ui.label(RichText::new(&key).strong());
+
+                            ui.label(truncate_chars(&ctrl.issue_summary, 28));
+
+                            ui.horizontal(|ui| {
+                                for tag in &ctrl.tags {
+                                    if ui.small_button(tag).on_hover_text("Click to remove").clicked() {
+                                        tag_edit = Some((id.clone(), tag.clone(), false));
+                                    }
+                                }
+                                ui.menu_button("+", |ui| {
+                                    for tag in known_tags.iter().filter(|t| !ctrl.tags.contains(t)) {
+                                        if ui.button(tag).clicked() {
+                                            tag_edit = Some((id.clone(), tag.clone(), true));
+                                            ui.close_menu();
+                                        }
+                                    }
+                                    ui.horizontal(|ui| {
+                                        let resp = ui.add(
+                                            egui::TextEdit::singleline(&mut self.tag_input)
+                                                .hint_text("New tag")
+                                                .desired_width(120.0),
+                                        );
+                                        let enter = resp.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                                        let tag = self.tag_input.trim().to_string();
+                                        if (ui.button("Add").clicked() || enter) && !tag.is_empty() {
+                                            tag_edit = Some((id.clone(), tag, true));
+                                            self.tag_input.clear();
+                                            ui.close_menu();
+                                        }
+                                    });
+                                });
+                            });
+
+                            let status_color =
+                                if is_closed { Color32::from_rgb(200, 60, 60) } else { Color32::from_rgb(60, 180, 60) };
+                            ui.colored_label(status_color, &ctrl.issue_status);
+
+                            if incident.size_complete {
+                                ui.label(format_size(incident.folder_size));
+                            } else {
+                                ui.colored_label(
+                                    Color32::GRAY,
+                                    format!("≈ {}", format_size(incident.folder_size)),
+                                )
+                                .on_hover_text("Approximate — sizing hasn't finished");
+                            }
+
+                            let elapsed = chrono::Utc::now()
+                                .signed_duration_since(ctrl.last_checked);
+                            ui.label(format_duration(elapsed));
+
+                            ui.horizontal(|ui| {
+                                if ui.button("Check").clicked() {
+                                    to_check = Some(id.clone());
+                                }
+                                if ui.button("Open").clicked() {
+                                    to_open = Some(id.clone());
+                                }
+                                if ui.button("Compare").clicked() {
+                                    to_compare = Some(id.clone());
+                                }
+                                if ui.add_enabled(!self.zip_pending, egui::Button::new("Zip")).clicked() {
+                                    to_zip = Some(id.clone());
+                                }
+                            });
+
+                            if is_closed || ctrl.marked_for_deletion {
+                                ui.horizontal(|ui| {
+                                    if can_archive && ui.button("Archive").clicked() {
+                                        to_archive = Some(id.clone());
+                                    }
+                                    if ui
+                                        .button(
+                                            RichText::new("Delete ⚠").color(Color32::RED),
+                                        )
+                                        .clicked()
+                                    {
+                                        to_delete = Some(id.clone());
+                                    }
+                                });
+                            } else {
+                                ui.label("");
+                            }
+
+                            ui.end_row();
+                        }
+                    });
+            });
+
+            let approx = if shown_complete { "" } else { "≈ " };
+            ui.label(RichText::new(format!("Total shown: {approx}{}", format_size(shown_bytes))).strong());
+
+            // 4. Apply collected actions (self is free again)
+            if let Some(column) = sort_clicked {
+                // Same column flips direction; a new column starts ascending,
+                // except size, where the biggest folders are usually wanted first.
+                let ascending = match self.incident_sort {
+                    (c, asc) if c == column => !asc,
+                    _ => column != IncidentSort::Size,
+                };
+                self.incident_sort = (column, ascending);
+                sort_incidents(&mut self.incidents, self.incident_sort);
+            }
+            if let Some(id) = to_check {
+                self.check_single_status(id, ctx);
+            }
+            if let Some((root, key)) = to_open {
+                self.incident_storage(&root).open_folder(&key);
+            }
+            if let Some(id) = to_delete {
+                self.delete_confirm = Some(id);
+            }
+            if let Some(id) = to_archive {
+                self.archive_folders(&[id]);
+            }
+            if let Some(id) = to_compare {
+                self.start_reconcile(id, ctx);
+            }
+            if let Some((root, key)) = to_zip {
+                self.zip_issue_folder(root, &key, ctx);
+            }
+            if let Some((id, tag, add)) = tag_edit {
+                self.edit_incident_tag(&id, &tag, add);
+            }
+        }
+
+        self.render_reconcile(ctx);
+
+        // 5. Deletion confirmation dialog
+        if let Some(id) = self.delete_confirm.clone() {
+            let mut confirmed = false;
+            let mut archived = false;
+            let mut cancelled = false;
+
+            egui::Window::new("Confirm Deletion")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.label(format!(
+                        "Move the folder for {} in {} to the recycle bin?",
+                        id.1,
+                        id.0.display()
+                    ));
+                    if can_archive {
+                        ui.label("Archiving keeps the files in the archive directory instead.");
+                    }
+                    ui.horizontal(|ui| {
+                        if can_archive && ui.button("Archive").clicked() {
+                            archived = true;
+                        }
+                        if ui.button("Yes, Delete").clicked() {
+                            confirmed = true;
+                        }
+                        if ui.button("Cancel").clicked() {
+                            cancelled = true;
+                        }
+                    });
+                });
+
+            if confirmed {
+                self.delete_folders(&[id]);
+                self.delete_confirm = None;
+            } else if archived {
+                self.archive_folders(&[id]);
+                self.delete_confirm = None;
+            } else if cancelled {
+                self.delete_confirm = None;
+            }
+        }
+
+        // 6. Bulk deletion confirmation, listing every folder that would go
+        if let Some(mut choices) = self.bulk_delete_confirm.take() {
+            let mut confirmed = false;
+            let mut archived = false;
+            let mut cancelled = false;
+            let ids: Vec<IncidentId> = choices
+                .iter()
+                .filter(|(_, ticked)| *ticked)
+                .map(|(id, _)| id.clone())
+                .collect();
+            let total = self.folders_size(&ids);
+            // A folder still being sized may be far bigger than it looks.
+            let size_unknown = self.incidents.iter().any(|i| ids.contains(&i.id()) && !i.size_complete);
+            let large = ids.len() > BULK_DELETE_MAX_COUNT || total > BULK_DELETE_MAX_BYTES || size_unknown;
+            let closed_statuses = self.config.closed_statuses.clone();
+
+            egui::Window::new("Confirm Bulk Deletion")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.colored_label(
+                        Color32::RED,
+                        format!(
+                            "Move {} folder(s), {} in total, to the recycle bin?",
+                            ids.len(),
+                            format_size(total)
+                        ),
+                    );
+                    ui.label("Untick any folder you want to keep.");
+                    ui.add_space(4.0);
+                    egui::ScrollArea::vertical()
+                        .id_salt("bulk_delete_scroll")
+                        .max_height(240.0)
+                        .show(ui, |ui| {
+                            egui::Grid::new("bulk_delete_grid").num_columns(3).show(ui, |ui| {
+                                for (id, ticked) in &mut choices {
+                                    let incident = self.incidents.iter().find(|i| i.id() == *id);
+                                    ui.checkbox(ticked, id.1.as_str())
+                                        .on_hover_text(id.0.display().to_string());
+                                    match incident {
+                                        Some(i) if i.size_complete => ui.label(format_size(i.folder_size)),
+                                        Some(i) => ui.label(format!("{}+", format_size(i.folder_size))),
+                                        None => ui.label(""),
+                                    };
+                                    match incident {
+                                        Some(i) if i.control.is_closed(&closed_statuses) => {
+                                            ui.label(RichText::new(&i.control.issue_status).weak())
+                                        }
+                                        Some(i) => ui.colored_label(
+                                            Color32::from_rgb(200, 120, 0),
+                                            format!("⚠ not closed ({}), marked", i.control.issue_status),
+                                        ),
+                                        None => ui.label(""),
+                                    };
+                                    ui.end_row();
+                                }
+                            });
+                        });
+                    ui.add_space(4.0);
+                    if large {
+                        ui.horizontal(|ui| {
+                            ui.label("Type DELETE to confirm:");
+                            ui.text_edit_singleline(&mut self.bulk_delete_typed);
+                        });
+                    }
+                    ui.horizontal(|ui| {
+                        let armed = !ids.is_empty() && (!large || self.bulk_delete_typed.trim() == "DELETE");
+                        if can_archive
+                            && ui
+                                .add_enabled(!ids.is_empty(), egui::Button::new("Archive Instead"))
+                                .clicked()
+                        {
+                            archived = true;
+                        }
+                        if ui.add_enabled(armed, egui::Button::new("Delete")).clicked() {
+                            confirmed = true;
+                        }
+                        if ui.button("Cancel").clicked() {
+                            cancelled = true;
+                        }
+                    });
+                });
+
+            if confirmed {
+                self.delete_folders(&ids);
+            } else if archived {
+                self.archive_folders(&ids);
+            } else if !cancelled {
+                self.bulk_delete_confirm = Some(choices);
+            }
+        }
+
+        // 7. The recycle bin refused some folders: delete them for good only on request.
+        if let Some(ids) = self.permanent_delete_confirm.clone() {
+            let mut permanent = false;
+            let mut keep = false;
+            egui::Window::new("Recycle Bin Unavailable")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    let keys: Vec<&str> = ids.iter().map(|(_, key)| key.as_str()).collect();
+                    ui.label(format!(
+                        "{} folder(s) couldn't be moved to the recycle bin: {}",
+                        ids.len(),
+                        keys.join(", ")
+                    ));
+                    ui.colored_label(Color32::RED, "Delete them permanently? This can't be undone.");
+                    ui.horizontal(|ui| {
+                        permanent = ui.button("Delete Permanently").clicked();
+                        keep = ui.button("Keep").clicked();
+                    });
+                });
+            if permanent {
+                self.delete_folders_permanently(&ids);
+                self.permanent_delete_confirm = None;
+            } else if keep {
+                self.permanent_delete_confirm = None;
+            }
+        }
+    }
+
+    // ─── History ───────────────────────────────────────────────────────────────
+
+    fn render_history(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Download History");
+        ui.add_space(8.0);
+
+        let (refresh_clicked, export_clicked, clear_clicked) = ui
+            .horizontal(|ui| {
+                ui.label("Search:");
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.history_search)
+                        .hint_text("Issue key or filename")
+                        .desired_width(240.0),
+                );
+                ui.label("Show:");
+                let limit_label = |n: usize| if n == 0 { "All".to_string() } else { format!("Last {n}") };
+                egui::ComboBox::from_id_salt("history_limit")
+                    .selected_text(limit_label(self.history_limit))
+                    .show_ui(ui, |ui| {
+                        for n in [50, 200, 1000, 0] {
+                            ui.selectable_value(&mut self.history_limit, n, limit_label(n));
+                        }
+                    });
+                (
+                    ui.button("↻ Refresh").clicked(),
+                    ui.button("Export...").clicked(),
+                    ui.add_enabled(!self.history.is_empty(), egui::Button::new("Clear History"))
+                        .clicked(),
+                )
+            })
+            .inner;
+
+        if refresh_clicked {
+            self.reload_history();
+        }
+        if clear_clicked {
+            self.history_clear_confirm = true;
+        }
+        if self.history_clear_confirm {
+            let mut confirmed = false;
+            let mut cancelled = false;
+            egui::Window::new("Clear History")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ui.ctx(), |ui| {
+                    ui.label(format!(
+                        "Remove all {} history entries? Downloaded files are not touched.",
+                        self.history.len()
+                    ));
+                    ui.horizontal(|ui| {
+                        if ui.button("Yes, Clear").clicked() {
+                            confirmed = true;
+                        }
+                        if ui.button("Cancel").clicked() {
+                            cancelled = true;
+                        }
+                    });
+                });
+            if confirmed {
+                self.history_status = match history::clear() {
+                    Ok(()) => "History cleared.".to_string(),
+                    Err(e) => e,
+                };
+                self.reload_history();
+            }
+            if confirmed || cancelled {
+                self.history_clear_confirm = false;
+            }
+        }
+
+        let needle = self.history_search.trim().to_lowercase();
+        let visible: Vec<&HistoryEntry> = self
+            .history
+            .iter()
+            .rev()
+            .filter(|e| {
+                needle.is_empty()
+                    || e.issue_key.to_lowercase().contains(&needle)
+                    || e.filename.to_lowercase().contains(&needle)
+            })
+            .take(if self.history_limit == 0 { usize::MAX } else { self.history_limit })
+            .collect();
+
+        if export_clicked {
+            let entries: Vec<HistoryEntry> = visible.iter().map(|e| (*e).clone()).collect();
+            if let Some(path) = rfd::FileDialog::new()
+                .set_file_name("download_history.csv")
+                .add_filter("CSV", &["csv"])
+                .add_filter("JSON", &["json"])
+                .save_file()
+            {
+                self.history_status =
+                    match export_history(&entries, &path, ExportFormat::from_path(&path)) {
+                        Ok(()) => format!("Exported {} entries to {}.", entries.len(), path.display()),
+                        Err(e) => format!("Export failed: {e}"),
+                    };
+            }
+        }
+
+        if !self.history_status.is_empty() {
+            ui.label(&self.history_status);
+        }
+        ui.label(format!("{} of {} entries", visible.len(), self.history.len()));
+        ui.add_space(4.0);
+        ui.separator();
+
+        egui::ScrollArea::vertical()
+            .id_salt("history_scroll")
+            .show(ui, |ui| {
+                egui::Grid::new("history_grid")
+                    .num_columns(6)
+                    .spacing([12.0, 4.0])
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.label(RichText::new("Downloaded").strong());
+                        ui.label(RichText::new("Issue").strong());
+                        ui.label(RichText::new("File").strong());
+                        ui.label(RichText::new("Size").strong());
+                        ui.label(RichText::new("Result").strong());
+                        ui.label(RichText::new("Saved To").strong());
+                        ui.end_row();
+
+                        for entry in &visible {
+                            ui.label(
+                                entry
+                                    .downloaded_at
+                                    .with_timezone(&chrono::Local)
+                                    .format("%Y-%m-%d %H:%M")
+                                    .to_string(),
+                            );
+                            ui.label(RichText::new(&entry.issue_key).strong());
+                            ui.label(&entry.filename);
+                            ui.label(format_size(entry.size));
+                            match &entry.error {
+                                None => ui.colored_label(Color32::from_rgb(60, 180, 60), "Done ✓"),
+                                Some(e) => ui
+                                    .colored_label(Color32::RED, "Error")
+                                    .on_hover_text(e.as_str()),
+                            };
+                            ui.label(entry.destination.to_string_lossy().as_ref());
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+
+    fn reload_history(&mut self) {
+        self.history = history::load();
+    }
+
+    /// Copies running totals from the sizing job into the incident list and
+    /// shows its progress with a stop button.
+    fn render_sizing(&mut self, ui: &mut egui::Ui) {
+        let Some(job) = &self.sizing else {
+            return;
+        };
+
+        let sizes = job.sizes.lock().unwrap().clone();
+        for incident in &mut self.incidents {
+            if let Some(size) = sizes.get(&incident.path) {
+                incident.folder_size = size.bytes;
+                incident.file_count = size.files;
+                incident.size_complete = size.complete;
+            }
+        }
+
+        let done = self.incidents.iter().filter(|i| i.size_complete).count();
+        if job.is_running() {
+            let stop_clicked = ui
+                .horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(format!("Sizing folders… {done}/{}", self.incidents.len()));
+                    ui.add_enabled(!job.is_stopped(), egui::Button::new("Stop sizing"))
+                        .clicked()
+                })
+                .inner;
+            if stop_clicked {
+                job.stop();
+            }
+        } else {
+            if job.is_stopped() && done < self.incidents.len() {
+                self.incidents_scan_status = format!(
+                    "Sizing stopped — {} folder(s) show approximate sizes.",
+                    self.incidents.len() - done
+                );
+            }
+            self.sizing = None;
+        }
+    }
+
+    /// Lists key-named folders without a usable control file and offers to rebuild it.
+    fn render_unmanaged(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        let repaired: Vec<(UnmanagedFolder, Result<IssueInfo, String>)> =
+            self.repair_results.lock().unwrap().drain(..).collect();
+        for (folder, result) in repaired {
+            let key = folder.issue_key.clone();
+            match result {
+                Ok(issue) => {
+                    let storage = StorageManager::new(folder.root.clone());
+                    let ctrl = ControlFile::new(&key, &issue.summary, &issue.status);
+                    match storage.save_control_file(&ctrl) {
+                        Ok(()) => {
+                            self.unmanaged.retain(|u| !(u.issue_key == key && u.root == folder.root));
+                            if let Some(incident) = storage.incident_folder(&key) {
+                                self.incidents.push(incident);
+                                self.incidents
+                                    .sort_by(|a, b| a.control.issue_key.cmp(&b.control.issue_key));
+                            }
+                            self.incidents_scan_status = format!("Repaired control file for {key}.");
+                        }
+                        Err(e) => self.incidents_scan_status = format!("Repair of {key} failed: {e}"),
+                    }
+                }
+                Err(e) => {
+                    self.incidents_scan_status =
+                        format!("Repair of {key} failed — is it a valid issue? {e}");
+                }
+            }
+        }
 
-                            ui.label(RichText::new(&key).strong());
+        if self.unmanaged.is_empty() {
+            return;
+        }
 
-                            let summary = if ctrl.issue_summary.len() > 28 {
-                                format!("{}...", &ctrl.issue_summary[..28])
-                            } else {
-                                ctrl.issue_summary.clone()
-                            };
-                            ui.label(summary);
+        let mut to_repair: Vec<UnmanagedFolder> = Vec::new();
+        egui::CollapsingHeader::new(
+            RichText::new(format!(
+                "{} folder(s) without a valid control file",
+                self.unmanaged.len()
+            ))
+            .color(Color32::from_rgb(200, 120, 0)),
+        )
+        .id_salt("unmanaged_folders")
+        .show(ui, |ui| {
+            if ui.small_button("Repair All").clicked() {
+                to_repair = self.unmanaged.clone();
+            }
+            egui::Grid::new("unmanaged_grid")
+                .num_columns(3)
+                .spacing([12.0, 4.0])
+                .show(ui, |ui| {
+                    for folder in &self.unmanaged {
+                        ui.label(RichText::new(&folder.issue_key).strong());
+                        ui.colored_label(
+                            Color32::GRAY,
+                            if folder.corrupt { "control file unreadable" } else { "no control file" },
+                        );
+                        if ui.small_button("Repair").clicked() {
+                            to_repair.push(folder.clone());
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
 
-                            let status_color =
-                                if is_closed { Color32::from_rgb(200, 60, 60) } else { Color32::from_rgb(60, 180, 60) };
-                            ui.colored_label(status_color, &ctrl.issue_status);
+        for folder in to_repair {
+            self.repair_control_file(folder, ctx);
+        }
+    }
 
-                            ui.label(format_size(incident.folder_size));
+    /// Fetches the issue named by the folder and rebuilds its control file.
+    fn repair_control_file(&self, folder: UnmanagedFolder, ctx: &egui::Context) {
+        let config = self.config.clone();
+        let results = Arc::clone(&self.repair_results);
+        let ctx = ctx.clone();
 
-                            let elapsed = chrono::Utc::now()
-                                .signed_duration_since(ctrl.last_checked);
-                            ui.label(format_duration(elapsed));
+        self.runtime.spawn(async move {
+            let client = JiraClient::new(config);
+            let result = client.fetch_issue(&folder.issue_key).await.map_err(String::from);
+            results.lock().unwrap().push((folder, result));
+            ctx.request_repaint();
+        });
+    }
 
-                            ui.horizontal(|ui| {
-                                if ui.button("Check").clicked() {
-                                    to_check = Some(key.clone());
-                                }
-                                if ui.button("Open").clicked() {
-                                    to_open = Some(key.clone());
-                                }
-                            });
+    fn start_reconcile(&mut self, id: IncidentId, ctx: &egui::Context) {
+        self.incidents_scan_status = format!("Comparing {} with JIRA...", id.1);
+        let config = self.config.clone();
+        let slot = Arc::clone(&self.reconcile_result);
+        let ctx = ctx.clone();
 
-                            if is_closed || ctrl.marked_for_deletion {
-                                if ui
-                                    .button(
-                                        RichText::new("Delete ⚠").color(Color32::RED),
-                                    )
-                                    .clicked()
-                                {
-                                    to_delete = Some(key.clone());
-                                }
-                            } else {
-                                ui.label("");
-                            }
+        self.runtime.spawn(async move {
+            let client = JiraClient::new(config);
+            let result = client.fetch_issue(&id.1).await.map_err(String::from);
+            *slot.lock().unwrap() = Some((id, result));
+            ctx.request_repaint();
+        });
+    }
 
-                            ui.end_row();
-                        }
+    fn render_reconcile(&mut self, ctx: &egui::Context) {
+        let finished = self.reconcile_result.lock().unwrap().take();
+        if let Some(((root, key), result)) = finished {
+            match result {
+                Ok(issue) => {
+                    let storage = self.incident_storage(&root);
+                    let local = storage.local_files(&key);
+                    let ctrl = storage.load_control_file(&key);
+                    let saved_files = ctrl.as_ref().map(|c| c.saved_files.clone()).unwrap_or_default();
+                    let report =
+                        reconcile(&issue.attachments, &local, &saved_files, |a| storage.saved_filename(&key, a));
+                    let items = report
+                        .missing
+                        .iter()
+                        .chain(report.size_mismatches.iter().map(|(a, _)| a))
+                        .map(|a| DownloadItem::new(a.clone()))
+                        .collect();
+                    self.incidents_scan_status = format!("Compared {key} with JIRA.");
+                    self.reconcile_view = Some(ReconcileView {
+                        root,
+                        issue_key: key,
+                        report,
+                        items,
+                        flagged: ctrl.map(|c| c.flagged_orphans).unwrap_or_default(),
                     });
-            });
-
-            // 4. Apply collected actions (self is free again)
-            if let Some(key) = to_check {
-                self.check_single_status(&key, ctx);
-            }
-            if let Some(key) = to_open {
-                let storage = StorageManager::new(self.config.download_dir.clone());
-                storage.open_folder(&key);
-            }
-            if let Some(key) = to_delete {
-                self.delete_confirm = Some(key);
+                }
+                Err(e) => {
+                    self.incidents_scan_status = format!("Compare failed for {key}: {e}");
+                }
             }
         }
 
-        // 5. Deletion confirmation dialog
-        if let Some(key) = self.delete_confirm.clone() {
-            let mut confirmed = false;
-            let mut cancelled = false;
+        let Some(view) = self.reconcile_view.as_mut() else {
+            return;
+        };
+        let mut open = true;
+        let mut download_all = false;
+        let mut flag: Option<String> = None;
 
-            egui::Window::new("Confirm Deletion")
-                .collapsible(false)
-                .resizable(false)
-                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
-                .show(ctx, |ui| {
-                    ui.label(format!(
-                        "Delete folder for {key}? This cannot be undone."
-                    ));
-                    ui.horizontal(|ui| {
-                        if ui.button("Yes, Delete").clicked() {
-                            confirmed = true;
+        egui::Window::new(format!("Compare {} with JIRA", view.issue_key))
+            .collapsible(false)
+            .resizable(true)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                let r = &view.report;
+                ui.label(format!(
+                    "{} matched, {} missing locally, {} size mismatch(es), {} orphan(s)",
+                    r.matched,
+                    r.missing.len(),
+                    r.size_mismatches.len(),
+                    r.orphans.len()
+                ));
+                ui.separator();
+
+                egui::ScrollArea::vertical()
+                    .id_salt("reconcile_scroll")
+                    .max_height(360.0)
+                    .show(ui, |ui| {
+                        if !view.items.is_empty() {
+                            ui.label(RichText::new("Not downloaded / size differs").strong());
+                            egui::Grid::new("reconcile_missing_grid")
+                                .num_columns(3)
+                                .spacing([12.0, 4.0])
+                                .striped(true)
+                                .show(ui, |ui| {
+                                    for item in &view.items {
+                                        ui.label(&item.attachment.filename);
+                                        let local_size = r
+                                            .size_mismatches
+                                            .iter()
+                                            .find(|(a, _)| a.id == item.attachment.id)
+                                            .map(|(_, f)| f.size);
+                                        match local_size {
+                                            Some(local) => ui.colored_label(
+                                                Color32::from_rgb(200, 120, 0),
+                                                format!(
+                                                    "JIRA {} / disk {}",
+                                                    format_size(item.attachment.size),
+                                                    format_size(local)
+                                                ),
+                                            ),
+                                            None => ui.label(format_size(item.attachment.size)),
+                                        };
+                                        ui.label(item.current_state().label());
+                                        ui.end_row();
+                                    }
+                                });
+                            if ui.button("Download All Missing").clicked() {
+                                download_all = true;
+                            }
+                            ui.add_space(8.0);
                         }
-                        if ui.button("Cancel").clicked() {
-                            cancelled = true;
+
+                        if !r.orphans.is_empty() {
+                            ui.label(RichText::new("On disk but not in JIRA").strong());
+                            egui::Grid::new("reconcile_orphans_grid")
+                                .num_columns(3)
+                                .spacing([12.0, 4.0])
+                                .striped(true)
+                                .show(ui, |ui| {
+                                    for file in &r.orphans {
+                                        let rel = file.relative_path();
+                                        ui.label(&rel).on_hover_text(file.path.display().to_string());
+                                        ui.label(format_size(file.size));
+                                        if view.flagged.contains(&rel) {
+                                            ui.colored_label(Color32::GRAY, "Flagged");
+                                        } else if ui.small_button("Flag").clicked() {
+                                            flag = Some(rel.clone());
+                                        }
+                                        ui.end_row();
+                                    }
+                                });
+                        }
+
+                        if r.missing.is_empty() && r.size_mismatches.is_empty() && r.orphans.is_empty() {
+                            ui.colored_label(Color32::from_rgb(60, 180, 60), "Folder matches JIRA ✓");
                         }
                     });
-                });
+            });
 
-            if confirmed {
-                let storage = StorageManager::new(self.config.download_dir.clone());
-                match storage.delete_folder(&key) {
-                    Ok(_) => {
-                        self.incidents.retain(|i| i.control.issue_key != key);
-                        self.incidents_scan_status = format!("Deleted folder for {key}.");
-                    }
-                    Err(e) => {
-                        self.incidents_scan_status = format!("Delete failed: {e}");
-                    }
+        let key = view.issue_key.clone();
+        if let Some(rel) = flag {
+            view.flagged.push(rel.clone());
+            let storage = StorageManager::new(view.root.clone());
+            let _ = storage.update_control_file(&key, |ctrl| {
+                if !ctrl.flagged_orphans.contains(&rel) {
+                    ctrl.flagged_orphans.push(rel);
                 }
-                self.delete_confirm = None;
-            } else if cancelled {
-                self.delete_confirm = None;
+            });
+        }
+        if download_all {
+            for item in &mut view.items {
+                item.selected = true;
             }
+            let items = view.items.clone();
+            // Into the folder the incident was found in, not necessarily `download_dir`.
+            let mut config = self.config.clone();
+            config.download_dir = view.root.clone();
+            self.download_manager
+                .start_all_downloads(&items, &key, &config, ctx.clone());
+            self.watch_batch(key.clone(), &items);
+        }
+        if !open {
+            self.reconcile_view = None;
         }
     }
 
-    fn check_single_status(&self, issue_key: &str, ctx: &egui::Context) {
+    fn check_single_status(&self, id: IncidentId, ctx: &egui::Context) {
         let config = self.config.clone();
-        let key = issue_key.to_string();
         let updates = Arc::clone(&self.check_status);
         let ctx = ctx.clone();
 
         self.runtime.spawn(async move {
             let client = JiraClient::new(config);
-            let result = client.fetch_issue_status(&key).await;
-            updates.lock().unwrap().push((key, result));
+            let result = client.fetch_issue_status(&id.1).await;
+            updates.lock().unwrap().push((id, result));
             ctx.request_repaint();
         });
     }
 
     fn check_all_statuses(&self, ctx: &egui::Context) {
         for incident in &self.incidents {
-            self.check_single_status(&incident.control.issue_key, ctx);
+            self.check_single_status(incident.id(), ctx);
         }
     }
 
-    fn delete_all_marked(&mut self) {
-        let storage = StorageManager::new(self.config.download_dir.clone());
-        let keys: Vec<String> = self
-            .incidents
+    fn export_inventory(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .set_file_name("incident_inventory.json")
+            .add_filter("JSON", &["json"])
+            .add_filter("CSV", &["csv"])
+            .save_file()
+        else {
+            return;
+        };
+
+        let format = ExportFormat::from_path(&path);
+        self.incidents_scan_status = match export_inventory(
+            &self.incidents,
+            &path,
+            format,
+            &self.config.closed_statuses,
+        ) {
+            Ok(()) => format!(
+                "Exported {} incident(s) to {}.",
+                self.incidents.len(),
+                path.display()
+            ),
+            Err(e) => format!("Export failed: {e}"),
+        };
+    }
+
+    fn marked_ids(&self) -> Vec<IncidentId> {
+        self.incidents
             .iter()
-            .filter(|i| i.control.marked_for_deletion || i.control.is_closed())
-            .map(|i| i.control.issue_key.clone())
-            .collect();
+            .filter(|i| {
+                i.control.marked_for_deletion
+                    || i.control.is_closed(&self.config.closed_statuses)
+            })
+            .map(IncidentFolder::id)
+            .collect()
+    }
+
+    fn folders_size(&self, ids: &[IncidentId]) -> u64 {
+        self.incidents
+            .iter()
+            .filter(|i| ids.contains(&i.id()))
+            .map(|i| i.folder_size)
+            .sum()
+    }
+
+    /// Some(true) for open incidents only, Some(false) for closed only.
+    fn incident_status_filter(&self) -> Option<bool> {
+        match (self.incident_open_only, self.incident_closed_only) {
+            (true, _) => Some(true),
+            (_, true) => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Adds or removes one tag, in the control file and the loaded list.
+    fn edit_incident_tag(&mut self, (root, issue_key): &IncidentId, tag: &str, add: bool) {
+        let result = self.incident_storage(root).update_control_file(issue_key, |ctrl| {
+            ctrl.tags.retain(|t| !t.eq_ignore_ascii_case(tag));
+            if add {
+                ctrl.tags.push(tag.to_string());
+                ctrl.tags.sort();
+            }
+            ctrl.tags.clone()
+        });
+        match result {
+            Ok(tags) => {
+                if let Some(incident) = self
+                    .incidents
+                    .iter_mut()
+                    .find(|i| i.root == *root && i.control.issue_key == *issue_key)
+                {
+                    incident.control.tags = tags;
+                }
+                // Don't leave the list filtered by a tag nobody has any more.
+                if let Some(filter) = &self.incident_tag_filter {
+                    if !all_incident_tags(&self.incidents).contains(filter) {
+                        self.incident_tag_filter = None;
+                    }
+                }
+            }
+            Err(e) => self.incidents_scan_status = format!("Could not update tags of {issue_key}: {e}"),
+        }
+    }
+
+    /// Storage rooted at the scan root an incident was found under.
+    fn incident_storage(&self, root: &std::path::Path) -> StorageManager {
+        StorageManager::new(root.to_path_buf()).with_filename_template(&self.config.filename_template)
+    }
+
+    /// Moves each folder into the configured archive directory; folders that
+    /// fail to move stay listed.
+    fn archive_folders(&mut self, ids: &[IncidentId]) {
+        let Some(archive_dir) = self.config.archive_dir.clone() else {
+            return;
+        };
+        let mut archived: Vec<IncidentId> = Vec::new();
+        let mut errors: Vec<String> = Vec::new();
+
+        for (root, key) in ids {
+            match self.incident_storage(root).archive_folder(key, &archive_dir) {
+                Ok(_) => archived.push((root.clone(), key.clone())),
+                Err(e) => errors.push(format!("{key}: {e}")),
+            }
+        }
+
+        self.incidents.retain(|i| !archived.contains(&i.id()));
+
+        self.incidents_scan_status = if errors.is_empty() {
+            format!("Archived {} folder(s) to {}.", archived.len(), archive_dir.display())
+        } else {
+            format!("Archived {}, errors: {}", archived.len(), errors.join("; "))
+        };
+    }
+
+    /// Moves the folders to the recycle bin. Those it refuses stay in place
+    /// and are offered for permanent deletion.
+    fn delete_folders(&mut self, ids: &[IncidentId]) {
+        let mut deleted: Vec<IncidentId> = Vec::new();
+        let mut refused: Vec<IncidentId> = Vec::new();
+
+        for (root, key) in ids {
+            match self.incident_storage(root).delete_folder(key) {
+                Ok(()) => deleted.push((root.clone(), key.clone())),
+                Err(e) => {
+                    log::warn!("{key}: {e}");
+                    refused.push((root.clone(), key.clone()));
+                }
+            }
+        }
+
+        self.incidents.retain(|i| !deleted.contains(&i.id()));
+
+        let mut status = format!("Moved {} folder(s) to the recycle bin.", deleted.len());
+        if !refused.is_empty() {
+            status.push_str(&format!(" ⚠ {} folder(s) were left in place.", refused.len()));
+            self.permanent_delete_confirm = Some(refused);
+        }
+        self.incidents_scan_status = status;
+    }
 
-        let mut deleted = 0;
+    fn delete_folders_permanently(&mut self, ids: &[IncidentId]) {
+        let mut deleted: Vec<IncidentId> = Vec::new();
         let mut errors: Vec<String> = Vec::new();
 
-        for key in &keys {
-            match storage.delete_folder(key) {
-                Ok(_) => deleted += 1,
+        for (root, key) in ids {
+            match self.incident_storage(root).delete_folder_permanently(key) {
+                Ok(()) => deleted.push((root.clone(), key.clone())),
                 Err(e) => errors.push(format!("{key}: {e}")),
             }
         }
 
-        self.incidents.retain(|i| !keys.contains(&i.control.issue_key));
+        self.incidents.retain(|i| !deleted.contains(&i.id()));
 
         self.incidents_scan_status = if errors.is_empty() {
-            format!("Deleted {deleted} folder(s).")
+            format!("Deleted {} folder(s) permanently.", deleted.len())
         } else {
-            format!("Deleted {deleted}, errors: {}", errors.join("; "))
+            format!("Deleted {} permanently, errors: {}", deleted.len(), errors.join("; "))
         };
     }
 }
 
 impl eframe::App for App {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        let prev_tab = self.tab.clone();
+        self.track_window_geometry(ctx);
+        let mut profile_switched = None;
+
+        // App-wide shortcuts; both carry a modifier, so they never clash with typing.
+        let (focus_input, open_settings) = ctx.input_mut(|i| {
+            (
+                i.consume_shortcut(&SHORTCUT_FOCUS_INPUT),
+                i.consume_shortcut(&SHORTCUT_SETTINGS),
+            )
+        });
+        if focus_input {
+            self.tab = Tab::Incident;
+            self.focus_incident_input = true;
+        }
+        if open_settings {
+            self.tab = Tab::Settings;
+        }
+
+        let (hovering, dropped) =
+            ctx.input(|i| (!i.raw.hovered_files.is_empty(), i.raw.dropped_files.clone()));
+        if hovering {
+            paint_drop_overlay(ctx);
+        }
+        if !dropped.is_empty() {
+            self.handle_drop(&dropped, ctx);
+        }
+
         egui::TopBottomPanel::top("tabs").show(ctx, |ui| {
             ui.horizontal(|ui| {
                 ui.selectable_value(&mut self.tab, Tab::Incident, "Incident");
@@ -770,18 +4177,147 @@ impl eframe::App for App {
                     Tab::IncidentsManager,
                     "Incidents Manager",
                 );
+                ui.selectable_value(&mut self.tab, Tab::History, "History");
                 ui.selectable_value(&mut self.tab, Tab::Settings, "⚙ Settings");
+
+                if self.config.profiles.len() > 1 {
+                    ui.separator();
+                    let current = self.config.active_profile;
+                    let mut chosen = current;
+                    egui::ComboBox::from_id_salt("profile_picker")
+                        .selected_text(&self.config.profiles[current].name)
+                        .show_ui(ui, |ui| {
+                            for (idx, profile) in self.config.profiles.iter().enumerate() {
+                                ui.selectable_value(&mut chosen, idx, &profile.name);
+                            }
+                        });
+                    if chosen != current {
+                        profile_switched = Some(chosen);
+                    }
+                }
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.label("⌨").on_hover_ui(shortcut_cheat_sheet);
+                    if let Some(info) = self.update_available.lock().unwrap().as_ref() {
+                        ui.hyperlink_to(format!("Update available ({})", info.version), &info.url);
+                    }
+                });
             });
         });
 
+        if let Some(index) = profile_switched {
+            self.switch_profile(index);
+        }
+        if prev_tab != self.tab {
+            self.on_tab_switch(&prev_tab, ctx);
+        }
+
+        self.process_bulk_results(ctx);
+        self.check_batch_complete();
+        self.render_status_bar(ctx);
+
         egui::CentralPanel::default().show(ctx, |ui| {
             match self.tab.clone() {
                 Tab::Settings => self.render_settings(ui),
                 Tab::Incident => self.render_incident(ui, ctx),
                 Tab::IncidentsManager => self.render_incidents_manager(ui, ctx),
+                Tab::History => self.render_history(ui),
             }
         });
+
+        let progress = if self.config.taskbar_progress {
+            self.taskbar_progress()
+        } else {
+            TaskbarProgress::Idle
+        };
+        self.taskbar.set(frame, progress);
+    }
+
+    fn on_exit(&mut self) {
+        // Only the UI state file is written, so unsaved Settings edits stay unsaved.
+        self.config.last_tab = Some(self.tab.as_start());
+        self.config.window = self.window_geometry;
+        let _ = self.config.save_ui_state();
+    }
+}
+
+/// Issue key behind a dropped item: a link shortcut (`.url`, `.webloc`,
+/// `.desktop`, as browsers create when a link is dragged out), or a file or
+/// folder named after the issue, such as an issue folder from the download dir.
+fn dropped_issue_key(file: &egui::DroppedFile) -> Option<String> {
+    let name = match &file.path {
+        Some(path) => path.file_name()?.to_string_lossy().to_string(),
+        None => file.name.clone(),
+    };
+    let ext = std::path::Path::new(&name)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_lowercase);
+
+    if matches!(ext.as_deref(), Some("url" | "webloc" | "desktop")) {
+        let text = match (&file.path, &file.bytes) {
+            (Some(path), _) => std::fs::read_to_string(path).ok()?,
+            (None, Some(bytes)) => String::from_utf8_lossy(bytes).to_string(),
+            (None, None) => return None,
+        };
+        // `URL=https://…` in .url/.desktop, `<string>https://…</string>` in .webloc.
+        let start = text.find("http")?;
+        let url: String = text[start..]
+            .chars()
+            .take_while(|c| !c.is_whitespace() && *c != '<' && *c != '"')
+            .collect();
+        return parse_issue_key(&url);
     }
+
+    let stem = std::path::Path::new(&name).file_stem()?.to_string_lossy().to_string();
+    parse_issue_key(&name).or_else(|| parse_issue_key(&stem))
+}
+
+fn paint_drop_overlay(ctx: &egui::Context) {
+    let painter = ctx.layer_painter(egui::LayerId::new(
+        egui::Order::Foreground,
+        egui::Id::new("drop_overlay"),
+    ));
+    let rect = ctx.screen_rect();
+    painter.rect_filled(rect, 0.0, Color32::from_black_alpha(160));
+    painter.text(
+        rect.center(),
+        egui::Align2::CENTER_CENTER,
+        "Drop a JIRA link or issue folder to fetch it",
+        egui::FontId::proportional(20.0),
+        Color32::WHITE,
+    );
+}
+
+fn star_button(ui: &mut egui::Ui, starred: bool) -> egui::Response {
+    let (icon, hint) = if starred {
+        (RichText::new("★").color(Color32::GOLD), "Remove from favorites")
+    } else {
+        (RichText::new("☆").color(Color32::GRAY), "Pin to favorites")
+    };
+    ui.add(egui::Button::new(icon).frame(false)).on_hover_text(hint)
+}
+
+/// Hover card listing the keyboard shortcuts, in the platform's notation.
+fn shortcut_cheat_sheet(ui: &mut egui::Ui) {
+    ui.label(RichText::new("Keyboard shortcuts").strong());
+    egui::Grid::new("shortcuts_grid").num_columns(2).show(ui, |ui| {
+        for (shortcut, action) in [
+            (Some(SHORTCUT_FOCUS_INPUT), "Focus the incident input"),
+            (None, "Fetch the issue (in the input)"),
+            (Some(SHORTCUT_SELECT_ALL), "Select all attachments"),
+            (Some(SHORTCUT_DOWNLOAD), "Download selected"),
+            (Some(SHORTCUT_SETTINGS), "Open Settings"),
+        ] {
+            let keys = match shortcut {
+                Some(s) => ui.ctx().format_shortcut(&s),
+                None => "Enter".to_string(),
+            };
+            ui.label(RichText::new(keys).monospace());
+            ui.label(action);
+            ui.end_row();
+        }
+    });
 }
 
 fn status_color(status: &str) -> Color32 {
@@ -795,6 +4331,277 @@ fn status_color(status: &str) -> Color32 {
     }
 }
 
+/// Shortens to `max` characters (not bytes, so multi-byte text never splits mid-char).
+fn truncate_chars(s: &str, max: usize) -> String {
+    if s.chars().count() > max {
+        format!("{}...", s.chars().take(max).collect::<String>())
+    } else {
+        s.to_string()
+    }
+}
+
+/// e.g. "4 images (3.1 MB), 2 logs (12.0 KB), 1 other (200 B)"
+fn kind_summary(items: &[DownloadItem]) -> String {
+    let mut groups: std::collections::BTreeMap<AttachmentKind, (usize, u64)> =
+        std::collections::BTreeMap::new();
+    for item in items {
+        let entry = groups.entry(item.attachment.kind()).or_default();
+        entry.0 += 1;
+        entry.1 += item.attachment.size;
+    }
+    groups
+        .into_iter()
+        .map(|(kind, (n, size))| format!("{n} {} ({})", kind.label(n), format_size(size)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// What was clicked in the attachment rows this frame.
+#[derive(Default)]
+struct RowClicks {
+    selection_changed: bool,
+    /// A saved file to open.
+    open: Option<std::path::PathBuf>,
+    /// Id of an attachment to download again.
+    redownload: Option<String>,
+}
+
+/// Column titles of the attachments grid; returns a sort column that was clicked.
+fn attachment_grid_header(
+    ui: &mut egui::Ui,
+    sort: Option<(AttachmentSort, bool)>,
+) -> Option<AttachmentSort> {
+    let mut clicked = None;
+    ui.label("");
+    ui.label("");
+    for (column, title) in [
+        (AttachmentSort::Name, "Filename"),
+        (AttachmentSort::Size, "Size"),
+        (AttachmentSort::Date, "Date"),
+    ] {
+        let arrow = match sort {
+            Some((c, true)) if c == column => " ⏶",
+            Some((c, false)) if c == column => " ⏷",
+            _ => "",
+        };
+        let header = RichText::new(format!("{title}{arrow}")).strong();
+        if ui.add(egui::Button::new(header).frame(false)).clicked() {
+            clicked = Some(column);
+        }
+    }
+    ui.label(RichText::new("Progress").strong());
+    ui.label(RichText::new("Status").strong());
+    ui.end_row();
+    clicked
+}
+
+/// One attachment in the attachments grid.
+fn attachment_row(
+    ui: &mut egui::Ui,
+    item: &mut DownloadItem,
+    previews: &mut ThumbnailCache,
+    config: &AppConfig,
+    clicks: &mut RowClicks,
+) {
+    let state = item.current_state();
+    clicks.selection_changed |= ui.checkbox(&mut item.selected, "").changed();
+    match previews.get(ui.ctx(), &item.attachment, config) {
+        Some(texture) => {
+            ui.add(egui::Image::new(texture).max_size(egui::vec2(48.0, 48.0)));
+        }
+        None => {
+            ui.label("");
+        }
+    }
+    ui.horizontal(|ui| {
+        match &item.attachment.source {
+            AttachmentSource::Comment { author } => {
+                ui.label(format!("💬 {}", item.attachment.filename))
+                    .on_hover_text(format!("Added in a comment by {author}"));
+            }
+            AttachmentSource::Issue => {
+                ui.label(&item.attachment.filename);
+            }
+        }
+        if ui
+            .small_button("Copy URL")
+            .on_hover_text("Copy the JIRA download link — opening it requires being logged in to JIRA")
+            .clicked()
+        {
+            ui.ctx().copy_text(item.attachment.content.clone());
+        }
+    });
+    ui.label(format_size(item.attachment.size));
+    ui.label(item.attachment.created.format("%Y-%m-%d").to_string());
+    let frac = state.progress_fraction().unwrap_or(0.0);
+    ui.add(egui::ProgressBar::new(frac).desired_width(120.0).show_percentage());
+    let label = state.label();
+    match &state {
+        FileState::Done | FileState::AlreadyOnDisk => {
+            ui.horizontal(|ui| {
+                ui.colored_label(Color32::from_rgb(60, 180, 60), &label);
+                if let Some(path) = item.saved_path() {
+                    if ui
+                        .small_button("Open")
+                        .on_hover_text(normal_path(&path).display().to_string())
+                        .clicked()
+                    {
+                        clicks.open = Some(path);
+                    }
+                }
+                if ui
+                    .small_button("Re-download")
+                    .on_hover_text("Replace the saved copy with a fresh download")
+                    .clicked()
+                {
+                    clicks.redownload = Some(item.attachment.id.clone());
+                }
+            });
+        }
+        FileState::ThumbnailDone => {
+            ui.colored_label(Color32::from_rgb(60, 180, 60), &label)
+                .on_hover_text("The original is not downloaded yet");
+        }
+        FileState::Error(_) => {
+            ui.colored_label(Color32::from_rgb(200, 60, 60), &label);
+        }
+        FileState::Cancelled => {
+            ui.colored_label(Color32::from_rgb(200, 120, 0), &label);
+        }
+        _ => {
+            ui.label(&label);
+        }
+    };
+    ui.end_row();
+}
+
+/// Reorders in place; items keep their selection and download state.
+fn sort_items(items: &mut [DownloadItem], sort: Option<(AttachmentSort, bool)>) {
+    let Some((column, ascending)) = sort else {
+        return;
+    };
+    items.sort_by(|a, b| {
+        let (a, b) = (&a.attachment, &b.attachment);
+        let ord = match column {
+            AttachmentSort::Name => a.filename.to_lowercase().cmp(&b.filename.to_lowercase()),
+            AttachmentSort::Size => a.size.cmp(&b.size),
+            AttachmentSort::Date => a.created.cmp(&b.created),
+        };
+        if ascending { ord } else { ord.reverse() }
+    });
+}
+
+fn sort_incidents(incidents: &mut [IncidentFolder], (column, ascending): (IncidentSort, bool)) {
+    incidents.sort_by(|a, b| {
+        let (ca, cb) = (&a.control, &b.control);
+        let ord = match column {
+            IncidentSort::Key => ca.issue_key.cmp(&cb.issue_key),
+            IncidentSort::Status => ca
+                .issue_status
+                .to_lowercase()
+                .cmp(&cb.issue_status.to_lowercase())
+                .then_with(|| ca.issue_key.cmp(&cb.issue_key)),
+            IncidentSort::Size => a.folder_size.cmp(&b.folder_size),
+            IncidentSort::LastChecked => ca.last_checked.cmp(&cb.last_checked),
+        };
+        if ascending { ord } else { ord.reverse() }
+    });
+}
+
+/// (files, files done, files failed, bytes downloaded, bytes total) across a
+/// set of rows. Failed files add nothing to the bytes downloaded, so the bar
+/// only reaches the end when every file made it. Cancelled files count as
+/// neither done nor failed: they are simply not finished.
+fn batch_totals<'a>(items: impl IntoIterator<Item = &'a DownloadItem>) -> (usize, usize, usize, u64, u64) {
+    let (mut count, mut finished, mut failed, mut downloaded, mut total) = (0, 0, 0, 0u64, 0u64);
+    for item in items {
+        count += 1;
+        total += item.attachment.size;
+        match item.current_state() {
+            FileState::Downloading { downloaded: d, .. } => downloaded += d,
+            FileState::Done | FileState::ThumbnailDone => {
+                downloaded += item.attachment.size;
+                finished += 1;
+            }
+            FileState::Error(_) => failed += 1,
+            FileState::Cancelled | FileState::Pending | FileState::Queued | FileState::AlreadyOnDisk => {}
+        }
+    }
+    (count, finished, failed, downloaded, total)
+}
+
+/// ", N failed" for progress labels, or nothing.
+fn failed_suffix(failed: usize) -> String {
+    if failed > 0 {
+        format!(", {failed} failed")
+    } else {
+        String::new()
+    }
+}
+
+fn passes_auto_select(
+    item: &DownloadItem,
+    excluded: &std::collections::BTreeSet<AttachmentKind>,
+    (min_mb, max_mb): (f64, f64),
+) -> bool {
+    let mb = item.attachment.size as f64 / 1_048_576.0;
+    !excluded.contains(&item.attachment.kind()) && mb >= min_mb && (max_mb <= 0.0 || mb <= max_mb)
+}
+
+/// Incidents Manager search: key/summary substring plus the quick filters.
+/// `open`: Some(true) keeps only open incidents, Some(false) only closed ones.
+fn incident_matches(
+    incident: &IncidentFolder,
+    filter: &str,
+    open: Option<bool>,
+    marked_only: bool,
+    tag: Option<&str>,
+    closed_statuses: &[String],
+) -> bool {
+    let ctrl = &incident.control;
+    if open.is_some_and(|open| open == ctrl.is_closed(closed_statuses)) {
+        return false;
+    }
+    if marked_only && !ctrl.marked_for_deletion {
+        return false;
+    }
+    if tag.is_some_and(|tag| !ctrl.tags.iter().any(|t| t == tag)) {
+        return false;
+    }
+    let filter = filter.trim().to_lowercase();
+    filter.is_empty()
+        || ctrl.issue_key.to_lowercase().contains(&filter)
+        || ctrl.issue_summary.to_lowercase().contains(&filter)
+}
+
+/// Every tag used by any incident, sorted and without duplicates.
+fn all_incident_tags(incidents: &[IncidentFolder]) -> Vec<String> {
+    let mut tags: Vec<String> = incidents
+        .iter()
+        .flat_map(|i| i.control.tags.iter().cloned())
+        .collect();
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+fn matches_filter(item: &DownloadItem, filter: &str) -> bool {
+    filter.is_empty()
+        || item
+            .attachment
+            .filename
+            .to_lowercase()
+            .contains(&filter.trim().to_lowercase())
+}
+
+/// Not on disk yet: never started, failed, or only the thumbnail fetched.
+fn is_missing(state: &FileState) -> bool {
+    matches!(
+        state,
+        FileState::Pending | FileState::Cancelled | FileState::Error(_) | FileState::ThumbnailDone
+    )
+}
+
 fn format_duration(d: chrono::Duration) -> String {
     let secs = d.num_seconds().unsigned_abs();
     if secs < 60 {
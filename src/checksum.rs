@@ -0,0 +1,60 @@
+use std::io::Read;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// Digest used to detect corrupted downloads. Kept as an enum so stronger
+/// algorithms can be added without changing stored control files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChecksumAlgorithm {
+    #[default]
+    Md5,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checksum {
+    pub algorithm: ChecksumAlgorithm,
+    /// Lowercase hex digest.
+    pub hex: String,
+}
+
+impl Checksum {
+    /// Hashes the file in chunks, so multi-GB attachments never sit in memory.
+    pub fn of_file(path: &Path, algorithm: ChecksumAlgorithm) -> Result<Self, String> {
+        use md5::Digest;
+
+        let mut file = std::fs::File::open(path)
+            .map_err(|e| format!("Failed to open {} for hashing: {e}", path.display()))?;
+        let mut buf = vec![0u8; 64 * 1024];
+        let hex = match algorithm {
+            ChecksumAlgorithm::Md5 => {
+                let mut hasher = md5::Md5::new();
+                loop {
+                    let n = file
+                        .read(&mut buf)
+                        .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+                    if n == 0 {
+                        break;
+                    }
+                    hasher.update(&buf[..n]);
+                }
+                hasher
+                    .finalize()
+                    .iter()
+                    .map(|b| format!("{b:02x}"))
+                    .collect()
+            }
+        };
+        Ok(Self { algorithm, hex })
+    }
+
+    /// Re-hashes `path` and compares it with this digest.
+    pub fn verify(&self, path: &Path) -> Result<(), String> {
+        let actual = Self::of_file(path, self.algorithm)?;
+        if actual.hex == self.hex {
+            Ok(())
+        } else {
+            Err(format!("checksum mismatch: got {} expected {}", actual.hex, self.hex))
+        }
+    }
+}
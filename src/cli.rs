@@ -0,0 +1,160 @@
+use std::io::Write;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::config::AppConfig;
+use crate::downloader::{DownloadItem, DownloadManager, FileState};
+use crate::jira::{issue_browse_url, parse_issue_key, JiraClient};
+use crate::storage::{normal_path, ControlFile, StorageManager};
+
+const USAGE: &str = "Usage: jira-downloader --issue <KEY or URL> --download";
+/// How often progress is printed while downloads run.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Command-line request, if the arguments ask for headless mode.
+pub struct CliArgs {
+    issue: String,
+    download: bool,
+}
+
+impl CliArgs {
+    /// `None` means no CLI flags were given and the GUI should start.
+    pub fn parse(args: &[String]) -> Option<Result<Self, String>> {
+        if args.is_empty() {
+            return None;
+        }
+        let mut issue = None;
+        let mut download = false;
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--issue" => match iter.next() {
+                    Some(value) => issue = Some(value.clone()),
+                    None => return Some(Err(format!("--issue needs a value\n{USAGE}"))),
+                },
+                "--download" => download = true,
+                "--help" | "-h" => return Some(Err(USAGE.to_string())),
+                other => return Some(Err(format!("Unknown argument: {other}\n{USAGE}"))),
+            }
+        }
+        Some(match issue {
+            Some(issue) => Ok(Self { issue, download }),
+            None => Err(format!("--issue is required\n{USAGE}")),
+        })
+    }
+}
+
+/// Fetches the issue and, with `--download`, saves every attachment that
+/// isn't on disk yet, using the same config, credentials and folder layout
+/// as the GUI. Returns the process exit code.
+pub fn run(runtime: &Arc<tokio::runtime::Runtime>, args: CliArgs) -> i32 {
+    match runtime.block_on(run_async(runtime, args)) {
+        Ok(()) => 0,
+        Err(e) => {
+            log::error!("Command-line run failed: {e}");
+            eprintln!("Error: {e}");
+            1
+        }
+    }
+}
+
+async fn run_async(runtime: &Arc<tokio::runtime::Runtime>, args: CliArgs) -> Result<(), String> {
+    let config = AppConfig::load();
+    if !config.has_credentials() {
+        return Err("No JIRA connection configured — set it up in the GUI first.".to_string());
+    }
+    let key = parse_issue_key(&args.issue)
+        .ok_or_else(|| format!("Invalid issue key or URL: {}", args.issue))?;
+
+    let client = JiraClient::new(config.clone());
+    let issue = client.fetch_issue(&key).await?;
+    println!("{} — {} [{}]", issue.key, issue.summary, issue.status);
+    println!("{} attachment(s)", issue.attachments.len());
+    if !args.download {
+        return Ok(());
+    }
+
+    let storage = StorageManager::new(config.download_dir.clone())
+        .with_filename_template(&config.filename_template);
+    storage.upsert_control_file(
+        &issue.key,
+        || ControlFile::new(&issue.key, &issue.summary, &issue.status),
+        |ctrl| {
+            ctrl.issue_summary = issue.summary.clone();
+            ctrl.issue_status = issue.status.clone();
+            ctrl.last_checked = chrono::Utc::now();
+        },
+    )?;
+    if config.write_issue_shortcut {
+        let url = issue_browse_url(&config.jira_url, &issue.key);
+        let _ = storage.write_issue_shortcut(&issue.key, &url);
+    }
+
+    let ctrl = storage
+        .load_control_file(&issue.key)
+        .unwrap_or_else(|| ControlFile::new(&issue.key, &issue.summary, &issue.status));
+    let items: Vec<DownloadItem> = issue
+        .attachments
+        .iter()
+        .map(|attachment| {
+            let mut item = DownloadItem::new(attachment.clone());
+            if let Some(path) = storage.existing_attachment(&issue.key, attachment, &ctrl.saved_files) {
+                item.selected = false;
+                // A copy whose recorded digest no longer matches is reported
+                // as failed rather than silently skipped.
+                match ctrl.checksums.get(&attachment.id).map(|known| known.verify(&path)) {
+                    Some(Err(e)) => *item.state.lock().unwrap() = FileState::Error(e),
+                    _ => {
+                        println!("  skip  {} (already on disk)", attachment.filename);
+                        *item.state.lock().unwrap() = FileState::AlreadyOnDisk;
+                    }
+                }
+            }
+            item
+        })
+        .collect();
+
+    // The GUI's download path, so size and checksum checks, history and
+    // naming are the same; without a window the repaint requests go nowhere.
+    let mut manager = DownloadManager::new(Arc::clone(runtime), config.max_concurrent_downloads);
+    manager.start_all_downloads(&items, &issue.key, &config, egui::Context::default());
+    let mut reported = vec![false; items.len()];
+    loop {
+        let busy = manager.is_busy();
+        for (item, reported) in items.iter().zip(&mut reported) {
+            match item.current_state() {
+                FileState::Done if !*reported => {
+                    *reported = true;
+                    let path = item.saved_path().unwrap_or_default();
+                    println!("\r  done  {}", normal_path(&path).display());
+                }
+                FileState::AlreadyOnDisk if item.selected && !*reported => {
+                    *reported = true;
+                    println!("\r  skip  {} (identical copy already on disk)", item.attachment.filename);
+                }
+                FileState::Error(e) if !*reported => {
+                    *reported = true;
+                    println!("\r  FAIL  {}: {e}", item.attachment.filename);
+                }
+                FileState::Downloading { downloaded, total } if total > 0 => {
+                    print!("\r  {:>3}%  {}", downloaded * 100 / total, item.attachment.filename);
+                    let _ = std::io::stdout().flush();
+                }
+                _ => {}
+            }
+        }
+        if !busy {
+            break;
+        }
+        tokio::time::sleep(PROGRESS_INTERVAL).await;
+    }
+    let failures = items
+        .iter()
+        .filter(|item| matches!(item.current_state(), FileState::Error(_)))
+        .count();
+
+    if failures > 0 {
+        return Err(format!("{failures} attachment(s) failed to download"));
+    }
+    Ok(())
+}
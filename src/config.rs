@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use std::sync::OnceLock;
 
 use aes_gcm::{
     aead::{Aead, KeyInit},
@@ -7,60 +8,538 @@ use aes_gcm::{
 };
 use base64::{engine::general_purpose::STANDARD as B64, Engine};
 use rand::RngCore;
-use winreg::{enums::*, RegKey};
 
-const REG_KEY_PATH: &str = "Software\\jira-downloader";
-const REG_ENC_VALUE: &str = "encryption_key";
+use crate::keystore;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CustomHeader {
+    pub name: String,
+    pub value: String,
+}
+
+/// One JIRA server's connection details.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    pub jira_url: String,
+    #[serde(default)]
+    pub auth_method: AuthMethod,
+    #[serde(default)]
+    pub email: String,
+    /// Plaintext token — never written to disk.
+    #[serde(skip)]
+    pub api_token: String,
+    #[serde(default)]
+    api_token_enc: String,
+    /// `api_token_enc` is set but couldn't be decrypted (key store locked or
+    /// unavailable); saving then keeps the blob instead of clearing it.
+    #[serde(skip)]
+    token_unreadable: bool,
+}
+
+/// How requests authenticate against JIRA.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuthMethod {
+    /// `email:api_token`, as used by JIRA Cloud.
+    #[default]
+    Basic,
+    /// Personal Access Token, as used by JIRA Server / Data Center.
+    Bearer,
+}
+
+impl AuthMethod {
+    pub fn label(self) -> &'static str {
+        match self {
+            AuthMethod::Basic => "Email + API token (Cloud)",
+            AuthMethod::Bearer => "Personal Access Token (Server/DC)",
+        }
+    }
+}
+
+/// Issue pinned to the top of the Incident tab.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FavoriteIssue {
+    pub key: String,
+    pub summary: String,
+}
+
+/// Restored window placement, in egui points. Size and position are those of
+/// the normal (un-maximized) window.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub maximized: bool,
+    /// Pixels per point when saved, to map the position back to screen pixels.
+    pub scale: f32,
+}
+
+/// What the automatic cleanup does with a closed incident folder.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RetentionAction {
+    /// Move it into `archive_dir`.
+    #[default]
+    Archive,
+    /// Send it to the recycle bin.
+    Delete,
+}
+
+impl RetentionAction {
+    pub fn label(self) -> &'static str {
+        match self {
+            RetentionAction::Archive => "Archive",
+            RetentionAction::Delete => "Delete",
+        }
+    }
+}
+
+/// How attachments are arranged inside an issue folder.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FolderLayout {
+    /// `<issue>/<file>`
+    Flat,
+    /// `<issue>/<YYYY-MM-DD>/<file>`, by upload date.
+    #[default]
+    Date,
+    /// `<issue>/images|logs|other/<file>`
+    ByType,
+}
+
+impl FolderLayout {
+    pub const ALL: [FolderLayout; 3] = [FolderLayout::Flat, FolderLayout::Date, FolderLayout::ByType];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            FolderLayout::Flat => "Flat",
+            FolderLayout::Date => "By upload date",
+            FolderLayout::ByType => "By file type",
+        }
+    }
+}
+
+/// Which tab the app opens on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StartTab {
+    /// Incident, or Settings while no JIRA URL is configured.
+    #[default]
+    Auto,
+    LastUsed,
+    Incident,
+    IncidentsManager,
+    History,
+    Settings,
+}
+
+impl StartTab {
+    pub const ALL: [StartTab; 6] = [
+        StartTab::Auto,
+        StartTab::LastUsed,
+        StartTab::Incident,
+        StartTab::IncidentsManager,
+        StartTab::History,
+        StartTab::Settings,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            StartTab::Auto => "Automatic",
+            StartTab::LastUsed => "Last used",
+            StartTab::Incident => "Incident",
+            StartTab::IncidentsManager => "Incidents Manager",
+            StartTab::History => "History",
+            StartTab::Settings => "Settings",
+        }
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
+    /// Saved JIRA servers. The active one is mirrored into the connection
+    /// fields below, which are what the rest of the app reads and edits.
+    #[serde(default)]
+    pub profiles: Vec<Profile>,
+    #[serde(default)]
+    pub active_profile: usize,
     pub jira_url: String,
+    #[serde(default)]
+    pub auth_method: AuthMethod,
+    /// Only used with `AuthMethod::Basic`.
     pub email: String,
     /// Plaintext token — never written to disk.
     #[serde(skip)]
     pub api_token: String,
     pub download_dir: PathBuf,
+    /// Replaces the `/rest` path segment for proxies that serve the API elsewhere.
+    #[serde(default)]
+    pub api_prefix: String,
+    /// Extra headers sent with every request, including downloads.
+    #[serde(default)]
+    pub custom_headers: Vec<CustomHeader>,
+    /// Explicit HTTP(S) proxy, e.g. `http://proxy.corp:8080`. Empty = use the
+    /// `HTTPS_PROXY`/`HTTP_PROXY` environment variables, if any.
+    #[serde(default)]
+    pub proxy_url: String,
+    #[serde(default)]
+    pub proxy_username: String,
+    /// Plaintext proxy password — never written to disk.
+    #[serde(skip)]
+    pub proxy_password: String,
+    /// Sent as `User-Agent` on every JIRA request. Empty = `DEFAULT_USER_AGENT`.
+    #[serde(default)]
+    pub user_agent: String,
+    /// PEM file with extra root certificates, for servers signed by a private CA.
+    #[serde(default)]
+    pub ca_bundle: Option<PathBuf>,
+    /// Skip certificate checks entirely. Last resort; Settings warns about it.
+    #[serde(default)]
+    pub accept_invalid_certs: bool,
+    #[serde(default = "default_connect_timeout")]
+    pub connect_timeout_secs: u64,
+    /// Limit for a whole API call; downloads instead fail after this long without data.
+    #[serde(default = "default_request_timeout")]
+    pub request_timeout_secs: u64,
+    /// Extra roots the Incidents Manager scans alongside `download_dir`.
+    /// New downloads always go to `download_dir`.
+    #[serde(default)]
+    pub additional_scan_dirs: Vec<PathBuf>,
+    /// Where the Incidents Manager moves closed issue folders on "Archive".
+    /// `None` leaves only permanent deletion available.
+    #[serde(default)]
+    pub archive_dir: Option<PathBuf>,
+    /// Include `archive_dir` in Incidents Manager scans.
+    #[serde(default)]
+    pub scan_archive: bool,
+    /// Opt-in background cleanup of closed incidents whose last status check
+    /// is older than `retention_days`.
+    #[serde(default)]
+    pub retention_enabled: bool,
+    #[serde(default = "default_retention_days")]
+    pub retention_days: u32,
+    #[serde(default)]
+    pub retention_action: RetentionAction,
+    /// Re-check every incident's status in the background.
+    #[serde(default)]
+    pub status_poll_enabled: bool,
+    #[serde(default = "default_status_poll_minutes")]
+    pub status_poll_minutes: u32,
+    #[serde(default)]
+    pub folder_layout: FolderLayout,
+    /// How saved attachments are named, e.g. `{key}_{date}_{name}.{ext}`.
+    /// Empty keeps JIRA's file name.
+    #[serde(default)]
+    pub filename_template: String,
+    /// With the date layout, skip the YYYY-MM-DD subfolder when all of an
+    /// issue's attachments share one date.
+    #[serde(default)]
+    pub collapse_single_date: bool,
+    /// Write a shortcut to the issue's JIRA page into each issue folder.
+    #[serde(default)]
+    pub write_issue_shortcut: bool,
+    /// Drop a fresh download when the file it would have replaced under the
+    /// same name is identical, instead of keeping a `_2` copy.
+    #[serde(default)]
+    pub skip_identical_downloads: bool,
+    /// Set each saved file's modified (and, on Windows, created) time to when
+    /// it was uploaded to JIRA instead of when it was downloaded.
+    #[serde(default)]
+    pub preserve_upload_time: bool,
+    /// Pause between starting successive downloads in a batch (0 = none).
+    #[serde(default)]
+    pub inter_file_delay_ms: u64,
+    /// Cap on the combined speed of all downloads in KiB/s (0 = unlimited).
+    #[serde(default)]
+    pub max_download_rate_kib: u64,
+    /// Downloads allowed to run at the same time; the rest wait as Queued.
+    #[serde(default = "default_max_concurrent")]
+    pub max_concurrent_downloads: usize,
+    /// Query behind the My Cases panel.
+    #[serde(default = "default_my_issues_jql")]
+    pub my_issues_jql: String,
+    /// Heading shown on the My Cases panel, to name the filter.
+    #[serde(default = "default_my_issues_title")]
+    pub my_issues_title: String,
+    /// Fetch the My Cases list as soon as the Incident tab is shown.
+    #[serde(default = "default_true")]
+    pub auto_load_my_cases: bool,
+    /// Also request each case's attachments so My Cases can show a count.
+    /// Off by default: it makes the search response much larger.
+    #[serde(default)]
+    pub my_cases_attachment_counts: bool,
+    /// Whether attachments without a remembered choice start out selected.
+    #[serde(default = "default_true")]
+    pub select_new_attachments: bool,
+    /// Abort an in-flight issue fetch when the user leaves the Incident tab.
+    #[serde(default)]
+    pub cancel_fetch_on_tab_switch: bool,
+    /// Mirror download progress on the Windows taskbar button.
+    #[serde(default = "default_true")]
+    pub taskbar_progress: bool,
+    /// Desktop notification when a batch of downloads has finished. Off by default.
+    #[serde(default)]
+    pub notify_on_batch_complete: bool,
+    /// Check once a day whether a newer release is available. Off by default.
+    #[serde(default)]
+    pub check_for_updates: bool,
+    #[serde(default = "default_update_url")]
+    pub update_check_url: String,
+    /// Record diagnostic data such as per-download timing metrics.
+    #[serde(default)]
+    pub debug_logging: bool,
+    /// Status names that count as closed for cleanup. Empty = built-in heuristic.
+    #[serde(default)]
+    pub closed_statuses: Vec<String>,
+    #[serde(default)]
+    pub start_tab: StartTab,
+    /// Tab open most recently, saved on every switch so it survives a crash;
+    /// only restored with `StartTab::LastUsed`. Kept in [`UiState`].
+    #[serde(default, skip_serializing)]
+    pub last_tab: Option<StartTab>,
+    /// Issue most recently fetched successfully; pre-filled on startup.
+    /// Kept in [`UiState`].
+    #[serde(default, skip_serializing)]
+    pub last_issue: Option<String>,
+    /// Most recently fetched issue keys, newest first, capped at `MAX_RECENT_ISSUES`.
+    /// Kept in [`UiState`].
+    #[serde(default, skip_serializing)]
+    pub recent_issues: Vec<String>,
+    /// Starred issues, in the order they were pinned. Kept in [`UiState`].
+    #[serde(default, skip_serializing)]
+    pub favorites: Vec<FavoriteIssue>,
+    /// Fetch `last_issue` again on startup instead of only pre-filling it.
+    #[serde(default = "default_true")]
+    pub reopen_last_issue: bool,
+    /// Window size and position when the app last closed. Kept in [`UiState`].
+    #[serde(default, skip_serializing)]
+    pub window: Option<WindowGeometry>,
     /// AES-256-GCM encrypted token stored in config.json.
     #[serde(default)]
     api_token_enc: String,
+    /// Encrypted proxy password, same scheme as the token.
+    #[serde(default)]
+    proxy_password_enc: String,
+    /// The proxy password blob is set but couldn't be decrypted.
+    #[serde(skip)]
+    proxy_password_unreadable: bool,
+}
+
+/// Where the app left off: window placement, last tab, recent and starred
+/// issues. Lives in `ui_state.json` next to config.json and is saved on its
+/// own, so recording it never rewrites the config or re-encrypts its secrets.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UiState {
+    #[serde(default)]
+    pub last_tab: Option<StartTab>,
+    #[serde(default)]
+    pub window: Option<WindowGeometry>,
+    #[serde(default)]
+    pub last_issue: Option<String>,
+    #[serde(default)]
+    pub recent_issues: Vec<String>,
+    #[serde(default)]
+    pub favorites: Vec<FavoriteIssue>,
+}
+
+impl UiState {
+    fn path() -> PathBuf {
+        config_dir().join("ui_state.json")
+    }
+
+    /// `None` when the file doesn't exist yet or can't be read.
+    fn load() -> Option<Self> {
+        let data = std::fs::read_to_string(Self::path()).ok()?;
+        serde_json::from_str(&data).ok()
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let path = Self::path();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create config dir: {e}"))?;
+        }
+        let data = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize UI state: {e}"))?;
+        std::fs::write(&path, data).map_err(|e| format!("Failed to write UI state: {e}"))
+    }
 }
 
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
+            profiles: Vec::new(),
+            active_profile: 0,
             jira_url: String::new(),
+            auth_method: AuthMethod::Basic,
             email: String::new(),
             api_token: String::new(),
             download_dir: default_download_dir(),
+            api_prefix: String::new(),
+            custom_headers: Vec::new(),
+            proxy_url: String::new(),
+            proxy_username: String::new(),
+            proxy_password: String::new(),
+            user_agent: String::new(),
+            ca_bundle: None,
+            accept_invalid_certs: false,
+            connect_timeout_secs: default_connect_timeout(),
+            request_timeout_secs: default_request_timeout(),
+            additional_scan_dirs: Vec::new(),
+            archive_dir: None,
+            scan_archive: false,
+            retention_enabled: false,
+            retention_days: default_retention_days(),
+            status_poll_enabled: false,
+            status_poll_minutes: default_status_poll_minutes(),
+            retention_action: RetentionAction::default(),
+            folder_layout: FolderLayout::default(),
+            filename_template: String::new(),
+            collapse_single_date: false,
+            write_issue_shortcut: false,
+            skip_identical_downloads: false,
+            preserve_upload_time: false,
+            inter_file_delay_ms: 0,
+            max_download_rate_kib: 0,
+            max_concurrent_downloads: default_max_concurrent(),
+            my_issues_jql: default_my_issues_jql(),
+            my_issues_title: default_my_issues_title(),
+            auto_load_my_cases: true,
+            my_cases_attachment_counts: false,
+            select_new_attachments: true,
+            cancel_fetch_on_tab_switch: false,
+            taskbar_progress: true,
+            notify_on_batch_complete: false,
+            check_for_updates: false,
+            update_check_url: default_update_url(),
+            debug_logging: false,
+            closed_statuses: Vec::new(),
+            start_tab: StartTab::Auto,
+            last_tab: None,
+            last_issue: None,
+            recent_issues: Vec::new(),
+            favorites: Vec::new(),
+            reopen_last_issue: true,
+            window: None,
             api_token_enc: String::new(),
+            proxy_password_enc: String::new(),
+            proxy_password_unreadable: false,
         }
     }
 }
 
+pub const MAX_RECENT_ISSUES: usize = 10;
+
+/// Identifies the app in JIRA access logs, e.g. `jira-downloader/0.1.0`.
+pub const DEFAULT_USER_AGENT: &str = concat!("jira-downloader/", env!("CARGO_PKG_VERSION"));
+
+pub const DEFAULT_MY_ISSUES_JQL: &str =
+    "assignee = currentUser() AND statusCategory != Done ORDER BY updated DESC";
+
+fn default_my_issues_jql() -> String {
+    DEFAULT_MY_ISSUES_JQL.to_string()
+}
+
+fn default_my_issues_title() -> String {
+    "My Open Cases".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_connect_timeout() -> u64 {
+    10
+}
+
+fn default_request_timeout() -> u64 {
+    300
+}
+
+fn default_max_concurrent() -> usize {
+    4
+}
+
+fn default_status_poll_minutes() -> u32 {
+    15
+}
+
+fn default_retention_days() -> u32 {
+    90
+}
+
+fn default_update_url() -> String {
+    "https://api.github.com/repos/merol77a/jira-downloader/releases/latest".to_string()
+}
+
+fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("USERPROFILE")
+        .or_else(|| std::env::var_os("HOME"))
+        .map(PathBuf::from)
+}
+
 fn default_download_dir() -> PathBuf {
-    std::env::var("USERPROFILE")
-        .ok()
-        .map(|p| PathBuf::from(p).join("JiraDownloads"))
-        .unwrap_or_else(|| PathBuf::from("C:\\JiraDownloads"))
+    home_dir()
+        .map(|p| p.join("JiraDownloads"))
+        .unwrap_or_else(|| {
+            PathBuf::from(if cfg!(windows) { "C:\\JiraDownloads" } else { "JiraDownloads" })
+        })
+}
+
+/// Marker file that switches the app into portable mode when placed next to
+/// the executable.
+const PORTABLE_MARKER: &str = "portable";
+const PORTABLE_KEY_FILE: &str = "encryption.key";
+
+/// The executable's directory when running in portable mode: config, state
+/// files and the encryption key then all live there instead of the user
+/// profile and OS key store.
+pub fn portable_dir() -> Option<PathBuf> {
+    static DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+    DIR.get_or_init(|| {
+        let exe = std::env::current_exe().ok()?;
+        let dir = exe.parent()?.to_path_buf();
+        let portable = dir.join(PORTABLE_MARKER).is_file()
+            || (dir.join("config.json").is_file() && dir.join(PORTABLE_KEY_FILE).is_file());
+        portable.then_some(dir)
+    })
+    .clone()
+}
+
+/// Directory holding config.json and the app's other small state files:
+/// `%APPDATA%` on Windows, `~/Library/Application Support` on macOS and
+/// `$XDG_CONFIG_HOME` (or `~/.config`) elsewhere. In portable mode it is the
+/// executable's own directory.
+pub fn config_dir() -> PathBuf {
+    if let Some(dir) = portable_dir() {
+        return dir;
+    }
+    let base = if cfg!(windows) {
+        std::env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        home_dir().map(|h| h.join("Library").join("Application Support"))
+    } else {
+        std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .filter(|p| p.is_absolute())
+            .or_else(|| home_dir().map(|h| h.join(".config")))
+    };
+    base.unwrap_or_else(|| PathBuf::from(".")).join("jira-downloader")
 }
 
 fn config_path() -> PathBuf {
-    let appdata = std::env::var("APPDATA")
-        .unwrap_or_else(|_| std::env::var("HOME").unwrap_or_else(|_| ".".to_string()));
-    PathBuf::from(appdata)
-        .join("jira-downloader")
-        .join("config.json")
+    config_dir().join("config.json")
 }
 
-/// Returns the 32-byte AES key stored in the registry, generating one on first run.
+/// Returns the 32-byte AES key from the key store (the OS one, or the key
+/// file in portable mode), generating one on first run.
 fn get_or_create_key() -> Result<[u8; 32], String> {
-    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
-    let (subkey, _) = hkcu
-        .create_subkey(REG_KEY_PATH)
-        .map_err(|e| format!("Registry open error: {e}"))?;
+    let store = match portable_dir() {
+        Some(dir) => keystore::file(dir.join(PORTABLE_KEY_FILE)),
+        None => keystore::platform(),
+    };
 
     // Try to read an existing key.
-    if let Ok(encoded) = subkey.get_value::<String, _>(REG_ENC_VALUE) {
+    if let Some(encoded) = store.load()? {
         if let Ok(bytes) = B64.decode(&encoded) {
             if bytes.len() == 32 {
                 let mut arr = [0u8; 32];
@@ -73,10 +552,7 @@ fn get_or_create_key() -> Result<[u8; 32], String> {
     // First run — generate and persist a new key.
     let mut key = [0u8; 32];
     rand::thread_rng().fill_bytes(&mut key);
-    let encoded = B64.encode(key);
-    subkey
-        .set_value(REG_ENC_VALUE, &encoded)
-        .map_err(|e| format!("Registry write error: {e}"))?;
+    store.store(&B64.encode(key))?;
     Ok(key)
 }
 
@@ -114,7 +590,181 @@ fn decrypt_token(encoded: &str) -> Option<String> {
     String::from_utf8(plaintext).ok()
 }
 
+/// The shareable part of the config: no token, no key material.
+#[derive(Debug, Serialize, Deserialize)]
+struct PortableConfig {
+    jira_url: String,
+    email: String,
+    download_dir: PathBuf,
+}
+
 impl AppConfig {
+    pub fn effective_user_agent(&self) -> &str {
+        match self.user_agent.trim() {
+            "" => DEFAULT_USER_AGENT,
+            custom => custom,
+        }
+    }
+
+    /// Copies the edited connection fields back into the active profile.
+    pub fn store_active_profile(&mut self) {
+        if let Some(profile) = self.profiles.get_mut(self.active_profile) {
+            profile.jira_url = self.jira_url.clone();
+            profile.auth_method = self.auth_method;
+            profile.email = self.email.clone();
+            profile.api_token = self.api_token.clone();
+        }
+    }
+
+    fn load_profile(&mut self, index: usize) {
+        if let Some(profile) = self.profiles.get(index) {
+            self.active_profile = index;
+            self.jira_url = profile.jira_url.clone();
+            self.auth_method = profile.auth_method;
+            self.email = profile.email.clone();
+            self.api_token = profile.api_token.clone();
+        }
+    }
+
+    /// Makes another profile active, keeping edits made to the current one.
+    pub fn switch_profile(&mut self, index: usize) {
+        if index < self.profiles.len() && index != self.active_profile {
+            self.store_active_profile();
+            self.load_profile(index);
+        }
+    }
+
+    /// Adds an empty profile and switches to it.
+    pub fn add_profile(&mut self, name: &str) {
+        self.profiles.push(Profile {
+            name: name.to_string(),
+            ..Profile::default()
+        });
+        self.switch_profile(self.profiles.len() - 1);
+    }
+
+    /// Removes the active profile; the last remaining one can't be removed.
+    pub fn remove_active_profile(&mut self) {
+        if self.profiles.len() <= 1 {
+            return;
+        }
+        self.profiles.remove(self.active_profile);
+        self.load_profile(self.active_profile.min(self.profiles.len() - 1));
+    }
+
+    /// Whether config.json holds a token for the active profile, readable or not.
+    pub fn has_saved_token(&self) -> bool {
+        self.profiles
+            .get(self.active_profile)
+            .is_some_and(|p| !p.api_token_enc.is_empty())
+    }
+
+    /// Records which profile is active in config.json without rewriting
+    /// anything else. The profile is looked up by name in the saved list, so
+    /// profiles added or removed since the last Save don't shift the index;
+    /// one that was never saved leaves the file alone.
+    pub fn save_active_profile(&self) -> Result<(), String> {
+        let Some(name) = self.profiles.get(self.active_profile).map(|p| p.name.as_str()) else {
+            return Ok(());
+        };
+        let path = config_path();
+        let data = match std::fs::read_to_string(&path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(format!("Failed to read config: {e}")),
+        };
+        let mut json: serde_json::Value =
+            serde_json::from_str(&data).map_err(|e| format!("Failed to parse config: {e}"))?;
+        let saved_name = |p: &serde_json::Value| p.get("name").and_then(|n| n.as_str()) == Some(name);
+        let Some(index) = json
+            .get("profiles")
+            .and_then(|p| p.as_array())
+            .and_then(|saved| saved.iter().position(saved_name))
+        else {
+            return Ok(());
+        };
+        json["active_profile"] = serde_json::Value::from(index);
+        let data = serde_json::to_string_pretty(&json)
+            .map_err(|e| format!("Failed to serialize config: {e}"))?;
+        std::fs::write(&path, data).map_err(|e| format!("Failed to write config: {e}"))
+    }
+
+    /// Writes the connection basics to `path` for setting up another machine.
+    /// Secrets are deliberately left out, so the file is safe to share.
+    pub fn export_to(&self, path: &std::path::Path) -> Result<(), String> {
+        let portable = PortableConfig {
+            jira_url: self.jira_url.clone(),
+            email: self.email.clone(),
+            download_dir: self.download_dir.clone(),
+        };
+        let data = serde_json::to_string_pretty(&portable)
+            .map_err(|e| format!("Failed to serialize config: {e}"))?;
+        std::fs::write(path, data).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+    }
+
+    /// Applies a file written by `export_to`. The token is cleared because it
+    /// belongs to the old URL/account and has to be entered again.
+    pub fn import_from(&mut self, path: &std::path::Path) -> Result<(), String> {
+        let data = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        let portable: PortableConfig =
+            serde_json::from_str(&data).map_err(|e| format!("Not a config export: {e}"))?;
+        self.jira_url = portable.jira_url;
+        self.email = portable.email;
+        self.download_dir = portable.download_dir;
+        self.api_token.clear();
+        Ok(())
+    }
+
+    /// Moves `key` to the front of the recent list, dropping the oldest past the cap.
+    pub fn push_recent_issue(&mut self, key: &str) {
+        self.recent_issues.retain(|k| !k.eq_ignore_ascii_case(key));
+        self.recent_issues.insert(0, key.to_string());
+        self.recent_issues.truncate(MAX_RECENT_ISSUES);
+    }
+
+    pub fn is_favorite(&self, key: &str) -> bool {
+        self.favorites.iter().any(|f| f.key.eq_ignore_ascii_case(key))
+    }
+
+    /// Stars `key`, or unstars it if it already is.
+    pub fn toggle_favorite(&mut self, key: &str, summary: &str) {
+        if self.is_favorite(key) {
+            self.favorites.retain(|f| !f.key.eq_ignore_ascii_case(key));
+        } else {
+            self.favorites.push(FavoriteIssue {
+                key: key.to_string(),
+                summary: summary.to_string(),
+            });
+        }
+    }
+
+    /// Enough is filled in to talk to JIRA without prompting.
+    pub fn has_credentials(&self) -> bool {
+        !self.jira_url.is_empty()
+            && (self.auth_method == AuthMethod::Bearer || !self.email.is_empty())
+    }
+
+    /// `download_dir` followed by any additional scan roots, without duplicates.
+    pub fn scan_dirs(&self) -> Vec<PathBuf> {
+        let mut dirs = vec![self.download_dir.clone()];
+        for dir in &self.additional_scan_dirs {
+            if !dirs.contains(dir) {
+                dirs.push(dir.clone());
+            }
+        }
+        if let Some(archive) = &self.archive_dir {
+            if self.scan_archive {
+                if !dirs.contains(archive) {
+                    dirs.push(archive.clone());
+                }
+            } else {
+                dirs.retain(|d| d != archive);
+            }
+        }
+        dirs
+    }
+
     pub fn load() -> Self {
         let path = config_path();
         let mut config = if path.exists() {
@@ -126,25 +776,102 @@ impl AppConfig {
             Self::default()
         };
 
-        // Decrypt token from the stored encrypted blob.
+        // Decrypt token from the stored encrypted blob. A blob that doesn't
+        // decrypt is remembered as such so `save` won't overwrite it.
+        let mut legacy_unreadable = false;
         if !config.api_token_enc.is_empty() {
-            if let Some(token) = decrypt_token(&config.api_token_enc) {
-                config.api_token = token;
+            match decrypt_token(&config.api_token_enc) {
+                Some(token) => config.api_token = token,
+                None => legacy_unreadable = true,
+            }
+        }
+        for profile in &mut config.profiles {
+            if profile.api_token_enc.is_empty() {
+                continue;
             }
+            match decrypt_token(&profile.api_token_enc) {
+                Some(token) => profile.api_token = token,
+                None => profile.token_unreadable = true,
+            }
+        }
+
+        // Configs from before profiles existed become the first profile.
+        if config.profiles.is_empty() {
+            config.profiles.push(Profile {
+                name: "Default".to_string(),
+                jira_url: config.jira_url.clone(),
+                auth_method: config.auth_method,
+                email: config.email.clone(),
+                api_token: config.api_token.clone(),
+                api_token_enc: config.api_token_enc.clone(),
+                token_unreadable: legacy_unreadable,
+            });
+        }
+        config.active_profile = config.active_profile.min(config.profiles.len() - 1);
+        config.load_profile(config.active_profile);
+        if !config.proxy_password_enc.is_empty() {
+            match decrypt_token(&config.proxy_password_enc) {
+                Some(password) => config.proxy_password = password,
+                None => config.proxy_password_unreadable = true,
+            }
+        }
+
+        // Older configs carried these fields themselves; they apply until the
+        // first UI state file is written.
+        if let Some(state) = UiState::load() {
+            config.last_tab = state.last_tab;
+            config.window = state.window;
+            config.last_issue = state.last_issue;
+            config.recent_issues = state.recent_issues;
+            config.favorites = state.favorites;
         }
 
         config
     }
 
-    pub fn save(&self) -> Result<(), String> {
+    fn ui_state(&self) -> UiState {
+        UiState {
+            last_tab: self.last_tab,
+            window: self.window,
+            last_issue: self.last_issue.clone(),
+            recent_issues: self.recent_issues.clone(),
+            favorites: self.favorites.clone(),
+        }
+    }
+
+    /// Writes only the UI state fields, leaving config.json alone.
+    pub fn save_ui_state(&self) -> Result<(), String> {
+        self.ui_state().save()
+    }
+
+    /// Writes config.json and the UI state. Secrets that couldn't be
+    /// decrypted on load are written back as the blobs they were read as.
+    /// The in-memory blobs are updated to match, so `has_saved_token` reflects
+    /// what was just written.
+    pub fn save(&mut self) -> Result<(), String> {
         let mut on_disk = self.clone();
+        on_disk.store_active_profile();
+        for profile in &mut on_disk.profiles {
+            if !profile.api_token.is_empty() {
+                profile.api_token_enc = encrypt_token(&profile.api_token)?;
+            } else if !profile.token_unreadable {
+                profile.api_token_enc.clear();
+            }
+        }
 
         // Encrypt the plaintext token for storage.
         if !self.api_token.is_empty() {
             on_disk.api_token_enc = encrypt_token(&self.api_token)?;
+        } else if let Some(active) = on_disk.profiles.get(on_disk.active_profile) {
+            on_disk.api_token_enc = active.api_token_enc.clone();
         } else {
             on_disk.api_token_enc = String::new();
         }
+        if !self.proxy_password.is_empty() {
+            on_disk.proxy_password_enc = encrypt_token(&self.proxy_password)?;
+        } else if !self.proxy_password_unreadable {
+            on_disk.proxy_password_enc.clear();
+        }
 
         let path = config_path();
         if let Some(parent) = path.parent() {
@@ -156,6 +883,15 @@ impl AppConfig {
         std::fs::write(&path, data)
             .map_err(|e| format!("Failed to write config: {e}"))?;
 
-        Ok(())
+        for (profile, saved) in self.profiles.iter_mut().zip(&on_disk.profiles) {
+            profile.api_token = saved.api_token.clone();
+            profile.api_token_enc = saved.api_token_enc.clone();
+            profile.token_unreadable &= saved.api_token.is_empty();
+        }
+        self.api_token_enc = on_disk.api_token_enc;
+        self.proxy_password_enc = on_disk.proxy_password_enc;
+        self.proxy_password_unreadable &= self.proxy_password.is_empty();
+
+        self.save_ui_state()
     }
 }
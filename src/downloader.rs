@@ -1,17 +1,30 @@
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 
-use egui;
+use tokio::sync::Semaphore;
+use tokio::task::AbortHandle;
 
-use crate::config::AppConfig;
+use crate::checksum::{Checksum, ChecksumAlgorithm};
+use crate::config::{AppConfig, FolderLayout};
+use crate::history::{self, HistoryEntry};
 use crate::jira::{Attachment, JiraClient};
-use crate::storage::StorageManager;
+use crate::metrics::{DownloadMetric, MetricsLog};
+use crate::storage::{
+    effective_layout, normal_path, part_path, release_target, set_upload_time, StorageManager,
+};
 
 #[derive(Debug, Clone)]
 pub enum FileState {
     Pending,
+    /// Started, but waiting for a free download slot.
+    Queued,
     Downloading { downloaded: u64, total: u64 },
     Done,
+    /// Only the thumbnail was fetched; the original is still not on disk.
+    ThumbnailDone,
     AlreadyOnDisk,
+    /// Stopped by Cancel All before it finished; can be started again.
+    Cancelled,
     Error(String),
 }
 
@@ -21,7 +34,7 @@ impl FileState {
             FileState::Downloading { downloaded, total } if *total > 0 => {
                 Some(*downloaded as f32 / *total as f32)
             }
-            FileState::Done | FileState::AlreadyOnDisk => Some(1.0),
+            FileState::Done | FileState::ThumbnailDone | FileState::AlreadyOnDisk => Some(1.0),
             _ => None,
         }
     }
@@ -29,6 +42,7 @@ impl FileState {
     pub fn label(&self) -> String {
         match self {
             FileState::Pending => "Pending".to_string(),
+            FileState::Queued => "Queued".to_string(),
             FileState::Downloading { downloaded, total } => {
                 if *total > 0 {
                     let pct = (*downloaded as f32 / *total as f32 * 100.0) as u32;
@@ -38,7 +52,9 @@ impl FileState {
                 }
             }
             FileState::Done => "Done ✓".to_string(),
+            FileState::ThumbnailDone => "Thumbnail ✓".to_string(),
             FileState::AlreadyOnDisk => "On disk ✓".to_string(),
+            FileState::Cancelled => "Cancelled".to_string(),
             FileState::Error(e) => format!("Error: {e}"),
         }
     }
@@ -49,6 +65,11 @@ pub struct DownloadItem {
     pub attachment: Attachment,
     pub state: Arc<Mutex<FileState>>,
     pub selected: bool,
+    /// Fetch the small thumbnail instead of the original (images only).
+    pub thumbnail: bool,
+    /// Where the file actually landed, which can differ from the attachment's
+    /// name after conflict renaming. Set once `Done` or found `AlreadyOnDisk`.
+    pub saved_path: Arc<Mutex<Option<PathBuf>>>,
 }
 
 impl DownloadItem {
@@ -57,95 +78,514 @@ impl DownloadItem {
             attachment,
             state: Arc::new(Mutex::new(FileState::Pending)),
             selected: true,
+            thumbnail: false,
+            saved_path: Arc::new(Mutex::new(None)),
         }
     }
 
     pub fn current_state(&self) -> FileState {
         self.state.lock().unwrap().clone()
     }
+
+    pub fn saved_path(&self) -> Option<PathBuf> {
+        self.saved_path.lock().unwrap().clone()
+    }
+}
+
+/// One file to fetch, detached from the UI's `DownloadItem`.
+struct DownloadJob {
+    attachment: Attachment,
+    state: Arc<Mutex<FileState>>,
+    saved_path: Arc<Mutex<Option<PathBuf>>>,
+    thumbnail: bool,
+    /// An earlier copy the download replaces once it has been verified.
+    replaces: Option<PathBuf>,
+}
+
+impl DownloadJob {
+    fn from_item(item: &DownloadItem) -> Self {
+        Self {
+            attachment: item.attachment.clone(),
+            state: Arc::clone(&item.state),
+            saved_path: Arc::clone(&item.saved_path),
+            thumbnail: item.thumbnail && item.attachment.thumbnail.is_some(),
+            replaces: None,
+        }
+    }
+}
+
+/// Shared by every download started in the same batch.
+#[derive(Clone)]
+struct DownloadContext {
+    issue_key: String,
+    config: AppConfig,
+    metrics: Arc<MetricsLog>,
+    /// Layout for this issue, with single-date collapsing already applied.
+    layout: FolderLayout,
+    ctx: egui::Context,
+}
+
+/// The tasks one `dispatch` call started, so they can all be cancelled.
+struct Batch {
+    dispatcher: AbortHandle,
+    /// Every job's state, to mark the ones still queued as cancelled.
+    states: Vec<Arc<Mutex<FileState>>>,
+    /// `None` once cancelled, so the dispatcher can't add a task after
+    /// `cancel_all` has taken the list.
+    running: Arc<Mutex<Option<Vec<AbortHandle>>>>,
+}
+
+impl Batch {
+    fn is_finished(&self) -> bool {
+        self.dispatcher.is_finished()
+            && self.running.lock().unwrap().as_ref().is_none_or(|tasks| tasks.iter().all(|t| t.is_finished()))
+    }
 }
 
 pub struct DownloadManager {
     runtime: Arc<tokio::runtime::Runtime>,
+    pub metrics: Arc<MetricsLog>,
+    max_concurrent: usize,
+    /// Shared by every batch so starting a second batch doesn't double the load.
+    slots: Arc<Semaphore>,
+    batches: Vec<Batch>,
 }
 
 impl DownloadManager {
-    pub fn new(runtime: Arc<tokio::runtime::Runtime>) -> Self {
-        Self { runtime }
+    pub fn new(runtime: Arc<tokio::runtime::Runtime>, max_concurrent: usize) -> Self {
+        let max_concurrent = max_concurrent.max(1);
+        Self {
+            runtime,
+            metrics: Arc::new(MetricsLog::default()),
+            max_concurrent,
+            slots: Arc::new(Semaphore::new(max_concurrent)),
+            batches: Vec::new(),
+        }
     }
 
-    pub fn start_download(
+    /// Resizes the shared slots in place, so batches already waiting see the
+    /// new limit too. When shrinking, slots held by running downloads are
+    /// taken back as those finish.
+    fn set_max_concurrent(&mut self, max_concurrent: usize) {
+        let max_concurrent = max_concurrent.max(1);
+        if max_concurrent > self.max_concurrent {
+            self.slots.add_permits(max_concurrent - self.max_concurrent);
+        } else if max_concurrent < self.max_concurrent {
+            let excess = self.max_concurrent - max_concurrent;
+            let in_use = excess - self.slots.forget_permits(excess);
+            if in_use > 0 {
+                let slots = Arc::clone(&self.slots);
+                self.runtime.spawn(async move {
+                    if let Ok(permits) = slots.acquire_many_owned(in_use as u32).await {
+                        permits.forget();
+                    }
+                });
+            }
+        }
+        self.max_concurrent = max_concurrent;
+    }
+
+    /// `items` is every attachment of the issue, not just the ones being
+    /// downloaded — collapsing the date layout depends on all of their dates.
+    fn context(
         &self,
-        item: &DownloadItem,
+        items: &[DownloadItem],
+        issue_key: &str,
+        config: &AppConfig,
+        ctx: egui::Context,
+    ) -> DownloadContext {
+        DownloadContext {
+            issue_key: issue_key.to_string(),
+            config: config.clone(),
+            metrics: Arc::clone(&self.metrics),
+            layout: effective_layout(
+                config.folder_layout,
+                config.collapse_single_date,
+                items.iter().map(|i| &i.attachment),
+            ),
+            ctx,
+        }
+    }
+
+    pub fn start_all_downloads(
+        &mut self,
+        items: &[DownloadItem],
+        issue_key: &str,
+        config: &AppConfig,
+        ctx: egui::Context,
+    ) {
+        let jobs: Vec<DownloadJob> = items
+            .iter()
+            .filter(|item| item.selected)
+            .filter(|item| {
+                matches!(
+                    item.current_state(),
+                    FileState::Pending
+                        | FileState::Cancelled
+                        | FileState::Error(_)
+                        | FileState::Done
+                        | FileState::ThumbnailDone
+                )
+            })
+            .map(DownloadJob::from_item)
+            .collect();
+        self.dispatch(jobs, items, issue_key, config, ctx);
+    }
+
+    /// Downloads `items[index]` whatever its state or selection, e.g. to
+    /// replace a copy already on disk. `replaces` is overwritten only after
+    /// the new file has passed the size and checksum checks.
+    pub fn start_download(
+        &mut self,
+        items: &[DownloadItem],
+        index: usize,
+        issue_key: &str,
+        config: &AppConfig,
+        replaces: Option<PathBuf>,
+        ctx: egui::Context,
+    ) {
+        let mut job = DownloadJob::from_item(&items[index]);
+        job.replaces = replaces;
+        let jobs = vec![job];
+        self.dispatch(jobs, items, issue_key, config, ctx);
+    }
+
+    fn dispatch(
+        &mut self,
+        jobs: Vec<DownloadJob>,
+        items: &[DownloadItem],
         issue_key: &str,
         config: &AppConfig,
         ctx: egui::Context,
     ) {
-        let attachment = item.attachment.clone();
-        let state = Arc::clone(&item.state);
-        let issue_key = issue_key.to_string();
-        let config = config.clone();
-
-        self.runtime.spawn(async move {
-            {
-                let mut s = state.lock().unwrap();
-                *s = FileState::Downloading {
-                    downloaded: 0,
-                    total: attachment.size,
+        for job in &jobs {
+            *job.state.lock().unwrap() = FileState::Queued;
+        }
+        ctx.request_repaint();
+        self.batches.retain(|b| !b.is_finished());
+        let states = jobs.iter().map(|j| Arc::clone(&j.state)).collect();
+        let running = Arc::new(Mutex::new(Some(Vec::new())));
+        let tasks = Arc::clone(&running);
+
+        self.set_max_concurrent(config.max_concurrent_downloads);
+        let slots = Arc::clone(&self.slots);
+        let delay = std::time::Duration::from_millis(config.inter_file_delay_ms);
+        let dl = self.context(items, issue_key, config, ctx);
+
+        // Launch downloads one after another from a single dispatcher task so the
+        // politeness delay is measured between successive starts, and each start
+        // waits for a free slot.
+        let dispatcher = self.runtime.spawn(async move {
+            for (idx, job) in jobs.into_iter().enumerate() {
+                if idx > 0 && !delay.is_zero() {
+                    tokio::time::sleep(delay).await;
+                }
+                let Ok(permit) = Arc::clone(&slots).acquire_owned().await else {
+                    return;
                 };
+                // Spawn and record under the lock, so a cancel either sees this
+                // task or stops it from starting at all.
+                let mut tasks = tasks.lock().unwrap();
+                let Some(tasks) = tasks.as_mut() else {
+                    return;
+                };
+                let dl = dl.clone();
+                let task = tokio::spawn(async move {
+                    run_download(job, dl).await;
+                    drop(permit);
+                });
+                tasks.push(task.abort_handle());
             }
-            ctx.request_repaint();
-
-            let client = JiraClient::new(config.clone());
-            let state_clone = Arc::clone(&state);
-            let ctx_clone = ctx.clone();
-
-            let result = client
-                .download_attachment(&attachment.content, move |downloaded, total| {
-                    let mut s = state_clone.lock().unwrap();
-                    *s = FileState::Downloading { downloaded, total };
-                    ctx_clone.request_repaint();
-                })
-                .await;
-
-            match result {
-                Ok(data) => {
-                    let storage = StorageManager::new(config.download_dir.clone());
-                    match storage.save_attachment(&issue_key, &attachment, &data) {
-                        Ok(_) => {
-                            let mut s = state.lock().unwrap();
-                            *s = FileState::Done;
-                        }
-                        Err(e) => {
-                            let mut s = state.lock().unwrap();
-                            *s = FileState::Error(e);
-                        }
-                    }
+        });
+        self.batches.push(Batch {
+            dispatcher: dispatcher.abort_handle(),
+            states,
+            running,
+        });
+    }
+
+    /// True while any batch still has files queued or downloading.
+    pub fn is_busy(&self) -> bool {
+        self.batches.iter().any(|b| !b.is_finished())
+    }
+
+    /// Stops every batch: queued files are never started and running ones are
+    /// aborted, their partial files removed. Finished files stay on disk.
+    pub fn cancel_all(&mut self) {
+        for batch in self.batches.drain(..) {
+            batch.dispatcher.abort();
+            for task in batch.running.lock().unwrap().take().into_iter().flatten() {
+                task.abort();
+            }
+            for state in &batch.states {
+                let mut state = state.lock().unwrap();
+                if matches!(*state, FileState::Queued) {
+                    *state = FileState::Cancelled;
                 }
+            }
+        }
+    }
+}
+
+/// Armed for the whole of a download; if the task is aborted (Cancel All)
+/// before it finishes, dropping this marks the row cancelled and removes the
+/// partial file.
+struct AbortCleanup {
+    state: Arc<Mutex<FileState>>,
+    target: Option<PathBuf>,
+    ctx: egui::Context,
+    armed: bool,
+}
+
+impl Drop for AbortCleanup {
+    fn drop(&mut self) {
+        if !self.armed {
+            return;
+        }
+        *self.state.lock().unwrap() = FileState::Cancelled;
+        if let Some(target) = &self.target {
+            let _ = std::fs::remove_file(part_path(target));
+            release_target(target);
+        }
+        self.ctx.request_repaint();
+    }
+}
+
+async fn run_download(job: DownloadJob, dl: DownloadContext) {
+    let mut cleanup = AbortCleanup {
+        state: Arc::clone(&job.state),
+        target: None,
+        ctx: dl.ctx.clone(),
+        armed: true,
+    };
+    download(job, dl, &mut cleanup).await;
+    cleanup.armed = false;
+}
+
+async fn download(job: DownloadJob, dl: DownloadContext, cleanup: &mut AbortCleanup) {
+    let DownloadJob {
+        attachment,
+        state,
+        saved_path,
+        thumbnail,
+        replaces,
+    } = job;
+    let DownloadContext {
+        issue_key,
+        config,
+        metrics,
+        layout,
+        ctx,
+    } = dl;
+
+    {
+        let mut s = state.lock().unwrap();
+        *s = FileState::Downloading {
+            downloaded: 0,
+            total: if thumbnail { 0 } else { attachment.size },
+        };
+    }
+    ctx.request_repaint();
+
+    let client = JiraClient::new(config.clone());
+    let state_clone = Arc::clone(&state);
+    let ctx_clone = ctx.clone();
+    let started = std::time::Instant::now();
+
+    // Thumbnail jobs only exist for attachments that have a thumbnail URL.
+    let url = match (&attachment.thumbnail, thumbnail) {
+        (Some(thumb), true) => thumb.as_str(),
+        _ => attachment.content.as_str(),
+    };
+
+    let storage = StorageManager::new(config.download_dir.clone())
+        .with_filename_template(&config.filename_template);
+    let target = if thumbnail {
+        storage.thumbnail_target(&issue_key, &attachment)
+    } else {
+        storage.attachment_target(&issue_key, &attachment, layout)
+    };
+
+    log::info!("{issue_key}: downloading {} ({} bytes)", attachment.filename, attachment.size);
+    let target_path = target.as_ref().ok().cloned();
+    cleanup.target = target_path.clone();
+    let mut retries = 0;
+    let result = match target {
+        Ok(path) => client
+            .download_attachment(url, &path, move |downloaded, total| {
+                let mut s = state_clone.lock().unwrap();
+                *s = FileState::Downloading { downloaded, total };
+                ctx_clone.request_repaint();
+            })
+            .await
+            .map(|(size, rate_limited)| {
+                retries = rate_limited;
+                (path, size)
+            }),
+        Err(e) => Err(e),
+    };
+
+    // The partial file is gone from here on: renamed, or removed on error.
+    if let Some(path) = cleanup.target.take() {
+        release_target(&path);
+    }
+
+    // A proxy can cut a response short without any error; don't call that Done.
+    let result = match result {
+        Ok((path, size)) if !thumbnail && attachment.size > 0 && size != attachment.size => {
+            let _ = std::fs::remove_file(&path);
+            Err(format!(
+                "size mismatch: got {size} bytes expected {} bytes",
+                attachment.size
+            ))
+        }
+        other => other,
+    };
+
+    let result = match result {
+        Ok((path, size)) if !thumbnail => {
+            match verify_checksum(&storage, &issue_key, &attachment, path.clone()).await {
+                Ok(()) => Ok((path, size)),
                 Err(e) => {
-                    let mut s = state.lock().unwrap();
-                    *s = FileState::Error(e);
+                    let _ = std::fs::remove_file(&path);
+                    Err(e)
                 }
             }
-            ctx.request_repaint();
-        });
+        }
+        other => other,
+    };
+
+    // Only now that the new copy checks out does it take the old one's place.
+    let result = match (result, replaces) {
+        (Ok((path, size)), Some(old)) => match std::fs::rename(&path, &old) {
+            Ok(()) => Ok((old, size)),
+            Err(e) => {
+                log::warn!("{issue_key}: could not replace {}: {e}", normal_path(&old).display());
+                Ok((path, size))
+            }
+        },
+        (result, _) => result,
+    };
+
+    if let Ok((path, _)) = &result {
+        if config.skip_identical_downloads && !thumbnail {
+            let candidate = path.clone();
+            let (key, att) = (issue_key.clone(), attachment.clone());
+            let (dir, template) = (config.download_dir.clone(), config.filename_template.clone());
+            let duplicate = tokio::task::spawn_blocking(move || {
+                StorageManager::new(dir)
+                    .with_filename_template(&template)
+                    .identical_earlier_copy(&key, &att, &candidate)
+            })
+            .await
+            .ok()
+            .flatten();
+            if let Some(existing) = duplicate {
+                log::info!(
+                    "{issue_key}: {} is identical to {}; not kept",
+                    attachment.filename,
+                    existing.display()
+                );
+                let _ = std::fs::remove_file(path);
+                *saved_path.lock().unwrap() = Some(existing);
+                *state.lock().unwrap() = FileState::AlreadyOnDisk;
+                ctx.request_repaint();
+                return;
+            }
+        }
     }
 
-    pub fn start_all_downloads(
-        &self,
-        items: &[DownloadItem],
-        issue_key: &str,
-        config: &AppConfig,
-        ctx: egui::Context,
-    ) {
-        for item in items {
-            if item.selected {
-                let state = item.current_state();
-                if matches!(state, FileState::Pending | FileState::Error(_) | FileState::Done) {
-                    self.start_download(item, issue_key, config, ctx.clone());
+    // History tells a fetched thumbnail apart from the original, either way.
+    let history_name = if thumbnail {
+        format!("{} (thumbnail)", attachment.filename)
+    } else {
+        attachment.filename.clone()
+    };
+    match result {
+        Ok((path, size)) => {
+            if config.debug_logging {
+                metrics.record(DownloadMetric::new(
+                    &issue_key,
+                    &attachment.filename,
+                    size,
+                    started.elapsed(),
+                    retries,
+                ));
+            }
+            let _ = history::append(&HistoryEntry {
+                issue_key: issue_key.clone(),
+                filename: history_name,
+                size,
+                downloaded_at: chrono::Utc::now(),
+                destination: normal_path(&path),
+                error: None,
+            });
+            log::info!("{issue_key}: saved {} ({size} bytes)", normal_path(&path).display());
+            if thumbnail {
+                // `saved_path` is the original's; it stays unset until that is fetched.
+                *state.lock().unwrap() = FileState::ThumbnailDone;
+            } else {
+                if let Err(e) = storage.record_saved_file(&issue_key, &attachment, &path, layout) {
+                    log::warn!("{issue_key}: {e}");
                 }
+                if config.preserve_upload_time {
+                    if let Err(e) = set_upload_time(&path, attachment.created) {
+                        log::warn!("{issue_key}: {e}");
+                    }
+                }
+                *saved_path.lock().unwrap() = Some(path);
+                *state.lock().unwrap() = FileState::Done;
             }
         }
+        Err(e) => {
+            log::error!("{issue_key}: {} failed: {e}", attachment.filename);
+            let _ = history::append(&HistoryEntry {
+                issue_key: issue_key.clone(),
+                filename: history_name,
+                size: if thumbnail { 0 } else { attachment.size },
+                downloaded_at: chrono::Utc::now(),
+                destination: target_path.map(|p| normal_path(&p)).unwrap_or_default(),
+                error: Some(e.clone()),
+            });
+            let mut s = state.lock().unwrap();
+            *s = FileState::Error(e);
+        }
+    }
+    ctx.request_repaint();
+}
+
+/// Compares the file against the digest recorded by an earlier download of
+/// the same attachment, or records it if there is none yet.
+async fn verify_checksum(
+    storage: &StorageManager,
+    issue_key: &str,
+    attachment: &Attachment,
+    path: PathBuf,
+) -> Result<(), String> {
+    let digest =
+        tokio::task::spawn_blocking(move || Checksum::of_file(&path, ChecksumAlgorithm::default()))
+            .await
+            .map_err(|e| format!("Checksum task failed: {e}"))??;
+
+    let known = storage
+        .update_control_file(issue_key, |ctrl| {
+            match ctrl.checksums.get(&attachment.id) {
+                Some(known) if known.algorithm == digest.algorithm => Some(known.clone()),
+                _ => {
+                    ctrl.checksums.insert(attachment.id.clone(), digest.clone());
+                    None
+                }
+            }
+        })
+        .ok()
+        .flatten();
+
+    match known {
+        Some(known) if known.hex != digest.hex => Err(format!(
+            "checksum mismatch: got {} expected {}",
+            digest.hex, known.hex
+        )),
+        _ => Ok(()),
     }
 }
 
@@ -0,0 +1,226 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::path::Path;
+
+use crate::history::HistoryEntry;
+use crate::jira::IssueInfo;
+use crate::storage::{is_bookkeeping_file, IncidentFolder, MANIFEST_FILE};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+}
+
+impl ExportFormat {
+    /// Picks the format from the file extension, defaulting to JSON.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("csv") => ExportFormat::Csv,
+            _ => ExportFormat::Json,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct InventoryRow<'a> {
+    issue_key: &'a str,
+    summary: &'a str,
+    status: &'a str,
+    closed: bool,
+    folder_size: u64,
+    last_checked: DateTime<Utc>,
+    file_count: u64,
+}
+
+impl<'a> InventoryRow<'a> {
+    fn from_incident(incident: &'a IncidentFolder, closed_statuses: &[String]) -> Self {
+        let ctrl = &incident.control;
+        Self {
+            issue_key: &ctrl.issue_key,
+            summary: &ctrl.issue_summary,
+            status: &ctrl.issue_status,
+            closed: ctrl.is_closed(closed_statuses),
+            folder_size: incident.folder_size,
+            last_checked: ctrl.last_checked,
+            file_count: incident.file_count,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Manifest<'a> {
+    issue_key: &'a str,
+    summary: &'a str,
+    status: &'a str,
+    exported_at: DateTime<Utc>,
+    attachments: Vec<ManifestAttachment<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct ManifestAttachment<'a> {
+    id: &'a str,
+    filename: &'a str,
+    size: u64,
+    created: DateTime<Utc>,
+    mime_type: &'a str,
+    content_url: &'a str,
+}
+
+/// Records what the issue held in JIRA right now, one entry per attachment,
+/// as `jira_manifest.json` in `dir`. Overwrites an earlier manifest.
+pub fn export_manifest(issue: &IssueInfo, dir: &Path) -> Result<std::path::PathBuf, String> {
+    let manifest = Manifest {
+        issue_key: &issue.key,
+        summary: &issue.summary,
+        status: &issue.status,
+        exported_at: Utc::now(),
+        attachments: issue
+            .attachments
+            .iter()
+            .map(|a| ManifestAttachment {
+                id: &a.id,
+                filename: &a.filename,
+                size: a.size,
+                created: a.created,
+                mime_type: &a.mime_type,
+                content_url: &a.content,
+            })
+            .collect(),
+    };
+    let data = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| format!("Serialize error: {e}"))?;
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    let path = dir.join(MANIFEST_FILE);
+    std::fs::write(&path, data).map_err(|e| format!("Write error: {e}"))?;
+    Ok(path)
+}
+
+/// Writes every incident's control data and folder stats to `path`.
+pub fn export_inventory(
+    incidents: &[IncidentFolder],
+    path: &Path,
+    format: ExportFormat,
+    closed_statuses: &[String],
+) -> Result<(), String> {
+    let rows: Vec<InventoryRow> = incidents
+        .iter()
+        .map(|i| InventoryRow::from_incident(i, closed_statuses))
+        .collect();
+
+    let data = match format {
+        ExportFormat::Json => serde_json::to_string_pretty(&rows)
+            .map_err(|e| format!("Serialize error: {e}"))?,
+        ExportFormat::Csv => {
+            let mut out = String::from(
+                "issue_key,summary,status,closed,folder_size,last_checked,file_count\r\n",
+            );
+            for row in &rows {
+                let fields = [
+                    csv_field(row.issue_key),
+                    csv_field(row.summary),
+                    csv_field(row.status),
+                    row.closed.to_string(),
+                    row.folder_size.to_string(),
+                    row.last_checked.to_rfc3339(),
+                    row.file_count.to_string(),
+                ];
+                out.push_str(&fields.join(","));
+                out.push_str("\r\n");
+            }
+            out
+        }
+    };
+
+    std::fs::write(path, data).map_err(|e| format!("Write error: {e}"))
+}
+
+/// Writes download history entries to `path`.
+pub fn export_history(
+    entries: &[HistoryEntry],
+    path: &Path,
+    format: ExportFormat,
+) -> Result<(), String> {
+    let data = match format {
+        ExportFormat::Json => serde_json::to_string_pretty(entries)
+            .map_err(|e| format!("Serialize error: {e}"))?,
+        ExportFormat::Csv => {
+            let mut out = String::from("issue_key,filename,size,downloaded_at,destination,error\r\n");
+            for entry in entries {
+                let fields = [
+                    csv_field(&entry.issue_key),
+                    csv_field(&entry.filename),
+                    entry.size.to_string(),
+                    entry.downloaded_at.to_rfc3339(),
+                    csv_field(&entry.destination.to_string_lossy()),
+                    csv_field(entry.error.as_deref().unwrap_or("")),
+                ];
+                out.push_str(&fields.join(","));
+                out.push_str("\r\n");
+            }
+            out
+        }
+    };
+
+    std::fs::write(path, data).map_err(|e| format!("Write error: {e}"))
+}
+
+/// Writes issue folder `src` into a zip at `dest` under a top-level `issue_key/` folder,
+/// keeping the date subfolders. Files are streamed one at a time so memory
+/// stays flat; the app's bookkeeping files are left out. Returns the number
+/// of files added.
+pub fn zip_folder(src: &Path, issue_key: &str, dest: &Path) -> Result<usize, String> {
+    let file = std::fs::File::create(dest).map_err(|e| format!("Failed to create zip: {e}"))?;
+    let mut zip = zip::ZipWriter::new(std::io::BufWriter::new(file));
+    let mut added = 0;
+
+    let result = add_to_zip(&mut zip, src, issue_key, issue_key, &mut added).and_then(|()| {
+        zip.finish().map(|_| ()).map_err(|e| format!("Failed to finish zip: {e}"))
+    });
+    if result.is_err() {
+        let _ = std::fs::remove_file(dest);
+    }
+    result.map(|()| added)
+}
+
+fn add_to_zip<W: std::io::Write + std::io::Seek>(
+    zip: &mut zip::ZipWriter<W>,
+    dir: &Path,
+    prefix: &str,
+    issue_key: &str,
+    added: &mut usize,
+) -> Result<(), String> {
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    zip.add_directory(format!("{prefix}/"), options)
+        .map_err(|e| format!("Zip error: {e}"))?;
+
+    let rd = std::fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {e}", dir.display()))?;
+    for entry in rd.flatten() {
+        let p = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        let entry_name = format!("{prefix}/{name}");
+        if p.is_dir() {
+            add_to_zip(zip, &p, &entry_name, issue_key, added)?;
+        } else if !is_bookkeeping_file(&p, issue_key, prefix == issue_key) {
+            let size = p.metadata().map(|m| m.len()).unwrap_or(0);
+            zip.start_file(entry_name.as_str(), options.large_file(size >= u32::MAX as u64))
+                .map_err(|e| format!("Zip error: {e}"))?;
+            let mut input = std::fs::File::open(&p)
+                .map_err(|e| format!("Failed to read {name}: {e}"))?;
+            std::io::copy(&mut input, zip).map_err(|e| format!("Failed to add {name}: {e}"))?;
+            *added += 1;
+        }
+    }
+    Ok(())
+}
+
+/// Quotes a CSV field when it contains a delimiter, quote or line break.
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,69 @@
+//! Fallback fonts for non-Latin issue summaries and filenames.
+//!
+//! egui's built-in fonts only cover Latin, Greek and Cyrillic. Rather than bundle
+//! tens of megabytes of font data, we load fonts the OS already ships and append
+//! them as fallbacks after the defaults:
+//!
+//! | Script                     | Windows           | macOS              | Linux (Noto)        |
+//! |----------------------------|-------------------|--------------------|---------------------|
+//! | Chinese / Japanese / Korean | Microsoft YaHei, Meiryo, Malgun Gothic | PingFang, Hiragino, Apple SD Gothic | Noto Sans CJK |
+//! | Arabic / Hebrew            | Segoe UI          | Geeza Pro, Arial Hebrew | Noto Sans Arabic / Hebrew |
+//! | Thai                       | Tahoma            | Thonburi           | Noto Sans Thai      |
+//! | Devanagari and other Indic | Nirmala UI        | Kohinoor Devanagari | Noto Sans Devanagari |
+//!
+//! Missing files are skipped, so on a given machine only what's installed is used.
+
+#[cfg(target_os = "windows")]
+const CANDIDATES: &[&str] = &[
+    "C:\\Windows\\Fonts\\msyh.ttc",
+    "C:\\Windows\\Fonts\\meiryo.ttc",
+    "C:\\Windows\\Fonts\\malgun.ttf",
+    "C:\\Windows\\Fonts\\segoeui.ttf",
+    "C:\\Windows\\Fonts\\tahoma.ttf",
+    "C:\\Windows\\Fonts\\Nirmala.ttf",
+];
+
+#[cfg(target_os = "macos")]
+const CANDIDATES: &[&str] = &[
+    "/System/Library/Fonts/PingFang.ttc",
+    "/System/Library/Fonts/Hiragino Sans GB.ttc",
+    "/System/Library/Fonts/AppleSDGothicNeo.ttc",
+    "/System/Library/Fonts/GeezaPro.ttc",
+    "/System/Library/Fonts/ArialHB.ttc",
+    "/System/Library/Fonts/Thonburi.ttc",
+    "/System/Library/Fonts/Kohinoor.ttc",
+];
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const CANDIDATES: &[&str] = &[
+    "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/truetype/noto/NotoSansArabic-Regular.ttf",
+    "/usr/share/fonts/truetype/noto/NotoSansHebrew-Regular.ttf",
+    "/usr/share/fonts/truetype/noto/NotoSansThai-Regular.ttf",
+    "/usr/share/fonts/truetype/noto/NotoSansDevanagari-Regular.ttf",
+];
+
+/// Appends every available fallback font to both font families.
+pub fn install_fallback_fonts(ctx: &egui::Context) {
+    let mut fonts = egui::FontDefinitions::default();
+    let mut installed = false;
+
+    for path in CANDIDATES {
+        let Ok(bytes) = std::fs::read(path) else {
+            continue;
+        };
+        let name = format!("fallback:{path}");
+        fonts
+            .font_data
+            .insert(name.clone(), egui::FontData::from_owned(bytes));
+        for family in [egui::FontFamily::Proportional, egui::FontFamily::Monospace] {
+            fonts.families.entry(family).or_default().push(name.clone());
+        }
+        installed = true;
+    }
+
+    if installed {
+        ctx.set_fonts(fonts);
+    }
+}
@@ -0,0 +1,104 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::config::config_dir;
+
+/// Once the log grows past this size it is trimmed to the newest entries that
+/// fit in `TRIM_TO_BYTES`, at most `KEEP_ENTRIES`. Trimming well below the cap
+/// leaves room for many appends before the next rewrite.
+const ROTATE_BYTES: u64 = 2 * 1_048_576;
+const TRIM_TO_BYTES: usize = 1_048_576;
+const KEEP_ENTRIES: usize = 5_000;
+
+/// Serializes appends from concurrent download tasks.
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub issue_key: String,
+    pub filename: String,
+    pub size: u64,
+    pub downloaded_at: DateTime<Utc>,
+    pub destination: PathBuf,
+    /// Why the download failed; `None` means it finished (`FileState::Done`).
+    /// Failed entries have an empty `destination` when no file was started.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+fn history_path() -> PathBuf {
+    config_dir().join("download_history.jsonl")
+}
+
+/// Appends one entry to the append-only JSON-lines log in the config dir.
+pub fn append(entry: &HistoryEntry) -> Result<(), String> {
+    let _guard = WRITE_LOCK.lock().unwrap();
+    let path = history_path();
+    std::fs::create_dir_all(config_dir())
+        .map_err(|e| format!("Failed to create config dir: {e}"))?;
+
+    let line = serde_json::to_string(entry).map_err(|e| format!("Serialize error: {e}"))?;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open history: {e}"))?;
+    writeln!(file, "{line}").map_err(|e| format!("Failed to write history: {e}"))?;
+    drop(file);
+
+    if std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0) > ROTATE_BYTES {
+        trim()?;
+    }
+    Ok(())
+}
+
+/// Keeps the newest lines that fit in `TRIM_TO_BYTES`, at most `KEEP_ENTRIES`.
+fn trim() -> Result<(), String> {
+    let data = std::fs::read_to_string(history_path())
+        .map_err(|e| format!("Failed to read history: {e}"))?;
+    let mut size = 0;
+    let keep: Vec<&str> = data
+        .lines()
+        .rev()
+        .take(KEEP_ENTRIES)
+        .take_while(|line| {
+            size += line.len() + 1;
+            size <= TRIM_TO_BYTES
+        })
+        .collect();
+    let mut out = String::with_capacity(size);
+    for line in keep.iter().rev() {
+        out.push_str(line);
+        out.push('\n');
+    }
+    std::fs::write(history_path(), out).map_err(|e| format!("Failed to write history: {e}"))
+}
+
+/// All entries, oldest first. Lines that fail to parse are skipped.
+pub fn load() -> Vec<HistoryEntry> {
+    let _guard = WRITE_LOCK.lock().unwrap();
+    read_entries()
+}
+
+/// Empties the log.
+pub fn clear() -> Result<(), String> {
+    let _guard = WRITE_LOCK.lock().unwrap();
+    match std::fs::remove_file(history_path()) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(format!("Failed to clear history: {e}")),
+    }
+}
+
+fn read_entries() -> Vec<HistoryEntry> {
+    std::fs::read_to_string(history_path())
+        .map(|data| {
+            data.lines()
+                .filter_map(|l| serde_json::from_str::<HistoryEntry>(l).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
@@ -1,8 +1,9 @@
 use chrono::{DateTime, Utc};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
 
-use crate::config::AppConfig;
+use crate::config::{AppConfig, AuthMethod};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Attachment {
@@ -13,6 +14,110 @@ pub struct Attachment {
     pub content: String,
     #[serde(rename = "mimeType", default)]
     pub mime_type: String,
+    /// Reduced-size preview URL; JIRA only provides one for images.
+    #[serde(default)]
+    pub thumbnail: Option<String>,
+    #[serde(default)]
+    pub source: AttachmentSource,
+}
+
+/// Where on the issue an attachment was added.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum AttachmentSource {
+    /// The issue's own attachment panel.
+    #[default]
+    Issue,
+    /// Referenced from a comment, by the comment's author.
+    Comment { author: String },
+}
+
+/// Coarse attachment category derived from the MIME type, falling back to the extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AttachmentKind {
+    Image,
+    Log,
+    Video,
+    Archive,
+    Document,
+    Other,
+}
+
+impl AttachmentKind {
+    pub const ALL: [AttachmentKind; 6] = [
+        AttachmentKind::Image,
+        AttachmentKind::Log,
+        AttachmentKind::Video,
+        AttachmentKind::Archive,
+        AttachmentKind::Document,
+        AttachmentKind::Other,
+    ];
+
+    /// Plural label used in summaries, e.g. "3 images".
+    pub fn label(&self, count: usize) -> &'static str {
+        let one = count == 1;
+        match self {
+            AttachmentKind::Image => if one { "image" } else { "images" },
+            AttachmentKind::Log => if one { "log" } else { "logs" },
+            AttachmentKind::Video => if one { "video" } else { "videos" },
+            AttachmentKind::Archive => if one { "archive" } else { "archives" },
+            AttachmentKind::Document => if one { "document" } else { "documents" },
+            AttachmentKind::Other => "other",
+        }
+    }
+}
+
+impl Attachment {
+    pub fn kind(&self) -> AttachmentKind {
+        let mime = self.mime_type.to_lowercase();
+        let ext = std::path::Path::new(&self.filename)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase())
+            .unwrap_or_default();
+
+        if mime.starts_with("image/") {
+            return AttachmentKind::Image;
+        }
+        if mime.starts_with("video/") {
+            return AttachmentKind::Video;
+        }
+        match ext.as_str() {
+            "png" | "jpg" | "jpeg" | "gif" | "bmp" | "webp" | "svg" | "tif" | "tiff" => {
+                AttachmentKind::Image
+            }
+            "mp4" | "mov" | "avi" | "mkv" | "webm" | "wmv" => AttachmentKind::Video,
+            "log" | "txt" | "out" | "err" | "trace" | "csv" | "yaml" | "yml" => AttachmentKind::Log,
+            // Structured data (exports, configs, API dumps) rather than logs,
+            // even when served as text/xml.
+            "json" | "xml" => AttachmentKind::Other,
+            "zip" | "gz" | "tgz" | "tar" | "7z" | "rar" | "bz2" | "xz" => AttachmentKind::Archive,
+            "pdf" | "doc" | "docx" | "xls" | "xlsx" | "ppt" | "pptx" | "odt" | "rtf" => {
+                AttachmentKind::Document
+            }
+            _ if mime.starts_with("text/") => AttachmentKind::Log,
+            _ if mime.contains("zip") || mime.contains("compressed") || mime.contains("tar") => {
+                AttachmentKind::Archive
+            }
+            _ => AttachmentKind::Other,
+        }
+    }
+}
+
+#[cfg(test)]
+impl Attachment {
+    /// A plain-text attachment on the issue itself, uploaded now.
+    pub fn for_test(id: &str, filename: &str, size: u64) -> Self {
+        Attachment {
+            id: id.to_string(),
+            filename: filename.to_string(),
+            size,
+            created: Utc::now(),
+            content: String::new(),
+            mime_type: "text/plain".to_string(),
+            thumbnail: None,
+            source: AttachmentSource::Issue,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -21,6 +126,36 @@ pub struct IssueInfo {
     pub summary: String,
     pub status: String,
     pub attachments: Vec<Attachment>,
+    /// API version that answered, e.g. "3".
+    pub api_version: &'static str,
+}
+
+/// Why an issue couldn't be fetched. A body that doesn't parse is kept apart
+/// because forcing API v2 often gets around it.
+#[derive(Debug, Clone)]
+pub enum FetchIssueError {
+    Unparseable(String),
+    Failed(String),
+}
+
+impl std::fmt::Display for FetchIssueError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FetchIssueError::Unparseable(e) | FetchIssueError::Failed(e) => f.write_str(e),
+        }
+    }
+}
+
+impl From<String> for FetchIssueError {
+    fn from(e: String) -> Self {
+        FetchIssueError::Failed(e)
+    }
+}
+
+impl From<FetchIssueError> for String {
+    fn from(e: FetchIssueError) -> Self {
+        e.to_string()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -28,6 +163,8 @@ pub struct IssueSummary {
     pub key: String,
     pub summary: String,
     pub status: String,
+    /// Only known when My Cases is set to fetch attachment counts.
+    pub attachment_count: Option<usize>,
 }
 
 #[derive(Deserialize)]
@@ -43,6 +180,56 @@ struct JiraFields {
     status: JiraStatus,
     #[serde(default)]
     attachment: Vec<JiraAttachment>,
+    #[serde(default)]
+    comment: Option<JiraComments>,
+}
+
+#[derive(Deserialize)]
+struct JiraComments {
+    #[serde(default)]
+    comments: Vec<JiraComment>,
+}
+
+#[derive(Deserialize)]
+struct JiraComment {
+    #[serde(default)]
+    author: Option<JiraUser>,
+    /// Wiki markup (API v2) or an Atlassian Document Format tree (API v3).
+    #[serde(default)]
+    body: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+struct JiraUser {
+    #[serde(rename = "displayName", default)]
+    display_name: String,
+}
+
+#[derive(Deserialize)]
+struct JiraRelatedResponse {
+    fields: JiraRelatedFields,
+}
+
+#[derive(Deserialize)]
+struct JiraRelatedFields {
+    #[serde(default)]
+    subtasks: Vec<JiraIssueRef>,
+    #[serde(default)]
+    issuelinks: Vec<JiraIssueLink>,
+}
+
+#[derive(Deserialize)]
+struct JiraIssueRef {
+    key: String,
+}
+
+/// One side of a link is always the issue itself, so only the other is sent.
+#[derive(Deserialize)]
+struct JiraIssueLink {
+    #[serde(rename = "inwardIssue", default)]
+    inward_issue: Option<JiraIssueRef>,
+    #[serde(rename = "outwardIssue", default)]
+    outward_issue: Option<JiraIssueRef>,
 }
 
 #[derive(Deserialize)]
@@ -62,6 +249,8 @@ struct JiraAttachment {
     content: String,
     #[serde(rename = "mimeType", default)]
     mime_type: String,
+    #[serde(default)]
+    thumbnail: Option<String>,
 }
 
 /// JIRA sends dates as "2024-01-15T10:30:00.000+0000" (no colon in offset).
@@ -86,57 +275,438 @@ fn epoch() -> DateTime<Utc> {
     DateTime::UNIX_EPOCH
 }
 
+/// Times a rate-limited request is retried before the 429 is returned.
+const RATE_LIMIT_RETRIES: u32 = 3;
+/// Longest Retry-After we honour; anything longer is capped.
+const MAX_RATE_LIMIT_WAIT: std::time::Duration = std::time::Duration::from_secs(120);
+
+/// When the latest rate-limit wait ends; shared so the UI can show it.
+static RATE_LIMITED_UNTIL: Mutex<Option<std::time::Instant>> = Mutex::new(None);
+
+fn note_rate_limit(wait: std::time::Duration) {
+    let until = std::time::Instant::now() + wait;
+    let mut current = RATE_LIMITED_UNTIL.lock().unwrap();
+    if current.is_none_or(|c| c < until) {
+        *current = Some(until);
+    }
+}
+
+/// Time left until JIRA's rate limit lifts, while requests are waiting on it.
+pub fn rate_limit_remaining() -> Option<std::time::Duration> {
+    let until = (*RATE_LIMITED_UNTIL.lock().unwrap())?;
+    until.checked_duration_since(std::time::Instant::now())
+}
+
+/// API version each server answered on, keyed by its REST root, so later
+/// calls go straight to it instead of probing v3 then v2 every time.
+static DETECTED_API_VERSION: Mutex<std::collections::BTreeMap<String, &'static str>> =
+    Mutex::new(std::collections::BTreeMap::new());
+
+/// The API version detected earlier for this connection, if any.
+pub fn detected_api_version(config: &AppConfig) -> Option<&'static str> {
+    DETECTED_API_VERSION.lock().unwrap().get(&api_root(config)).copied()
+}
+
+/// Forgets the detected version so the next request probes again.
+pub fn reset_detected_api_version(config: &AppConfig) {
+    DETECTED_API_VERSION.lock().unwrap().remove(&api_root(config));
+}
+
+fn remember_api_version(config: &AppConfig, version: &'static str) {
+    let root = api_root(config);
+    let previous = DETECTED_API_VERSION.lock().unwrap().insert(root.clone(), version);
+    if previous != Some(version) {
+        log::info!("{root}: using REST API v{version}");
+    }
+}
+
+/// Token bucket shared by every download so the bandwidth limit caps their
+/// sum, kept as the moment the budget is next free.
+static BANDWIDTH_FREE_AT: Mutex<Option<std::time::Instant>> = Mutex::new(None);
+/// Idle budget a download may burst through after a quiet spell.
+const BANDWIDTH_BURST: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Waits until `bytes` more fit under `limit` bytes per second across all
+/// downloads. A limit of 0 means unlimited.
+async fn throttle(bytes: usize, limit: u64) {
+    if limit == 0 {
+        return;
+    }
+    let cost = std::time::Duration::from_secs_f64(bytes as f64 / limit as f64);
+    let wait = {
+        let now = std::time::Instant::now();
+        let earliest = now.checked_sub(BANDWIDTH_BURST).unwrap_or(now);
+        let mut free_at = BANDWIDTH_FREE_AT.lock().unwrap();
+        let start = free_at.map_or(earliest, |t| t.max(earliest));
+        let end = start + cost;
+        *free_at = Some(end);
+        end.saturating_duration_since(now)
+    };
+    if !wait.is_zero() {
+        tokio::time::sleep(wait).await;
+    }
+}
+
+/// `Retry-After` is either delay-seconds or an HTTP-date.
+fn parse_retry_after(value: &str) -> Option<std::time::Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(std::time::Duration::from_secs(secs));
+    }
+    let at = DateTime::parse_from_rfc2822(value).ok()?;
+    (at.with_timezone(&Utc) - Utc::now()).to_std().ok()
+}
+
+/// Config fields that affect how the HTTP client itself is built.
+#[derive(Debug, Clone, PartialEq)]
+struct ClientSettings {
+    proxy_url: String,
+    proxy_username: String,
+    proxy_password: String,
+    user_agent: String,
+    ca_bundle: Option<std::path::PathBuf>,
+    accept_invalid_certs: bool,
+    connect_timeout_secs: u64,
+}
+
+impl ClientSettings {
+    fn from_config(config: &AppConfig) -> Self {
+        Self {
+            proxy_url: config.proxy_url.trim().to_string(),
+            proxy_username: config.proxy_username.clone(),
+            proxy_password: config.proxy_password.clone(),
+            user_agent: config.effective_user_agent().to_string(),
+            ca_bundle: config.ca_bundle.clone(),
+            accept_invalid_certs: config.accept_invalid_certs,
+            connect_timeout_secs: config.connect_timeout_secs,
+        }
+    }
+
+    fn build(&self) -> Result<Client, String> {
+        // Without an explicit proxy, reqwest picks up HTTPS_PROXY/HTTP_PROXY itself.
+        let mut builder = Client::builder()
+            .user_agent(self.user_agent.as_str())
+            .connect_timeout(std::time::Duration::from_secs(self.connect_timeout_secs.max(1)));
+        if !self.proxy_url.is_empty() {
+            let mut proxy = reqwest::Proxy::all(&self.proxy_url)
+                .map_err(|e| format!("Invalid proxy URL '{}': {e}", self.proxy_url))?;
+            if !self.proxy_username.is_empty() {
+                proxy = proxy.basic_auth(&self.proxy_username, &self.proxy_password);
+            }
+            builder = builder.proxy(proxy);
+        }
+        if let Some(path) = &self.ca_bundle {
+            for cert in load_ca_bundle(path)? {
+                builder = builder.add_root_certificate(cert);
+            }
+        }
+        if self.accept_invalid_certs {
+            builder = builder.danger_accept_invalid_certs(true);
+        }
+        builder
+            .build()
+            .map_err(|e| format!("Failed to set up HTTP client: {e}"))
+    }
+}
+
+/// Every certificate in a PEM bundle; an unreadable or empty file is an error
+/// so requests don't silently go out without the intended roots.
+pub fn load_ca_bundle(path: &std::path::Path) -> Result<Vec<reqwest::Certificate>, String> {
+    let pem = std::fs::read(path)
+        .map_err(|e| format!("Could not read CA bundle {}: {e}", path.display()))?;
+    let certs = reqwest::Certificate::from_pem_bundle(&pem)
+        .map_err(|e| format!("Invalid CA bundle {}: {e}", path.display()))?;
+    if certs.is_empty() {
+        return Err(format!("No certificates found in CA bundle {}", path.display()));
+    }
+    Ok(certs)
+}
+
+/// Why a request never got an HTTP response, as far as the error tells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SendFailure {
+    Timeout,
+    /// Rejected during the TLS handshake.
+    Certificate,
+    /// The host name didn't resolve: a typo, or no network / VPN.
+    Dns,
+    /// The machine has no route to the network at all.
+    Offline,
+    /// Reached the host, but nothing listens on that port.
+    Refused,
+    Connect,
+    Other,
+}
+
+impl SendFailure {
+    fn of(e: &reqwest::Error) -> Self {
+        let mut chain = String::new();
+        let mut source: Option<&dyn std::error::Error> = Some(e);
+        while let Some(err) = source {
+            chain.push_str(&err.to_string().to_lowercase());
+            chain.push('\n');
+            source = err.source();
+        }
+        Self::classify(e.is_timeout(), e.is_connect(), &chain)
+    }
+
+    /// `chain` is every message in the error's source chain, lower-cased.
+    /// The wording comes from hyper and the OS, so several variants are matched.
+    fn classify(timeout: bool, connect: bool, chain: &str) -> Self {
+        let any = |needles: &[&str]| needles.iter().any(|n| chain.contains(n));
+        if timeout {
+            SendFailure::Timeout
+        } else if any(&["certificate", "unknownissuer"]) {
+            SendFailure::Certificate
+        } else if any(&[
+            "dns error",
+            "failed to lookup address",
+            "no such host",
+            "name or service not known",
+            "nodename nor servname",
+            "temporary failure in name resolution",
+        ]) {
+            SendFailure::Dns
+        } else if any(&[
+            "network is unreachable",
+            "no route to host",
+            "network unreachable",
+            "unreachable network",
+        ]) {
+            SendFailure::Offline
+        } else if any(&["connection refused", "actively refused"]) {
+            SendFailure::Refused
+        } else if connect {
+            SendFailure::Connect
+        } else {
+            SendFailure::Other
+        }
+    }
+}
+
+/// One connection pool for the whole app, so back-to-back requests reuse
+/// keep-alive connections and TLS sessions. Rebuilt only when the proxy or
+/// certificate settings change. `Client` is a cheap handle.
+fn shared_client(config: &AppConfig) -> Result<Client, String> {
+    static CLIENT: Mutex<Option<(ClientSettings, Client)>> = Mutex::new(None);
+
+    let settings = ClientSettings::from_config(config);
+    let mut cached = CLIENT.lock().unwrap();
+    if let Some((current, client)) = cached.as_ref() {
+        if *current == settings {
+            return Ok(client.clone());
+        }
+    }
+    let client = settings.build()?;
+    *cached = Some((settings, client.clone()));
+    Ok(client)
+}
+
+/// The proxy requests will go through, for error messages.
+fn active_proxy(config: &AppConfig) -> Option<String> {
+    let configured = config.proxy_url.trim();
+    if !configured.is_empty() {
+        return Some(configured.to_string());
+    }
+    ["HTTPS_PROXY", "https_proxy", "HTTP_PROXY", "http_proxy"]
+        .iter()
+        .find_map(|var| std::env::var(var).ok().filter(|v| !v.is_empty()))
+}
+
 pub struct JiraClient {
-    client: Client,
+    /// Err when the proxy settings are unusable; reported by every request.
+    client: Result<Client, String>,
     config: AppConfig,
 }
 
 impl JiraClient {
     pub fn new(config: AppConfig) -> Self {
         Self {
-            client: Client::new(),
+            client: shared_client(&config),
             config,
         }
     }
 
     fn base_url(&self) -> String {
-        // Strip any extra path — only keep scheme + host (+ optional port)
-        let url = self.config.jira_url.trim_end_matches('/');
-        if let Ok(parsed) = url::Url::parse(url) {
-            let mut base = format!("{}://{}", parsed.scheme(), parsed.host_str().unwrap_or(""));
-            if let Some(port) = parsed.port() {
-                base.push_str(&format!(":{port}"));
-            }
-            // Keep context path if present (e.g. /jira for Jira Server)
-            let path = parsed.path().trim_end_matches('/');
-            if !path.is_empty() && path != "/" {
-                base.push_str(path);
-            }
-            base
-        } else {
-            url.to_string()
-        }
+        base_url(&self.config.jira_url)
     }
 
     fn auth(&self) -> reqwest::header::HeaderValue {
         use base64::Engine;
-        let creds = format!("{}:{}", self.config.email, self.config.api_token);
-        let encoded = base64::engine::general_purpose::STANDARD.encode(creds.as_bytes());
-        reqwest::header::HeaderValue::from_str(&format!("Basic {encoded}")).unwrap()
+        let value = match self.config.auth_method {
+            AuthMethod::Basic => {
+                let creds = format!("{}:{}", self.config.email, self.config.api_token.trim());
+                let encoded = base64::engine::general_purpose::STANDARD.encode(creds.as_bytes());
+                format!("Basic {encoded}")
+            }
+            AuthMethod::Bearer => format!("Bearer {}", self.config.api_token.trim()),
+        };
+        reqwest::header::HeaderValue::from_str(&value)
+            .unwrap_or_else(|_| reqwest::header::HeaderValue::from_static(""))
+    }
+
+    /// REST endpoint URL, e.g. `api_url("api/2/myself")`. The `/rest` segment can be
+    /// replaced via `api_prefix` for reverse proxies that remap the API path.
+    fn api_url(&self, path: &str) -> String {
+        format!("{}/{path}", api_root(&self.config))
+    }
+
+    /// `probe` in order, except that the version detected earlier goes first
+    /// when it's one of them. The others stay as fallbacks.
+    fn api_versions(&self, probe: &[&'static str]) -> Vec<&'static str> {
+        let mut versions = probe.to_vec();
+        let detected = detected_api_version(&self.config);
+        if let Some(pos) = versions.iter().position(|&v| Some(v) == detected) {
+            let first = versions.remove(pos);
+            versions.insert(0, first);
+        }
+        versions
+    }
+
+    /// Whether `version` is the one detected earlier, so a 404 from it means
+    /// the issue is missing rather than the version unsupported.
+    fn is_detected(&self, version: &str) -> bool {
+        detected_api_version(&self.config) == Some(version)
+    }
+
+    /// GET request with authorization plus any custom headers from the config.
+    /// A custom `Authorization` header replaces the computed one; invalid
+    /// headers are skipped (Settings flags them).
+    fn request(&self, url: &str) -> Result<reqwest::RequestBuilder, String> {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(reqwest::header::AUTHORIZATION, self.auth());
+        for h in &self.config.custom_headers {
+            if let Ok((name, value)) = parse_header(&h.name, &h.value) {
+                headers.insert(name, value);
+            }
+        }
+        let client = self.client.as_ref().map_err(|e| e.clone())?;
+        Ok(client.get(url).headers(headers))
+    }
+
+    fn request_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.config.request_timeout_secs.max(1))
+    }
+
+    /// Explains a failed send, calling out timeouts and the proxy when one is in play.
+    fn send_error(&self, e: reqwest::Error, url: &str) -> String {
+        let failure = SendFailure::of(&e);
+        let host = url::Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_string))
+            .unwrap_or_else(|| url.to_string());
+        match failure {
+            SendFailure::Timeout => {
+                let secs = if e.is_connect() {
+                    self.config.connect_timeout_secs
+                } else {
+                    self.config.request_timeout_secs
+                };
+                return format!("Timed out after {secs}s\nURL: {url}");
+            }
+            SendFailure::Certificate => {
+                let hint = if self.config.ca_bundle.is_some() {
+                    "the configured CA bundle doesn't cover this server's certificate"
+                } else {
+                    "if JIRA uses a certificate from a private CA, set a CA bundle in Settings"
+                };
+                return format!("Server certificate error — TLS verification failed ({hint}): {e}\nURL: {url}");
+            }
+            _ => {}
+        }
+        if let Some(proxy) = active_proxy(&self.config) {
+            if failure != SendFailure::Other {
+                return format!("Could not connect through proxy {proxy}: {e}\nURL: {url}");
+            }
+        }
+        match failure {
+            SendFailure::Dns => format!(
+                "Can't find the server {host} — check the JIRA URL, and that you're online \
+                 (and on the VPN if JIRA needs one). DNS lookup failed.\nURL: {url}"
+            ),
+            SendFailure::Offline => format!(
+                "No network connection — this machine can't reach the network.\nURL: {url}"
+            ),
+            SendFailure::Refused => format!(
+                "{host} refused the connection — check the port in the JIRA URL.\nURL: {url}"
+            ),
+            SendFailure::Connect => format!("Could not connect to {host}: {e}\nURL: {url}"),
+            _ => format!("Request failed: {e}\nURL: {url}"),
+        }
+    }
+
+    /// Sends a GET, transparently waiting out `429 Too Many Requests` up to
+    /// `RATE_LIMIT_RETRIES` times. `api` requests expect JSON and get the
+    /// overall request timeout; downloads handle stalls themselves.
+    /// Also returns how many times it retried after being rate limited.
+    async fn send(&self, url: &str, api: bool) -> Result<(reqwest::Response, u32), String> {
+        let mut attempt = 0;
+        loop {
+            let mut req = self.request(url)?;
+            if api {
+                req = req
+                    .header(reqwest::header::ACCEPT, "application/json")
+                    .timeout(self.request_timeout());
+            }
+            log::debug!("GET {url}");
+            // API calls time out as a whole. Downloads can't — big files take
+            // long — so only the wait for the headers is limited here; the
+            // body then has its own stall timeout.
+            let sent = if api {
+                req.send().await
+            } else {
+                let timeout = self.request_timeout();
+                tokio::time::timeout(timeout, req.send()).await.map_err(|_| {
+                    let secs = timeout.as_secs();
+                    let msg = format!("Timed out after {secs}s waiting for a response\nURL: {url}");
+                    log::warn!("GET {url} failed: {msg}");
+                    msg
+                })?
+            };
+            let resp = sent.map_err(|e| {
+                let msg = self.send_error(e, url);
+                log::warn!("GET {url} failed: {msg}");
+                msg
+            })?;
+
+            let status = resp.status();
+            if status.is_success() {
+                log::debug!("GET {url} -> {status}");
+            } else {
+                log::warn!("GET {url} -> {status}");
+            }
+            if status != reqwest::StatusCode::TOO_MANY_REQUESTS || attempt >= RATE_LIMIT_RETRIES {
+                return Ok((resp, attempt));
+            }
+            attempt += 1;
+
+            let wait = resp
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(parse_retry_after)
+                .unwrap_or(std::time::Duration::from_secs(5))
+                .min(MAX_RATE_LIMIT_WAIT);
+            log::warn!(
+                "Rate limited on {url}; retry {attempt}/{RATE_LIMIT_RETRIES} in {}s",
+                wait.as_secs()
+            );
+            note_rate_limit(wait);
+            tokio::time::sleep(wait).await;
+        }
     }
 
     /// Returns (status, content_type, body)
     async fn get_raw(&self, url: &str) -> Result<(reqwest::StatusCode, String, String), String> {
-        let resp = self
-            .client
-            .get(url)
-            .header(reqwest::header::AUTHORIZATION, self.auth())
-            .header(reqwest::header::ACCEPT, "application/json")
-            .send()
-            .await
-            .map_err(|e| format!("Request failed: {e}\nURL: {url}"))?;
+        let (resp, _) = self.send(url, true).await?;
 
         let status = resp.status();
+        if status == reqwest::StatusCode::PROXY_AUTHENTICATION_REQUIRED {
+            return Err(
+                "Proxy authentication required (HTTP 407) — check the proxy username and password."
+                    .to_string(),
+            );
+        }
         let content_type = resp
             .headers()
             .get(reqwest::header::CONTENT_TYPE)
@@ -166,9 +736,14 @@ impl JiraClient {
     }
 
     pub async fn test_connection(&self) -> Result<String, String> {
-        // Try API v3 first (Cloud), fall back to v2 (Server/Data Center)
-        for api_ver in &["3", "2"] {
-            let url = format!("{}/rest/api/{}/myself", self.base_url(), api_ver);
+        // Try API v3 first (Cloud), fall back to v2 (Server/Data Center).
+        // PATs only exist on Server/DC, which serves v2.
+        let versions = self.api_versions(match self.config.auth_method {
+            AuthMethod::Basic => &["3", "2"],
+            AuthMethod::Bearer => &["2"],
+        });
+        for api_ver in versions {
+            let url = self.api_url(&format!("api/{api_ver}/myself"));
             let (status, ct, body) = self.get_raw(&url).await?;
 
             if let Some(err) = Self::check_html_response(status, &ct, &body, &url) {
@@ -177,31 +752,47 @@ impl JiraClient {
 
             if status.is_success() {
                 let parsed: serde_json::Value = serde_json::from_str(&body)
-                    .map_err(|e| format!("Parse error: {e}\nBody: {}", &body[..body.len().min(300)]))?;
+                    .map_err(|e| format!("Parse error: {e}\nBody: {}", body_excerpt(&body, 300)))?;
                 let name = parsed["displayName"].as_str().unwrap_or("unknown");
+                remember_api_version(&self.config, api_ver);
                 return Ok(format!("Connected as: {name} (API v{api_ver})"));
             }
         }
+        let what = match self.config.auth_method {
+            AuthMethod::Basic => "your email and API token",
+            AuthMethod::Bearer => "your Personal Access Token",
+        };
         Err(format!(
-            "Authentication failed.\nCheck your email and API token.\nJIRA URL: {}",
+            "Authentication failed.\nCheck {what}.\nJIRA URL: {}",
             self.base_url()
         ))
     }
 
     pub async fn fetch_my_issues(&self) -> Result<Vec<IssueSummary>, String> {
-        // JQL: all unresolved issues assigned to the current user, newest first
-        let jql = "assignee = currentUser() AND statusCategory != Done ORDER BY updated DESC";
+        let jql = match self.config.my_issues_jql.trim() {
+            "" => crate::config::DEFAULT_MY_ISSUES_JQL,
+            custom => custom,
+        };
         let encoded_jql = url::form_urlencoded::byte_serialize(jql.as_bytes()).collect::<String>();
+        let fields = if self.config.my_cases_attachment_counts {
+            "summary,status,attachment"
+        } else {
+            "summary,status"
+        };
 
         // Try the new /search/jql endpoint first (required as of 2025),
         // fall back to the old /search for on-prem JIRA Server/Data Center.
-        for endpoint in &["rest/api/3/search/jql", "rest/api/2/search"] {
-            let url = format!(
-                "{}/{}?jql={}&fields=summary,status&maxResults=100",
-                self.base_url(),
-                endpoint,
-                encoded_jql
-            );
+        // Only "not there" answers fall back; anything else is reported as is.
+        // A server already known to be v2-only skips the first attempt.
+        let mut jql_endpoint_status = None;
+        let endpoints: &[&str] = match detected_api_version(&self.config) {
+            Some("2") => &["api/2/search"],
+            _ => &["api/3/search/jql", "api/2/search"],
+        };
+        for endpoint in endpoints {
+            let url = self.api_url(&format!(
+                "{endpoint}?jql={encoded_jql}&fields={fields}&maxResults=100"
+            ));
 
             let (status, ct, body) = self.get_raw(&url).await?;
 
@@ -211,13 +802,34 @@ impl JiraClient {
 
             if (status == reqwest::StatusCode::NOT_FOUND
                 || status == reqwest::StatusCode::GONE)
-                && *endpoint == "rest/api/3/search/jql"
+                && *endpoint == "api/3/search/jql"
             {
+                jql_endpoint_status = Some(status);
                 continue;
             }
 
+            if status == reqwest::StatusCode::BAD_REQUEST {
+                return Err(match jira_error_messages(&body) {
+                    Some(msg) => format!("JIRA rejected the My Cases query: {msg}"),
+                    None => "JIRA rejected the My Cases query (HTTP 400). Check the JQL in Settings.".to_string(),
+                });
+            }
+
+            if *endpoint == "api/2/search" && is_search_retired(status, &body) {
+                let detail = jira_error_messages(&body).unwrap_or_else(|| format!("HTTP {status}"));
+                let tried = jql_endpoint_status
+                    .map(|s| format!(" (it answered HTTP {s})"))
+                    .unwrap_or_default();
+                return Err(format!(
+                    "JIRA has retired the old search API: {detail}\n\
+                     The newer /rest/api/3/search/jql endpoint wasn't reachable either{tried}. \
+                     Check that the JIRA URL and API path prefix in Settings point straight at \
+                     your JIRA site, and that no proxy blocks that path."
+                ));
+            }
+
             if !status.is_success() {
-                return Err(format!("HTTP {status}: {}", &body[..body.len().min(300)]));
+                return Err(format!("HTTP {status}: {}", body_excerpt(&body, 300)));
             }
 
             #[derive(Deserialize)]
@@ -234,11 +846,19 @@ impl JiraClient {
                 #[serde(default)]
                 summary: String,
                 status: JiraStatus,
+                /// Only the length matters, so the entries aren't parsed.
+                #[serde(default)]
+                attachment: Option<Vec<serde::de::IgnoredAny>>,
             }
 
             let resp: SearchResponse = serde_json::from_str(&body).map_err(|e| {
-                format!("Failed to parse search response: {e}\nRaw: {}", &body[..body.len().min(300)])
+                format!("Failed to parse search response: {e}\nRaw: {}", body_excerpt(&body, 300))
             })?;
+            if *endpoint == "api/3/search/jql" {
+                remember_api_version(&self.config, "3");
+            } else if jql_endpoint_status.is_some() {
+                remember_api_version(&self.config, "2");
+            }
 
             return Ok(resp
                 .issues
@@ -247,6 +867,7 @@ impl JiraClient {
                     key: i.key,
                     summary: i.fields.summary,
                     status: i.fields.status.name,
+                    attachment_count: i.fields.attachment.map(|a| a.len()),
                 })
                 .collect());
         }
@@ -254,68 +875,119 @@ impl JiraClient {
         Ok(vec![])
     }
 
-    pub async fn fetch_issue(&self, key: &str) -> Result<IssueInfo, String> {
-        // Try API v3 first, fall back to v2
-        for api_ver in &["3", "2"] {
-            let url = format!(
-                "{}/rest/api/{}/issue/{}?fields=summary,status,attachment",
-                self.base_url(),
-                api_ver,
-                key
-            );
+    pub async fn fetch_issue(&self, key: &str) -> Result<IssueInfo, FetchIssueError> {
+        // Try API v3 first, fall back to v2, unless the server's version is known
+        let versions = self.api_versions(&["3", "2"]);
+        self.fetch_issue_with_versions(key, &versions).await
+    }
+
+    /// Tries each API version in order. A 404 or a response body that doesn't parse
+    /// moves on to the next version; the last parse error is returned if none work.
+    /// A 404 from the version detected earlier is final, since that version is
+    /// known to exist. A version reached by probing past 404s is remembered for
+    /// later calls.
+    pub async fn fetch_issue_with_versions(
+        &self,
+        key: &str,
+        api_versions: &[&'static str],
+    ) -> Result<IssueInfo, FetchIssueError> {
+        let mut parse_error: Option<String> = None;
+
+        for (idx, api_ver) in api_versions.iter().enumerate() {
+            let is_last = idx + 1 == api_versions.len();
+            let url = self.api_url(&format!(
+                "api/{api_ver}/issue/{key}?fields=summary,status,attachment,comment"
+            ));
 
             let (status, ct, body) = self.get_raw(&url).await?;
 
             if let Some(err) = Self::check_html_response(status, &ct, &body, &url) {
-                return Err(err);
+                return Err(err.into());
             }
 
-            if status == reqwest::StatusCode::NOT_FOUND && *api_ver == "3" {
-                continue; // try v2
+            if status == reqwest::StatusCode::NOT_FOUND && !is_last && !self.is_detected(api_ver) {
+                continue; // try the next version
+            }
+
+            if status == reqwest::StatusCode::NOT_FOUND || status == reqwest::StatusCode::FORBIDDEN {
+                return Err(FetchIssueError::Failed(format!(
+                    "{key} not found or not accessible (HTTP {status}) — its project may have been \
+                     archived, or you may lack permission to view it.\nURL: {url}"
+                )));
             }
 
             if !status.is_success() {
-                return Err(format!("HTTP {status}\nURL: {url}\nBody: {}", &body[..body.len().min(300)]));
+                return Err(format!("HTTP {status}\nURL: {url}\nBody: {}", body_excerpt(&body, 300)).into());
             }
 
-            let issue: JiraIssueResponse = serde_json::from_str(&body).map_err(|e| {
-                let snippet = &body[..body.len().min(500)];
-                format!("Failed to parse response (API v{api_ver}): {e}\nRaw: {snippet}")
-            })?;
+            let issue: JiraIssueResponse = match serde_json::from_str(&body) {
+                Ok(issue) => issue,
+                Err(e) => {
+                    let snippet = body_excerpt(&body, 500);
+                    parse_error = Some(format!(
+                        "Failed to parse response (API v{api_ver}): {e}\nRaw: {snippet}"
+                    ));
+                    continue;
+                }
+            };
 
+            // Files dropped into comments are stored as issue attachments too;
+            // the comment bodies tell us which ones, and who added them.
+            let mut from_comments: std::collections::HashMap<String, String> =
+                std::collections::HashMap::new();
+            for comment in issue.fields.comment.map(|c| c.comments).unwrap_or_default() {
+                let author = comment.author.map(|a| a.display_name).unwrap_or_default();
+                let mut names = Vec::new();
+                comment_file_references(&comment.body, &mut names);
+                for name in names {
+                    from_comments.entry(name).or_insert_with(|| author.clone());
+                }
+            }
+
+            let mut seen = std::collections::HashSet::new();
             let attachments = issue
                 .fields
                 .attachment
                 .into_iter()
+                .filter(|a| seen.insert(a.id.clone()))
                 .map(|a| Attachment {
+                    source: match from_comments.get(&a.filename) {
+                        Some(author) => AttachmentSource::Comment { author: author.clone() },
+                        None => AttachmentSource::Issue,
+                    },
                     id: a.id,
                     filename: a.filename,
                     size: a.size,
                     created: a.created,
                     content: a.content,
                     mime_type: a.mime_type,
+                    thumbnail: a.thumbnail,
                 })
                 .collect();
 
+            if parse_error.is_none() && api_versions.len() > 1 {
+                remember_api_version(&self.config, api_ver);
+            }
             return Ok(IssueInfo {
                 key: issue.key,
                 summary: issue.fields.summary,
                 status: issue.fields.status.name,
                 attachments,
+                api_version: api_ver,
             });
         }
 
-        Err(format!("Issue {} not found on {}", key, self.base_url()))
+        Err(match parse_error {
+            Some(e) => FetchIssueError::Unparseable(e),
+            None => FetchIssueError::Failed(format!("Issue {} not found on {}", key, self.base_url())),
+        })
     }
 
     pub async fn fetch_issue_status(&self, key: &str) -> Result<String, String> {
-        for api_ver in &["3", "2"] {
-            let url = format!(
-                "{}/rest/api/{}/issue/{}?fields=status",
-                self.base_url(),
-                api_ver,
-                key
-            );
+        let versions = self.api_versions(&["3", "2"]);
+        for (idx, api_ver) in versions.iter().enumerate() {
+            let is_last = idx + 1 == versions.len();
+            let url = self.api_url(&format!("api/{api_ver}/issue/{key}?fields=status"));
 
             let (status, ct, body) = self.get_raw(&url).await?;
 
@@ -323,59 +995,332 @@ impl JiraClient {
                 return Err(err);
             }
 
-            if status == reqwest::StatusCode::NOT_FOUND && *api_ver == "3" {
+            if status == reqwest::StatusCode::NOT_FOUND && !is_last && !self.is_detected(api_ver) {
                 continue;
             }
 
             if !status.is_success() {
-                return Err(format!("HTTP {status}: {}", &body[..body.len().min(200)]));
+                return Err(format!("HTTP {status}: {}", body_excerpt(&body, 200)));
             }
 
             let issue: JiraIssueResponse = serde_json::from_str(&body).map_err(|e| {
                 format!("Parse error: {e}")
             })?;
 
+            if versions.len() > 1 {
+                remember_api_version(&self.config, api_ver);
+            }
             return Ok(issue.fields.status.name);
         }
 
         Err(format!("Issue {} not found", key))
     }
 
+    /// Keys of the issue's subtasks and/or linked issues, subtasks first, each once.
+    pub async fn fetch_related_keys(
+        &self,
+        key: &str,
+        subtasks: bool,
+        links: bool,
+    ) -> Result<Vec<String>, String> {
+        let versions = self.api_versions(&["3", "2"]);
+        for (idx, api_ver) in versions.iter().enumerate() {
+            let is_last = idx + 1 == versions.len();
+            let url = self.api_url(&format!("api/{api_ver}/issue/{key}?fields=subtasks,issuelinks"));
+
+            let (status, ct, body) = self.get_raw(&url).await?;
+
+            if let Some(err) = Self::check_html_response(status, &ct, &body, &url) {
+                return Err(err);
+            }
+
+            if status == reqwest::StatusCode::NOT_FOUND && !is_last && !self.is_detected(api_ver) {
+                continue;
+            }
+
+            if !status.is_success() {
+                return Err(format!("HTTP {status}: {}", body_excerpt(&body, 200)));
+            }
+
+            let issue: JiraRelatedResponse = serde_json::from_str(&body).map_err(|e| {
+                format!("Parse error: {e}")
+            })?;
+
+            if versions.len() > 1 {
+                remember_api_version(&self.config, api_ver);
+            }
+            let fields = issue.fields;
+            let mut keys: Vec<String> = Vec::new();
+            let sub = fields.subtasks.into_iter().filter(|_| subtasks);
+            let linked = fields
+                .issuelinks
+                .into_iter()
+                .filter(|_| links)
+                .filter_map(|l| l.inward_issue.or(l.outward_issue));
+            for related in sub.chain(linked) {
+                if related.key != key && !keys.contains(&related.key) {
+                    keys.push(related.key);
+                }
+            }
+            return Ok(keys);
+        }
+
+        Err(format!("Issue {} not found", key))
+    }
+
+    /// Small downloads such as thumbnails, kept in memory.
+    pub async fn fetch_bytes(&self, url: &str) -> Result<Vec<u8>, String> {
+        let (resp, _) = self.send(url, false).await?;
+        if !resp.status().is_success() {
+            return Err(format!("HTTP {}", resp.status()));
+        }
+        let timeout = self.request_timeout();
+        tokio::time::timeout(timeout, resp.bytes())
+            .await
+            .map_err(|_| format!("Timed out after {}s without data", timeout.as_secs()))?
+            .map(|b| b.to_vec())
+            .map_err(|e| format!("Stream error: {e}"))
+    }
+
+    /// Streams the attachment into `<target>.part` and renames it to `target`
+    /// once complete, so memory stays flat for multi-GB files. The partial
+    /// file is removed on any error. Returns the number of bytes written and
+    /// how often the request was retried after rate limiting.
     pub async fn download_attachment(
         &self,
         url: &str,
+        target: &std::path::Path,
+        on_progress: impl Fn(u64, u64) + Send + 'static,
+    ) -> Result<(u64, u32), String> {
+        let part = crate::storage::part_path(target);
+
+        let result = self.stream_to_file(url, &part, on_progress).await;
+        let result = match result {
+            Ok(n) => tokio::fs::rename(&part, target)
+                .await
+                .map(|_| n)
+                .map_err(|e| format!("Failed to finish file: {e}")),
+            Err(e) => Err(e),
+        };
+        if result.is_err() {
+            let _ = tokio::fs::remove_file(&part).await;
+        }
+        result
+    }
+
+    async fn stream_to_file(
+        &self,
+        url: &str,
+        path: &std::path::Path,
         on_progress: impl Fn(u64, u64) + Send + 'static,
-    ) -> Result<bytes::Bytes, String> {
+    ) -> Result<(u64, u32), String> {
         use futures::StreamExt;
+        use tokio::io::AsyncWriteExt;
 
-        let resp = self
-            .client
-            .get(url)
-            .header(reqwest::header::AUTHORIZATION, self.auth())
-            .send()
-            .await
-            .map_err(|e| format!("Request failed: {e}"))?;
+        let (resp, retries) = self.send(url, false).await?;
 
+        if resp.status() == reqwest::StatusCode::PROXY_AUTHENTICATION_REQUIRED {
+            return Err("Proxy authentication required (HTTP 407)".to_string());
+        }
         if !resp.status().is_success() {
             return Err(format!("HTTP {}", resp.status()));
         }
 
+        let mut file = tokio::fs::File::create(path)
+            .await
+            .map_err(|e| format!("Failed to create file: {e}"))?;
+
         let total = resp.content_length().unwrap_or(0);
         let mut downloaded: u64 = 0;
-        let mut buf = bytes::BytesMut::new();
         let mut stream = resp.bytes_stream();
 
-        while let Some(chunk) = stream.next().await {
+        // No overall limit — big files legitimately take long — but a stalled
+        // transfer fails once no data has arrived for the request timeout.
+        let stall = self.request_timeout();
+        loop {
+            let next = tokio::time::timeout(stall, stream.next())
+                .await
+                .map_err(|_| format!("Timed out after {}s without data", stall.as_secs()))?;
+            let Some(chunk) = next else {
+                break;
+            };
             let chunk = chunk.map_err(|e| format!("Stream error: {e}"))?;
+            file.write_all(&chunk)
+                .await
+                .map_err(|e| format!("Failed to write file: {e}"))?;
             downloaded += chunk.len() as u64;
-            buf.extend_from_slice(&chunk);
             on_progress(downloaded, total);
+            throttle(chunk.len(), self.config.max_download_rate_kib.saturating_mul(1024)).await;
         }
 
-        Ok(buf.freeze())
+        file.flush()
+            .await
+            .map_err(|e| format!("Failed to write file: {e}"))?;
+        Ok((downloaded, retries))
+    }
+}
+
+/// Cloud answers the removed `/rest/api/2/search` with 410 Gone, or with an
+/// error telling clients to migrate to `/search/jql`.
+fn is_search_retired(status: reqwest::StatusCode, body: &str) -> bool {
+    if status == reqwest::StatusCode::GONE {
+        return true;
+    }
+    !status.is_success()
+        && jira_error_messages(body).is_some_and(|msg| {
+            let msg = msg.to_lowercase();
+            msg.contains("search/jql") || msg.contains("migrate") || msg.contains("has been removed")
+        })
+}
+
+/// At most the first `max` bytes of a response body for an error message,
+/// cut back to a character boundary.
+fn body_excerpt(body: &str, max: usize) -> &str {
+    let mut end = body.len().min(max);
+    while !body.is_char_boundary(end) {
+        end -= 1;
+    }
+    &body[..end]
+}
+
+/// The human-readable part of a JIRA error body
+/// (`{"errorMessages": [...], "errors": {...}}`).
+fn jira_error_messages(body: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(body).ok()?;
+    let mut messages: Vec<String> = value["errorMessages"]
+        .as_array()
+        .map(|a| a.iter().filter_map(|m| m.as_str().map(str::to_string)).collect())
+        .unwrap_or_default();
+    if let Some(errors) = value["errors"].as_object() {
+        messages.extend(errors.values().filter_map(|m| m.as_str().map(str::to_string)));
     }
+    (!messages.is_empty()).then(|| messages.join("\n"))
 }
 
+/// Collects file names a comment body points at: `[^file]` and `!file!`
+/// in wiki markup, `media` nodes in ADF. Only used to tag real attachments,
+/// so an occasional false match is harmless.
+fn comment_file_references(body: &serde_json::Value, out: &mut Vec<String>) {
+    match body {
+        serde_json::Value::String(text) => {
+            for (open, close) in [("[^", ']'), ("!", '!')] {
+                let mut rest = text.as_str();
+                while let Some(start) = rest.find(open) {
+                    rest = &rest[start + open.len()..];
+                    let Some(end) = rest.find(close) else {
+                        break;
+                    };
+                    let inner = &rest[..end];
+                    // `!image.png|thumbnail!` carries display options after the bar.
+                    let name = inner.split('|').next().unwrap_or("").trim();
+                    if !name.is_empty() && !name.contains('\n') && name.contains('.') {
+                        out.push(name.to_string());
+                    }
+                    rest = &rest[end + 1..];
+                }
+            }
+        }
+        serde_json::Value::Object(node) => {
+            if node.get("type").and_then(|t| t.as_str()) == Some("media") {
+                if let Some(alt) = node.get("attrs").and_then(|a| a.get("alt")).and_then(|a| a.as_str()) {
+                    out.push(alt.to_string());
+                }
+            }
+            if let Some(content) = node.get("content") {
+                comment_file_references(content, out);
+            }
+        }
+        serde_json::Value::Array(nodes) => {
+            for node in nodes {
+                comment_file_references(node, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Scheme + host (+ port and context path) of the configured JIRA URL.
+/// A URL [`check_jira_url`] rejects is used as typed.
+pub fn base_url(jira_url: &str) -> String {
+    check_jira_url(jira_url).unwrap_or_else(|_| jira_url.trim().trim_end_matches('/').to_string())
+}
+
+/// Normalises a JIRA URL as typed in Settings into the base URL requests
+/// use: `https://` is assumed when no scheme is given, and anything after the
+/// context path (a pasted board, issue or API link) is dropped.
+pub fn check_jira_url(jira_url: &str) -> Result<String, String> {
+    let url = jira_url.trim().trim_end_matches('/');
+    if url.is_empty() {
+        return Err("Enter the address of your JIRA site.".to_string());
+    }
+    let with_scheme = if url.contains("://") {
+        url.to_string()
+    } else {
+        format!("https://{url}")
+    };
+    let parsed = url::Url::parse(&with_scheme).map_err(|e| format!("Not a valid URL: {e}"))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(format!("Unsupported scheme \"{}\" — use https://", parsed.scheme()));
+    }
+    let host = parsed.host_str().unwrap_or("");
+    if host.is_empty() {
+        return Err("The URL has no host name.".to_string());
+    }
+
+    let mut base = format!("{}://{host}", parsed.scheme());
+    if let Some(port) = parsed.port() {
+        base.push_str(&format!(":{port}"));
+    }
+    // Keep context path if present (e.g. /jira for Jira Server), but not a
+    // pasted API or page path: `/jira/rest/api/2` would otherwise get
+    // `/rest/...` twice. Cloud sites never have a context path.
+    if host.ends_with(".atlassian.net") {
+        return Ok(base);
+    }
+    for segment in parsed.path().split('/').filter(|s| !s.is_empty()) {
+        if PAGE_SEGMENTS.iter().any(|p| segment.eq_ignore_ascii_case(p)) {
+            break;
+        }
+        base.push('/');
+        base.push_str(segment);
+    }
+    Ok(base)
+}
+
+/// Path segments that start JIRA's API or web pages rather than a context path.
+const PAGE_SEGMENTS: &[&str] = &[
+    "rest", "browse", "secure", "projects", "issues", "plugins", "software", "servicedesk",
+    "login.jsp",
+];
+
+/// The REST root requests are built on, honouring `api_prefix` (`/rest` by default).
+fn api_root(config: &AppConfig) -> String {
+    let prefix = config.api_prefix.trim().trim_end_matches('/');
+    let prefix = if prefix.is_empty() { "/rest" } else { prefix };
+    let slash = if prefix.starts_with('/') { "" } else { "/" };
+    format!("{}{slash}{prefix}", base_url(&config.jira_url))
+}
+
+/// Link to the issue in the JIRA web UI.
+pub fn issue_browse_url(jira_url: &str, key: &str) -> String {
+    format!("{}/browse/{key}", base_url(jira_url))
+}
+
+/// Validates a user-supplied header; used both when sending and by Settings.
+pub fn parse_header(
+    name: &str,
+    value: &str,
+) -> Result<(reqwest::header::HeaderName, reqwest::header::HeaderValue), String> {
+    let name = reqwest::header::HeaderName::from_bytes(name.trim().as_bytes())
+        .map_err(|_| format!("Invalid header name: {name:?}"))?;
+    let value = reqwest::header::HeaderValue::from_str(value.trim())
+        .map_err(|_| format!("Invalid value for header {name}"))?;
+    Ok((name, value))
+}
+
+/// Query parameters JIRA's web UI uses to point at an issue.
+const ISSUE_QUERY_PARAMS: [&str; 4] = ["selectedIssue", "issueKey", "issue", "key"];
+
 /// Parse issue key from either "PROJ-123" or full JIRA URL
 pub fn parse_issue_key(input: &str) -> Option<String> {
     let input = input.trim();
@@ -389,13 +1334,33 @@ pub fn parse_issue_key(input: &str) -> Option<String> {
                 }
             }
         }
+        // Board and search views select the issue in a query parameter.
+        let parsed = url::Url::parse(input).ok();
+        if let Some(url) = &parsed {
+            for (name, value) in url.query_pairs() {
+                if ISSUE_QUERY_PARAMS.contains(&name.as_ref()) && is_valid_issue_key(&value) {
+                    return Some(value.to_uppercase());
+                }
+            }
+        }
         if let Some(last) = parts.last() {
             let key = last.split('?').next().unwrap_or("");
             if is_valid_issue_key(key) {
                 return Some(key.to_uppercase());
             }
         }
-        None
+        // Last resort: any upper-case PROJ-123 token outside the host name,
+        // which could itself look like a key (jira-1.corp.com).
+        let after_host = match &parsed {
+            Some(url) => &url[url::Position::BeforePath..],
+            None => input,
+        };
+        after_host
+            .split(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+            .find(|token| {
+                is_valid_issue_key(token) && token.chars().all(|c| !c.is_ascii_lowercase())
+            })
+            .map(str::to_string)
     } else if is_valid_issue_key(input) {
         Some(input.to_uppercase())
     } else {
@@ -403,15 +1368,288 @@ pub fn parse_issue_key(input: &str) -> Option<String> {
     }
 }
 
+/// Splits a pasted list on newlines, commas and spaces. Returns the distinct
+/// keys in order, and the entries that weren't a key or issue URL.
+pub fn parse_issue_keys(input: &str) -> (Vec<String>, Vec<String>) {
+    let mut keys: Vec<String> = Vec::new();
+    let mut invalid = Vec::new();
+    for entry in input
+        .split(|c: char| c == ',' || c == ';' || c.is_whitespace())
+        .filter(|e| !e.is_empty())
+    {
+        match parse_issue_key(entry) {
+            Some(key) if !keys.contains(&key) => keys.push(key),
+            Some(_) => {}
+            None => invalid.push(entry.to_string()),
+        }
+    }
+    (keys, invalid)
+}
+
 fn is_valid_issue_key(s: &str) -> bool {
     if let Some(dash_pos) = s.find('-') {
         let prefix = &s[..dash_pos];
         let suffix = &s[dash_pos + 1..];
-        !prefix.is_empty()
+        // Project keys start with a letter and may contain digits after it (ABC2, X1).
+        prefix.starts_with(|c: char| c.is_ascii_alphabetic())
+            && prefix.chars().all(|c| c.is_ascii_alphanumeric())
             && !suffix.is_empty()
-            && prefix.chars().all(|c| c.is_ascii_alphabetic())
             && suffix.chars().all(|c| c.is_ascii_digit())
     } else {
         false
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_and_xml_are_not_logs() {
+        let kind = |filename: &str, mime_type: &str| {
+            Attachment {
+                mime_type: mime_type.to_string(),
+                ..Attachment::for_test("1", filename, 1)
+            }
+            .kind()
+        };
+        assert_eq!(kind("dump.json", "application/json"), AttachmentKind::Other);
+        assert_eq!(kind("config.xml", "text/xml"), AttachmentKind::Other);
+        assert_eq!(kind("server.log", "text/plain"), AttachmentKind::Log);
+    }
+
+    #[test]
+    fn body_excerpt_stops_at_a_character_boundary() {
+        assert_eq!(body_excerpt("short", 200), "short");
+        assert_eq!(body_excerpt("abcdef", 3), "abc");
+        // 'é' is two bytes; cutting after one of them would panic.
+        assert_eq!(body_excerpt("aé", 2), "a");
+    }
+
+    #[test]
+    fn issue_keys_may_have_digits_after_the_first_letter() {
+        for key in ["ABC-1", "ABC2-45", "A1B2-3", "X1-9", "abc-12"] {
+            assert!(is_valid_issue_key(key), "{key}");
+        }
+    }
+
+    #[test]
+    fn issue_keys_need_a_leading_letter_and_a_number() {
+        for s in ["123-45", "1AB-2", "ABC-", "-12", "ABC", "ABC-12a", "A_B-1", "AB C-1", ""] {
+            assert!(!is_valid_issue_key(s), "{s}");
+        }
+    }
+
+    #[test]
+    fn issue_key_from_browse_url() {
+        let cases = [
+            ("https://acme.atlassian.net/browse/ABC-123", "ABC-123"),
+            ("https://acme.atlassian.net/browse/abc-123?focusedCommentId=10", "ABC-123"),
+            ("https://jira.corp.com/jira/browse/OPS2-7/", "OPS2-7"),
+        ];
+        for (url, key) in cases {
+            assert_eq!(parse_issue_key(url).as_deref(), Some(key), "{url}");
+        }
+    }
+
+    #[test]
+    fn issue_key_from_board_url() {
+        let cases = [
+            (
+                "https://acme.atlassian.net/jira/software/projects/ABC/boards/1?selectedIssue=ABC-42",
+                "ABC-42",
+            ),
+            ("https://jira.corp.com/projects/ABC/issues/ABC-7?filter=allopenissues", "ABC-7"),
+            ("https://jira.corp.com/issues/?jql=project%3DABC&issueKey=ABC-9", "ABC-9"),
+        ];
+        for (url, key) in cases {
+            assert_eq!(parse_issue_key(url).as_deref(), Some(key), "{url}");
+        }
+    }
+
+    #[test]
+    fn issue_key_from_plain_text() {
+        assert_eq!(parse_issue_key("ABC-1").as_deref(), Some("ABC-1"));
+        assert_eq!(parse_issue_key("  abc2-45 ").as_deref(), Some("ABC2-45"));
+        assert_eq!(parse_issue_key("not a key"), None);
+        // The host name looks like a key but isn't one.
+        assert_eq!(parse_issue_key("https://jira-1.corp.com/secure/Dashboard.jspa"), None);
+    }
+
+    #[test]
+    fn jira_url_for_cloud_drops_any_path() {
+        assert_eq!(check_jira_url("acme.atlassian.net").unwrap(), "https://acme.atlassian.net");
+        assert_eq!(
+            check_jira_url("https://acme.atlassian.net/jira/software/projects/ABC/boards/1").unwrap(),
+            "https://acme.atlassian.net"
+        );
+        assert_eq!(base_url("https://acme.atlassian.net/"), "https://acme.atlassian.net");
+    }
+
+    #[test]
+    fn jira_url_keeps_the_context_path() {
+        assert_eq!(check_jira_url("https://jira.corp.com/jira/").unwrap(), "https://jira.corp.com/jira");
+        assert_eq!(
+            check_jira_url("http://jira.corp.com:8080/jira/browse/ABC-1").unwrap(),
+            "http://jira.corp.com:8080/jira"
+        );
+    }
+
+    #[test]
+    fn jira_url_drops_a_pasted_api_path() {
+        assert_eq!(
+            check_jira_url("https://jira.corp.com/jira/rest/api/3").unwrap(),
+            "https://jira.corp.com/jira"
+        );
+        assert_eq!(
+            base_url("https://jira.corp.com/rest/api/3/issue/ABC-1"),
+            "https://jira.corp.com"
+        );
+    }
+
+    #[test]
+    fn jira_url_rejects_what_it_cannot_use() {
+        assert!(check_jira_url("  ").is_err());
+        assert!(check_jira_url("ftp://jira.corp.com").is_err());
+        // Used as typed when it doesn't check out.
+        assert_eq!(base_url("ftp://jira.corp.com/"), "ftp://jira.corp.com");
+    }
+
+    #[test]
+    fn send_failures_are_classified_by_message() {
+        let cases = [
+            (true, false, "operation timed out", SendFailure::Timeout),
+            (false, true, "invalid peer certificate: unknownissuer", SendFailure::Certificate),
+            (false, true, "certificate verify failed", SendFailure::Certificate),
+            (false, true, "dns error: failed to lookup address information", SendFailure::Dns),
+            (false, true, "failed to lookup address information", SendFailure::Dns),
+            (false, true, "no such host is known. (os error 11001)", SendFailure::Dns),
+            (false, true, "name or service not known", SendFailure::Dns),
+            (false, true, "nodename nor servname provided, or not known", SendFailure::Dns),
+            (false, true, "temporary failure in name resolution", SendFailure::Dns),
+            (false, true, "network is unreachable (os error 101)", SendFailure::Offline),
+            (false, true, "no route to host (os error 113)", SendFailure::Offline),
+            (false, true, "a socket operation was attempted to an unreachable network", SendFailure::Offline),
+            (false, true, "connect: network unreachable", SendFailure::Offline),
+            (false, true, "connection refused (os error 111)", SendFailure::Refused),
+            (false, true, "the target machine actively refused it", SendFailure::Refused),
+            (false, true, "tcp connect error", SendFailure::Connect),
+            (false, false, "connection closed before message completed", SendFailure::Other),
+        ];
+        for (timeout, connect, chain, expected) in cases {
+            assert_eq!(SendFailure::classify(timeout, connect, chain), expected, "{chain}");
+        }
+    }
+
+    #[test]
+    fn timeout_wins_over_the_message() {
+        let chain = "connection refused\ncertificate";
+        assert_eq!(SendFailure::classify(true, true, chain), SendFailure::Timeout);
+    }
+
+    /// A stand-in JIRA on a local port: each request is answered from
+    /// `routes` by path prefix, or with 404. Returns the base URL.
+    fn mock_jira(routes: &'static [(&'static str, u16, &'static str)]) -> String {
+        use std::io::{BufRead, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else {
+                    continue;
+                };
+                let mut reader = std::io::BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                let _ = reader.read_line(&mut request_line);
+                let mut header = String::new();
+                while reader.read_line(&mut header).is_ok_and(|n| n > 2) {
+                    header.clear();
+                }
+                let path = request_line.split_whitespace().nth(1).unwrap_or("");
+                let (status, body) = routes
+                    .iter()
+                    .find(|(prefix, ..)| path.starts_with(prefix))
+                    .map_or((404, "{}"), |(_, status, body)| (*status, *body));
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 {status} Mock\r\nContent-Type: application/json\r\n\
+                     Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+            }
+        });
+        base
+    }
+
+    fn my_issues(
+        routes: &'static [(&'static str, u16, &'static str)],
+    ) -> Result<Vec<IssueSummary>, String> {
+        let mut config = AppConfig::default();
+        config.jira_url = mock_jira(routes);
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(JiraClient::new(config).fetch_my_issues())
+    }
+
+    #[test]
+    fn my_issues_reports_a_rejected_query_without_falling_back() {
+        let err = my_issues(&[
+            ("/rest/api/3/search/jql", 400, r#"{"errorMessages":["Field 'foo' does not exist."]}"#),
+            ("/rest/api/2/search", 200, r#"{"issues":[]}"#),
+        ])
+        .unwrap_err();
+        assert_eq!(err, "JIRA rejected the My Cases query: Field 'foo' does not exist.");
+    }
+
+    #[test]
+    fn my_issues_falls_back_to_the_old_search_on_404() {
+        let issues = my_issues(&[
+            ("/rest/api/3/search/jql", 404, "{}"),
+            (
+                "/rest/api/2/search",
+                200,
+                r#"{"issues":[{"key":"ABC-1","fields":{"summary":"Crash","status":{"name":"Open"}}}]}"#,
+            ),
+        ])
+        .unwrap();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].key, "ABC-1");
+        assert_eq!(issues[0].status, "Open");
+    }
+
+    #[test]
+    fn detected_version_still_falls_back_when_its_body_does_not_parse() {
+        let mut config = AppConfig::default();
+        config.jira_url = mock_jira(&[
+            ("/rest/api/3/issue/ABC-1", 200, r#"{"key":"ABC-1","fields":"unexpected"}"#),
+            (
+                "/rest/api/2/issue/ABC-1",
+                200,
+                r#"{"key":"ABC-1","fields":{"summary":"Crash","status":{"name":"Open"}}}"#,
+            ),
+        ]);
+        remember_api_version(&config, "3");
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let issue = runtime.block_on(JiraClient::new(config).fetch_issue("ABC-1")).unwrap();
+        assert_eq!(issue.api_version, "2");
+        assert_eq!(issue.summary, "Crash");
+    }
+
+    #[test]
+    fn my_issues_explains_a_retired_search_api() {
+        let err = my_issues(&[
+            ("/rest/api/3/search/jql", 410, "{}"),
+            (
+                "/rest/api/2/search",
+                410,
+                r#"{"errorMessages":["The requested API has been removed. Please migrate."]}"#,
+            ),
+        ])
+        .unwrap_err();
+        assert!(
+            err.starts_with("JIRA has retired the old search API: The requested API has been removed."),
+            "{err}"
+        );
+        assert!(err.contains("(it answered HTTP 410 Gone)"), "{err}");
+    }
+}
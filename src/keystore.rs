@@ -0,0 +1,102 @@
+use std::path::PathBuf;
+
+/// Where the AES key protecting stored secrets lives: the registry on
+/// Windows, the Keychain on macOS and the Secret Service on Linux, or a
+/// plain key file in portable mode.
+pub trait KeyStore {
+    /// The stored value, or `None` on first run.
+    fn load(&self) -> Result<Option<String>, String>;
+    fn store(&self, value: &str) -> Result<(), String>;
+}
+
+pub fn platform() -> Box<dyn KeyStore> {
+    #[cfg(windows)]
+    {
+        Box::new(RegistryStore)
+    }
+    #[cfg(not(windows))]
+    {
+        Box::new(KeyringStore)
+    }
+}
+
+/// Portable mode: the key sits in a file next to `config.json`.
+pub fn file(path: PathBuf) -> Box<dyn KeyStore> {
+    Box::new(FileStore { path })
+}
+
+struct FileStore {
+    path: PathBuf,
+}
+
+impl KeyStore for FileStore {
+    fn load(&self) -> Result<Option<String>, String> {
+        match std::fs::read_to_string(&self.path) {
+            Ok(value) => Ok(Some(value.trim().to_string())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(format!("Key file read error: {e}")),
+        }
+    }
+
+    fn store(&self, value: &str) -> Result<(), String> {
+        std::fs::write(&self.path, value).map_err(|e| format!("Key file write error: {e}"))
+    }
+}
+
+#[cfg(windows)]
+struct RegistryStore;
+
+#[cfg(windows)]
+impl RegistryStore {
+    const KEY_PATH: &'static str = "Software\\jira-downloader";
+    const VALUE: &'static str = "encryption_key";
+
+    fn subkey() -> Result<winreg::RegKey, String> {
+        use winreg::{enums::HKEY_CURRENT_USER, RegKey};
+        let (subkey, _) = RegKey::predef(HKEY_CURRENT_USER)
+            .create_subkey(Self::KEY_PATH)
+            .map_err(|e| format!("Registry open error: {e}"))?;
+        Ok(subkey)
+    }
+}
+
+#[cfg(windows)]
+impl KeyStore for RegistryStore {
+    fn load(&self) -> Result<Option<String>, String> {
+        Ok(Self::subkey()?.get_value::<String, _>(Self::VALUE).ok())
+    }
+
+    fn store(&self, value: &str) -> Result<(), String> {
+        Self::subkey()?
+            .set_value(Self::VALUE, &value)
+            .map_err(|e| format!("Registry write error: {e}"))
+    }
+}
+
+#[cfg(not(windows))]
+struct KeyringStore;
+
+#[cfg(not(windows))]
+impl KeyringStore {
+    fn entry() -> Result<keyring::Entry, String> {
+        keyring::Entry::new("jira-downloader", "encryption_key")
+            .map_err(|e| format!("Keyring error: {e}"))
+    }
+}
+
+#[cfg(not(windows))]
+impl KeyStore for KeyringStore {
+    fn load(&self) -> Result<Option<String>, String> {
+        match Self::entry()?.get_password() {
+            Ok(value) => Ok(Some(value)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(format!("Keyring read error: {e}")),
+        }
+    }
+
+    fn store(&self, value: &str) -> Result<(), String> {
+        Self::entry()?
+            .set_password(value)
+            .map_err(|e| format!("Keyring write error: {e}"))
+    }
+}
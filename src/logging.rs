@@ -0,0 +1,104 @@
+//! Application log (`app.log` in the config directory) behind the `log` facade.
+//!
+//! The file is rotated once it passes `MAX_LOG_SIZE`, keeping `KEEP_ROTATED`
+//! older copies as `app.log.1`, `app.log.2`, …. Callers log URLs, statuses and
+//! errors only — never credentials or request headers.
+
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::config::config_dir;
+
+const MAX_LOG_SIZE: u64 = 5 * 1024 * 1024;
+const KEEP_ROTATED: usize = 3;
+
+pub fn log_path() -> PathBuf {
+    config_dir().join("app.log")
+}
+
+struct FileLogger {
+    /// Open log file and its current length; `None` if it couldn't be opened.
+    file: Mutex<Option<(File, u64)>>,
+}
+
+static LOGGER: FileLogger = FileLogger {
+    file: Mutex::new(None),
+};
+
+/// Installs the file logger. `debug` adds per-request detail.
+pub fn init(debug: bool) {
+    let _ = std::fs::create_dir_all(config_dir());
+    *LOGGER.file.lock().unwrap() = open();
+    if log::set_logger(&LOGGER).is_ok() {
+        set_debug(debug);
+    }
+}
+
+/// Applies the Settings "Debug Logging" toggle without restarting.
+pub fn set_debug(debug: bool) {
+    log::set_max_level(if debug {
+        log::LevelFilter::Debug
+    } else {
+        log::LevelFilter::Info
+    });
+}
+
+fn open() -> Option<(File, u64)> {
+    let file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log_path())
+        .ok()?;
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+    Some((file, len))
+}
+
+/// Shifts `app.log.N` up by one, dropping the oldest, and starts a fresh file.
+fn rotate() -> Option<(File, u64)> {
+    let path = log_path();
+    let rotated = |n: usize| PathBuf::from(format!("{}.{n}", path.display()));
+    let _ = std::fs::remove_file(rotated(KEEP_ROTATED));
+    for n in (1..KEEP_ROTATED).rev() {
+        let _ = std::fs::rename(rotated(n), rotated(n + 1));
+    }
+    let _ = std::fs::rename(&path, rotated(1));
+    open()
+}
+
+impl log::Log for FileLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let line = format!(
+            "{} {:<5} [{}] {}\n",
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
+            record.level(),
+            record.target(),
+            record.args()
+        );
+
+        let mut slot = self.file.lock().unwrap();
+        if matches!(&*slot, Some((_, len)) if *len >= MAX_LOG_SIZE) {
+            *slot = None;
+            *slot = rotate();
+        }
+        if let Some((file, len)) = slot.as_mut() {
+            if file.write_all(line.as_bytes()).is_ok() {
+                *len += line.len() as u64;
+            }
+        }
+    }
+
+    fn flush(&self) {
+        if let Some((file, _)) = self.file.lock().unwrap().as_mut() {
+            let _ = file.flush();
+        }
+    }
+}
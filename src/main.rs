@@ -1,8 +1,25 @@
 mod app;
+mod checksum;
+mod cli;
 mod config;
 mod downloader;
+mod export;
+mod fonts;
+mod history;
 mod jira;
+mod keystore;
+mod logging;
+mod metrics;
+mod notify;
+mod reconcile;
+mod retention;
+mod sizing;
+mod status_poll;
 mod storage;
+mod taskbar;
+mod thumbnails;
+mod updater;
+mod watch;
 
 use std::sync::Arc;
 
@@ -14,11 +31,37 @@ fn main() -> eframe::Result<()> {
             .expect("Failed to create tokio runtime"),
     );
 
+    let saved = config::AppConfig::load();
+    logging::init(saved.debug_logging);
+    log::info!("Starting version {}", env!("CARGO_PKG_VERSION"));
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match cli::CliArgs::parse(&args) {
+        Some(Ok(cli_args)) => std::process::exit(cli::run(&rt, cli_args)),
+        Some(Err(msg)) => {
+            eprintln!("{msg}");
+            std::process::exit(2);
+        }
+        None => {}
+    }
+
+    let mut viewport = egui::ViewportBuilder::default()
+        .with_title("JIRA Attachment Downloader")
+        .with_inner_size([900.0, 600.0])
+        .with_min_inner_size([700.0, 400.0]);
+    if let Some(window) = saved.window {
+        viewport = viewport
+            .with_inner_size([window.width.max(700.0), window.height.max(400.0)])
+            .with_maximized(window.maximized);
+        // A position on a since-disconnected monitor would open the window
+        // out of reach; let the OS place it instead.
+        if title_bar_visible(&window) {
+            viewport = viewport.with_position([window.x, window.y]);
+        }
+    }
+
     let options = eframe::NativeOptions {
-        viewport: egui::ViewportBuilder::default()
-            .with_title("JIRA Attachment Downloader")
-            .with_inner_size([900.0, 600.0])
-            .with_min_inner_size([700.0, 400.0]),
+        viewport,
         renderer: eframe::Renderer::Wgpu,
         ..Default::default()
     };
@@ -31,3 +74,26 @@ fn main() -> eframe::Result<()> {
         }),
     )
 }
+
+/// True when the top strip of the saved window, where the title bar is,
+/// overlaps a connected monitor.
+#[cfg(windows)]
+fn title_bar_visible(window: &config::WindowGeometry) -> bool {
+    use windows::Win32::Foundation::RECT;
+    use windows::Win32::Graphics::Gdi::{MonitorFromRect, MONITOR_DEFAULTTONULL};
+
+    let px = |v: f32| (v * window.scale) as i32;
+    let strip = RECT {
+        left: px(window.x),
+        top: px(window.y),
+        right: px(window.x + window.width),
+        bottom: px(window.y + 32.0),
+    };
+    unsafe { !MonitorFromRect(&strip, MONITOR_DEFAULTTONULL).is_invalid() }
+}
+
+/// Window managers elsewhere keep new windows on screen themselves.
+#[cfg(not(windows))]
+fn title_bar_visible(_window: &config::WindowGeometry) -> bool {
+    true
+}
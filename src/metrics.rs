@@ -0,0 +1,86 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::io::Write;
+use std::sync::Mutex;
+
+use crate::config::config_dir;
+
+/// How many recent downloads the on-screen average covers.
+const RECENT_WINDOW: usize = 10;
+/// Past this size the log moves to `download_metrics.jsonl.1`, replacing the
+/// previous one, and a new log is started.
+const ROTATE_BYTES: u64 = 1_048_576;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadMetric {
+    pub issue_key: String,
+    pub filename: String,
+    pub bytes: u64,
+    pub elapsed_ms: u64,
+    pub bytes_per_sec: u64,
+    pub retries: u32,
+    pub finished_at: DateTime<Utc>,
+}
+
+impl DownloadMetric {
+    pub fn new(
+        issue_key: &str,
+        filename: &str,
+        bytes: u64,
+        elapsed: std::time::Duration,
+        retries: u32,
+    ) -> Self {
+        let elapsed_ms = elapsed.as_millis() as u64;
+        let bytes_per_sec = (bytes * 1000).checked_div(elapsed_ms).unwrap_or(bytes);
+        Self {
+            issue_key: issue_key.to_string(),
+            filename: filename.to_string(),
+            bytes,
+            elapsed_ms,
+            bytes_per_sec,
+            retries,
+            finished_at: Utc::now(),
+        }
+    }
+}
+
+/// Keeps the last few metrics in memory and appends every one to
+/// `download_metrics.jsonl` in the config dir.
+#[derive(Default)]
+pub struct MetricsLog {
+    recent: Mutex<VecDeque<DownloadMetric>>,
+}
+
+impl MetricsLog {
+    pub fn record(&self, metric: DownloadMetric) {
+        // Also serialises the file writes of concurrent downloads.
+        let mut recent = self.recent.lock().unwrap();
+        if let Ok(line) = serde_json::to_string(&metric) {
+            let _ = std::fs::create_dir_all(config_dir());
+            let path = config_dir().join("download_metrics.jsonl");
+            if std::fs::metadata(&path).is_ok_and(|m| m.len() > ROTATE_BYTES) {
+                let _ = std::fs::rename(&path, path.with_extension("jsonl.1"));
+            }
+            if let Ok(mut file) = std::fs::OpenOptions::new().create(true).append(true).open(&path) {
+                let _ = writeln!(file, "{line}");
+            }
+        }
+
+        recent.push_back(metric);
+        while recent.len() > RECENT_WINDOW {
+            recent.pop_front();
+        }
+    }
+
+    /// Average throughput (bytes/s) over the recent window and how many files it covers.
+    pub fn recent_average(&self) -> Option<(u64, usize)> {
+        let recent = self.recent.lock().unwrap();
+        let bytes: u64 = recent.iter().map(|m| m.bytes).sum();
+        let ms: u64 = recent.iter().map(|m| m.elapsed_ms).sum();
+        if recent.is_empty() || ms == 0 {
+            return None;
+        }
+        Some((bytes * 1000 / ms, recent.len()))
+    }
+}
@@ -0,0 +1,11 @@
+//! Desktop notifications (toast on Windows, Notification Center on macOS,
+//! the freedesktop notification service on Linux). Failures are ignored — a
+//! missing notification daemon must never affect downloads.
+
+pub fn show(title: &str, body: &str) {
+    let _ = notify_rust::Notification::new()
+        .appname("JIRA Attachment Downloader")
+        .summary(title)
+        .body(body)
+        .show();
+}
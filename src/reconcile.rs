@@ -0,0 +1,148 @@
+use std::collections::{BTreeMap, HashSet};
+
+use crate::config::FolderLayout;
+use crate::jira::Attachment;
+use crate::storage::{layout_subfolder, stored_names, LocalFile};
+
+/// Differences between an issue's attachments in JIRA and its folder on disk.
+#[derive(Debug, Clone, Default)]
+pub struct ReconcileReport {
+    /// Listed in JIRA but not found locally.
+    pub missing: Vec<Attachment>,
+    /// On disk but no longer (or never) attached in JIRA.
+    pub orphans: Vec<LocalFile>,
+    /// Same name, different size — likely truncated or replaced.
+    pub size_mismatches: Vec<(Attachment, LocalFile)>,
+    pub matched: usize,
+}
+
+/// Matches attachments to local files. The path recorded for an attachment in
+/// `saved_files` (from the control file, relative to the issue folder) wins;
+/// otherwise by name, where a file in the attachment's own date or file-type
+/// folder wins, then one directly in the issue folder, then a same-named file
+/// in any other subfolder.
+/// `saved_name` gives the name the current filename template would save an
+/// attachment under; files saved under JIRA's own name match as well.
+pub fn reconcile(
+    attachments: &[Attachment],
+    local: &[LocalFile],
+    saved_files: &BTreeMap<String, String>,
+    saved_name: impl Fn(&Attachment) -> String,
+) -> ReconcileReport {
+    let mut report = ReconcileReport::default();
+    let mut claimed: HashSet<usize> = HashSet::new();
+
+    // Recorded paths first, so a name match can't take another attachment's file.
+    let recorded: Vec<Option<usize>> = attachments
+        .iter()
+        .map(|attachment| {
+            let rel = saved_files.get(&attachment.id)?;
+            let idx = local.iter().position(|f| f.relative_path() == *rel)?;
+            claimed.insert(idx).then_some(idx)
+        })
+        .collect();
+
+    for (attachment, recorded) in attachments.iter().zip(recorded) {
+        let names = stored_names(&saved_name(attachment), &attachment.filename);
+        // Prefer the copy in the folder some layout would have put it in, then
+        // a flat one: a folder first saved flat (a single upload date) keeps
+        // those files when later uploads go into date folders.
+        let expected: Vec<String> = [FolderLayout::Date, FolderLayout::ByType]
+            .iter()
+            .filter_map(|&layout| layout_subfolder(layout, attachment))
+            .collect();
+        let candidates: Vec<usize> = local
+            .iter()
+            .enumerate()
+            .filter(|(idx, f)| !claimed.contains(idx) && names.contains(&f.name))
+            .map(|(idx, _)| idx)
+            .collect();
+        let best = recorded.or_else(|| candidates.iter().copied().min_by_key(|&idx| match &local[idx].subfolder {
+            Some(sub) if expected.contains(sub) => 0,
+            None => 1,
+            Some(_) => 2,
+        }));
+
+        match best {
+            Some(idx) => {
+                claimed.insert(idx);
+                let file = &local[idx];
+                if attachment.size > 0 && file.size != attachment.size {
+                    report.size_mismatches.push((attachment.clone(), file.clone()));
+                } else {
+                    report.matched += 1;
+                }
+            }
+            None => report.missing.push(attachment.clone()),
+        }
+    }
+
+    report.orphans = local
+        .iter()
+        .enumerate()
+        .filter(|(idx, _)| !claimed.contains(idx))
+        .map(|(_, f)| f.clone())
+        .collect();
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn attachment(id: &str, filename: &str, size: u64, day: u32) -> Attachment {
+        Attachment {
+            created: Utc.with_ymd_and_hms(2024, 3, day, 12, 0, 0).unwrap(),
+            ..Attachment::for_test(id, filename, size)
+        }
+    }
+
+    fn local(name: &str, size: u64, subfolder: Option<&str>) -> LocalFile {
+        LocalFile {
+            path: subfolder.map_or_else(|| name.into(), |s| std::path::Path::new(s).join(name)),
+            name: name.to_string(),
+            size,
+            subfolder: subfolder.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn folder_mixing_flat_and_dated_files_matches_each_copy() {
+        // Saved flat while every upload was on the 1st; a later upload of the
+        // same name went into its date folder.
+        let attachments = [attachment("1", "log.txt", 5, 1), attachment("2", "log.txt", 7, 2)];
+        let files = [local("log.txt", 7, Some("2024-03-02")), local("log.txt", 5, None)];
+        let report = reconcile(&attachments, &files, &BTreeMap::new(), |a| a.filename.clone());
+        assert_eq!(report.matched, 2);
+        assert!(report.size_mismatches.is_empty());
+        assert!(report.missing.is_empty());
+        assert!(report.orphans.is_empty());
+    }
+
+    #[test]
+    fn own_date_folder_wins_over_the_flat_copy() {
+        let attachments = [attachment("1", "log.txt", 5, 2)];
+        let files = [local("log.txt", 9, None), local("log.txt", 5, Some("2024-03-02"))];
+        let report = reconcile(&attachments, &files, &BTreeMap::new(), |a| a.filename.clone());
+        assert_eq!(report.matched, 1);
+        assert_eq!(report.orphans.len(), 1);
+        assert_eq!(report.orphans[0].subfolder, None);
+    }
+
+    #[test]
+    fn recorded_path_wins_over_the_name_match() {
+        // Upload 1 was saved after upload 2 and got renamed to log_2.txt; by
+        // name alone it would take log.txt and upload 2 would look missing.
+        let attachments = [attachment("1", "log.txt", 5, 2), attachment("2", "log.txt", 7, 2)];
+        let files = [local("log.txt", 7, Some("2024-03-02")), local("log_2.txt", 5, Some("2024-03-02"))];
+        let saved = BTreeMap::from([
+            ("1".to_string(), "2024-03-02/log_2.txt".to_string()),
+            ("2".to_string(), "2024-03-02/log.txt".to_string()),
+        ]);
+        let report = reconcile(&attachments, &files, &saved, |a| a.filename.clone());
+        assert_eq!(report.matched, 2);
+        assert!(report.size_mismatches.is_empty());
+        assert!(report.orphans.is_empty());
+    }
+}
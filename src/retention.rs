@@ -0,0 +1,180 @@
+//! Opt-in automatic cleanup of closed incident folders.
+//!
+//! On startup and then every few hours, every scan root is walked and each
+//! incident whose last status check is older than the retention period is
+//! re-checked against JIRA. Only issues JIRA still reports as closed are
+//! archived or deleted; a failed check always leaves the folder alone, and so
+//! does a recycle bin that can't be used, since nobody is there to confirm a
+//! permanent delete. Every action is appended to `retention.log` in the config
+//! directory.
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::config::{config_dir, AppConfig, RetentionAction};
+use crate::jira::JiraClient;
+use crate::storage::{status_is_closed, StorageManager};
+use crate::watch::{run_periodic, ConnectionState};
+
+const PASS_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+pub fn log_path() -> PathBuf {
+    config_dir().join("retention.log")
+}
+
+/// The background cleanup loop; dropped or stopped when settings change.
+pub struct RetentionTask {
+    stop: Arc<AtomicBool>,
+    /// One-line summary of the most recent pass, for the Incidents Manager.
+    pub last_summary: Arc<Mutex<Option<String>>>,
+}
+
+impl RetentionTask {
+    /// `connection` is shared with the other background loops and shown in the UI.
+    pub fn start(
+        runtime: &tokio::runtime::Runtime,
+        config: AppConfig,
+        connection: Arc<Mutex<ConnectionState>>,
+        ctx: egui::Context,
+    ) -> Self {
+        let task = Self {
+            stop: Arc::new(AtomicBool::new(false)),
+            last_summary: Arc::new(Mutex::new(None)),
+        };
+        let stop = Arc::clone(&task.stop);
+        let summary = Arc::clone(&task.last_summary);
+
+        runtime.spawn(async move {
+            let pass = || {
+                let config = config.clone();
+                let summary = Arc::clone(&summary);
+                let stop = Arc::clone(&stop);
+                let ctx = ctx.clone();
+                async move {
+                    if stop.load(Ordering::Relaxed) {
+                        return;
+                    }
+                    let line = run_pass(&config).await;
+                    *summary.lock().unwrap() = Some(line);
+                    ctx.request_repaint();
+                }
+            };
+
+            pass().await;
+            run_periodic(PASS_INTERVAL, config.clone(), connection, Arc::clone(&stop), ctx.clone(), pass)
+                .await;
+        });
+
+        task
+    }
+
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Drop for RetentionTask {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// One cleanup pass over every scan root. Returns a summary line.
+async fn run_pass(config: &AppConfig) -> String {
+    if !config.has_credentials() {
+        return "Auto-cleanup skipped: no JIRA connection configured.".to_string();
+    }
+    let archive_dir = match (config.retention_action, &config.archive_dir) {
+        (RetentionAction::Archive, None) => {
+            let msg = "Auto-cleanup skipped: no archive directory set.".to_string();
+            record(&msg);
+            return msg;
+        }
+        (_, dir) => dir.clone(),
+    };
+
+    let mut roots = config.scan_dirs();
+    roots.retain(|root| Some(root) != config.archive_dir.as_ref());
+    let incidents = tokio::task::spawn_blocking(move || StorageManager::scan_roots(&roots))
+        .await
+        .unwrap_or_default();
+    let cutoff = chrono::Utc::now() - chrono::Duration::days(i64::from(config.retention_days));
+    let client = JiraClient::new(config.clone());
+
+    let mut cleaned = 0;
+    let mut failed = 0;
+    for incident in incidents {
+        let key = incident.control.issue_key.clone();
+        // Closed ones count from when they closed; the others from their
+        // last check, since background polling keeps that recent.
+        let ctrl = &incident.control;
+        if ctrl.closed_since.unwrap_or(ctrl.last_checked) > cutoff {
+            continue;
+        }
+
+        let status = match client.fetch_issue_status(&key).await {
+            Ok(status) => status,
+            Err(e) => {
+                record(&format!("{key}: status check failed, left in place: {e}"));
+                failed += 1;
+                continue;
+            }
+        };
+        let storage = StorageManager::new(incident.root.clone());
+        if !status_is_closed(&status, &config.closed_statuses) {
+            let _ = storage.update_control_file(&key, |ctrl| {
+                ctrl.record_status(&status, &config.closed_statuses);
+            });
+            continue;
+        }
+
+        let result = match (config.retention_action, &archive_dir) {
+            (RetentionAction::Archive, Some(dir)) => storage
+                .archive_folder(&key, dir)
+                .map(|to| format!("archived to {}", to.display())),
+            _ => storage
+                .delete_folder(&key)
+                .map(|()| "moved to the recycle bin".to_string())
+                .map_err(|e| format!("{e}; left in place")),
+        };
+        match result {
+            Ok(what) => {
+                record(&format!("{key} ({status}): {what}"));
+                cleaned += 1;
+            }
+            Err(e) => {
+                record(&format!("{key} ({status}): cleanup failed: {e}"));
+                failed += 1;
+            }
+        }
+    }
+
+    let verb = match config.retention_action {
+        RetentionAction::Archive => "archived",
+        RetentionAction::Delete => "deleted",
+    };
+    let mut line = format!(
+        "Auto-cleanup at {}: {cleaned} folder(s) {verb}",
+        chrono::Local::now().format("%Y-%m-%d %H:%M")
+    );
+    if failed > 0 {
+        line.push_str(&format!(", {failed} skipped after errors"));
+    }
+    line.push('.');
+    line
+}
+
+fn record(line: &str) {
+    log::info!("{line}");
+    let Ok(mut file) = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log_path())
+    else {
+        return;
+    };
+    let _ = writeln!(file, "{} {line}", chrono::Local::now().format("%Y-%m-%d %H:%M:%S"));
+}
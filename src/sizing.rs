@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::Semaphore;
+
+/// Folders walked at the same time.
+const PARALLEL_FOLDERS: usize = 4;
+/// Publish a running total after this many entries.
+const REPORT_EVERY: u64 = 500;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FolderSize {
+    pub bytes: u64,
+    pub files: u64,
+    /// False while the walk is still running or after it was stopped.
+    pub complete: bool,
+}
+
+/// Running totals per folder, read by the UI every frame.
+pub type SizeMap = Arc<Mutex<HashMap<PathBuf, FolderSize>>>;
+
+/// One sizing run over a set of incident folders.
+pub struct SizingJob {
+    pub sizes: SizeMap,
+    cancel: Arc<AtomicBool>,
+    remaining: Arc<Mutex<usize>>,
+}
+
+impl SizingJob {
+    /// Walks `folders` on the blocking pool, at most `PARALLEL_FOLDERS` at a time.
+    pub fn start(
+        runtime: &tokio::runtime::Runtime,
+        folders: Vec<PathBuf>,
+        ctx: egui::Context,
+    ) -> Self {
+        let job = Self {
+            sizes: Arc::new(Mutex::new(HashMap::new())),
+            cancel: Arc::new(AtomicBool::new(false)),
+            remaining: Arc::new(Mutex::new(folders.len())),
+        };
+
+        let semaphore = Arc::new(Semaphore::new(PARALLEL_FOLDERS));
+        for folder in folders {
+            let semaphore = Arc::clone(&semaphore);
+            let sizes = Arc::clone(&job.sizes);
+            let cancel = Arc::clone(&job.cancel);
+            let remaining = Arc::clone(&job.remaining);
+            let ctx = ctx.clone();
+
+            runtime.spawn(async move {
+                let Ok(_permit) = semaphore.acquire_owned().await else {
+                    return;
+                };
+                let _ = tokio::task::spawn_blocking(move || {
+                    if !cancel.load(Ordering::Relaxed) {
+                        size_folder(&folder, &sizes, &cancel, &ctx);
+                    }
+                    *remaining.lock().unwrap() -= 1;
+                    ctx.request_repaint();
+                })
+                .await;
+            });
+        }
+
+        job
+    }
+
+    /// Stops every walk; folders not yet finished keep their partial totals.
+    pub fn stop(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_running(&self) -> bool {
+        *self.remaining.lock().unwrap() > 0
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+    }
+}
+
+/// Same totals as `storage::dir_stats`, but published as it goes and abortable.
+fn size_folder(root: &Path, sizes: &SizeMap, cancel: &AtomicBool, ctx: &egui::Context) {
+    let mut total = FolderSize::default();
+    let mut pending = vec![root.to_path_buf()];
+    let mut seen = 0u64;
+
+    while let Some(dir) = pending.pop() {
+        let Ok(rd) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in rd.flatten() {
+            if cancel.load(Ordering::Relaxed) {
+                sizes.lock().unwrap().insert(root.to_path_buf(), total);
+                return;
+            }
+            let p = entry.path();
+            if p.is_file() {
+                total.bytes += p.metadata().map(|m| m.len()).unwrap_or(0);
+                if p.file_name().and_then(|n| n.to_str()) != Some(".jira_control.json") {
+                    total.files += 1;
+                }
+            } else if p.is_dir() {
+                pending.push(p);
+            }
+
+            seen += 1;
+            if seen.is_multiple_of(REPORT_EVERY) {
+                sizes.lock().unwrap().insert(root.to_path_buf(), total);
+                ctx.request_repaint();
+            }
+        }
+    }
+
+    total.complete = true;
+    sizes.lock().unwrap().insert(root.to_path_buf(), total);
+}
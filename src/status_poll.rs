@@ -0,0 +1,95 @@
+//! Opt-in periodic refresh of incident statuses for the Incidents Manager.
+//!
+//! Each round rescans the scan roots for incidents and checks their statuses
+//! one after another; the next round is only scheduled once this one has
+//! finished, so slow servers never see overlapping rounds. Each status is
+//! saved to the incident's control file, then queued like "Check All Status"
+//! results so a listed incident updates in place.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Local};
+
+use crate::config::AppConfig;
+use crate::jira::JiraClient;
+use crate::storage::{IncidentId, StorageManager};
+use crate::watch::{run_periodic, ConnectionState};
+
+/// Status results keyed by incident, drained by the Incidents Manager.
+pub type StatusUpdates = Arc<Mutex<Vec<(IncidentId, Result<String, String>)>>>;
+
+/// The polling loop; dropped or stopped when settings change.
+pub struct StatusPoller {
+    stop: Arc<AtomicBool>,
+    /// When the most recent round finished.
+    pub last_run: Arc<Mutex<Option<DateTime<Local>>>>,
+}
+
+impl StatusPoller {
+    pub fn start(
+        runtime: &tokio::runtime::Runtime,
+        config: AppConfig,
+        updates: StatusUpdates,
+        connection: Arc<Mutex<ConnectionState>>,
+        ctx: egui::Context,
+    ) -> Self {
+        let poller = Self {
+            stop: Arc::new(AtomicBool::new(false)),
+            last_run: Arc::new(Mutex::new(None)),
+        };
+        let stop = Arc::clone(&poller.stop);
+        let last_run = Arc::clone(&poller.last_run);
+        let interval = Duration::from_secs(u64::from(config.status_poll_minutes.max(1)) * 60);
+
+        runtime.spawn(async move {
+            let round = || {
+                let config = config.clone();
+                let updates = Arc::clone(&updates);
+                let last_run = Arc::clone(&last_run);
+                let stop = Arc::clone(&stop);
+                let ctx = ctx.clone();
+                async move {
+                    if !config.has_credentials() {
+                        return;
+                    }
+                    let client = JiraClient::new(config.clone());
+                    for incident in StorageManager::scan_roots(&config.scan_dirs()) {
+                        if stop.load(Ordering::Relaxed) {
+                            return;
+                        }
+                        let key = &incident.control.issue_key;
+                        let result = client.fetch_issue_status(key).await;
+                        if let Ok(status) = &result {
+                            // Saved here too: the Incidents Manager only applies
+                            // results for folders it has listed.
+                            let storage = StorageManager::new(incident.root.clone());
+                            let _ = storage.update_control_file(key, |ctrl| {
+                                ctrl.record_status(status, &config.closed_statuses);
+                            });
+                        }
+                        updates.lock().unwrap().push((incident.id(), result));
+                        ctx.request_repaint();
+                    }
+                    *last_run.lock().unwrap() = Some(Local::now());
+                    ctx.request_repaint();
+                }
+            };
+
+            run_periodic(interval, config.clone(), connection, Arc::clone(&stop), ctx.clone(), round).await;
+        });
+
+        poller
+    }
+
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Drop for StatusPoller {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
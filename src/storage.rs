@@ -1,109 +1,476 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::{Path, PathBuf};
 
-use crate::jira::Attachment;
+use crate::checksum::{Checksum, ChecksumAlgorithm};
+use crate::config::FolderLayout;
+use crate::jira::{parse_issue_key, Attachment, AttachmentKind};
+
+/// Bump on any new field: older versions treat files from a newer schema as
+/// read-only instead of saving them without the fields they don't know.
+pub const CONTROL_SCHEMA_VERSION: u32 = 4;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ControlFile {
+    /// Format version; files from before versioning read as 0.
+    #[serde(default)]
+    pub schema_version: u32,
     pub issue_key: String,
     pub issue_summary: String,
     pub issue_status: String,
     pub last_checked: DateTime<Utc>,
     pub marked_for_deletion: bool,
+    /// Remembered download checkbox per attachment id.
+    #[serde(default)]
+    pub selections: BTreeMap<String, bool>,
+    /// Local files flagged as no longer attached in JIRA (paths relative to the issue folder).
+    #[serde(default)]
+    pub flagged_orphans: Vec<String>,
+    /// Digest of each downloaded attachment, by attachment id.
+    #[serde(default)]
+    pub checksums: BTreeMap<String, Checksum>,
+    /// Layout files were last saved in; older folders don't record one.
+    #[serde(default)]
+    pub layout: Option<FolderLayout>,
+    /// User-defined labels such as "customer-impacting", kept sorted.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Where each downloaded attachment was saved, by attachment id (path
+    /// relative to the issue folder). Tells same-named attachments apart.
+    #[serde(default)]
+    pub saved_files: BTreeMap<String, String>,
+    /// When the issue was first seen closed, or None while it's open (or
+    /// hasn't been checked since this was added). Auto-cleanup counts from here.
+    #[serde(default)]
+    pub closed_since: Option<DateTime<Utc>>,
 }
 
 impl ControlFile {
     pub fn new(key: &str, summary: &str, status: &str) -> Self {
         Self {
+            schema_version: CONTROL_SCHEMA_VERSION,
             issue_key: key.to_string(),
             issue_summary: summary.to_string(),
             issue_status: status.to_string(),
             last_checked: Utc::now(),
             marked_for_deletion: false,
+            selections: BTreeMap::new(),
+            flagged_orphans: Vec::new(),
+            checksums: BTreeMap::new(),
+            layout: None,
+            tags: Vec::new(),
+            saved_files: BTreeMap::new(),
+            closed_since: None,
         }
     }
 
-    pub fn is_closed(&self) -> bool {
-        let s = self.issue_status.to_lowercase();
-        s == "done" || s == "closed" || s == "resolved" || s.contains("clos") || s.contains("resolv")
+    /// Brings a file from an older version up to the current schema.
+    /// Returns true if anything changed.
+    fn migrate(&mut self) -> bool {
+        if self.schema_version >= CONTROL_SCHEMA_VERSION {
+            return false;
+        }
+        // 0 → 1: versioning introduced.
+        // 1 → 2: `tags`.
+        // 2 → 3: `saved_files`.
+        // 3 → 4: `closed_since`.
+        // Every field added since the first release has a serde default, so
+        // there is nothing else to fill in.
+        self.schema_version = CONTROL_SCHEMA_VERSION;
+        true
+    }
+
+    /// Written by a newer version of the app. Saving it could drop fields this
+    /// version doesn't know about, so it is only ever read.
+    pub fn is_from_newer_version(&self) -> bool {
+        self.schema_version > CONTROL_SCHEMA_VERSION
+    }
+
+    /// `closed_statuses` is the user-configured list from Settings; when it is
+    /// empty the built-in name heuristic is used instead.
+    pub fn is_closed(&self, closed_statuses: &[String]) -> bool {
+        status_is_closed(&self.issue_status, closed_statuses)
+    }
+
+    /// Stores a freshly fetched status and marks the folder for deletion when
+    /// it's closed. `closed_since` keeps the first time it was seen closed, so
+    /// later checks don't restart the retention clock.
+    pub fn record_status(&mut self, status: &str, closed_statuses: &[String]) {
+        self.issue_status = status.to_string();
+        self.last_checked = Utc::now();
+        self.marked_for_deletion = self.is_closed(closed_statuses);
+        self.closed_since = if self.marked_for_deletion {
+            self.closed_since.or(Some(self.last_checked))
+        } else {
+            None
+        };
+    }
+}
+
+pub fn status_is_closed(status: &str, closed_statuses: &[String]) -> bool {
+    if !closed_statuses.is_empty() {
+        let status = status.trim();
+        return closed_statuses
+            .iter()
+            .any(|c| c.trim().eq_ignore_ascii_case(status));
     }
+    let s = status.to_lowercase();
+    s == "done" || s == "closed" || s == "resolved" || s.contains("clos") || s.contains("resolv")
 }
 
 #[derive(Debug, Clone)]
 pub struct IncidentFolder {
-    #[allow(dead_code)]
     pub path: PathBuf,
+    /// The scan root this folder was found under.
+    pub root: PathBuf,
     pub control: ControlFile,
     pub folder_size: u64,
+    pub file_count: u64,
+    /// False until the folder has been fully sized; the totals are partial meanwhile.
+    pub size_complete: bool,
 }
 
+/// Scan root and issue key; the same key can be stored under more than one root.
+pub type IncidentId = (PathBuf, String);
+
+impl IncidentFolder {
+    pub fn id(&self) -> IncidentId {
+        (self.root.clone(), self.control.issue_key.clone())
+    }
+}
+
+/// A key-named folder whose control file is missing or unreadable, so it
+/// doesn't show up as a managed incident.
+#[derive(Debug, Clone)]
+pub struct UnmanagedFolder {
+    pub root: PathBuf,
+    pub issue_key: String,
+    /// A control file exists but couldn't be parsed.
+    pub corrupt: bool,
+}
+
+/// A downloaded file inside an issue folder.
+#[derive(Debug, Clone)]
+pub struct LocalFile {
+    pub path: PathBuf,
+    pub name: String,
+    pub size: u64,
+    /// The date or file-type folder it sits in, or None for the flat layout.
+    pub subfolder: Option<String>,
+}
+
+impl LocalFile {
+    /// Path below the issue folder, as recorded in `saved_files`.
+    pub fn relative_path(&self) -> String {
+        match &self.subfolder {
+            Some(dir) => format!("{dir}/{}", self.name),
+            None => self.name.clone(),
+        }
+    }
+}
+
+/// Serialises control file writes across the app, so concurrent downloads
+/// and status checks don't overwrite each other's changes.
+static CONTROL_FILE_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+/// Targets of the downloads in progress. Held while a download picks and
+/// reserves its file name, so two same-named attachments downloading at once
+/// don't pick the same one.
+static RESERVED_TARGETS: std::sync::Mutex<BTreeSet<PathBuf>> =
+    std::sync::Mutex::new(BTreeSet::new());
+
 pub struct StorageManager {
     pub base_dir: PathBuf,
+    /// Naming template for saved attachments; empty keeps JIRA's file name.
+    pub filename_template: String,
 }
 
 impl StorageManager {
     pub fn new(base_dir: PathBuf) -> Self {
-        Self { base_dir }
+        Self {
+            base_dir,
+            filename_template: String::new(),
+        }
+    }
+
+    pub fn with_filename_template(mut self, template: &str) -> Self {
+        self.filename_template = template.to_string();
+        self
+    }
+
+    /// The name an attachment is saved under. Falls back to JIRA's file name
+    /// when the template doesn't render.
+    pub fn saved_filename(&self, issue_key: &str, attachment: &Attachment) -> String {
+        if self.filename_template.trim().is_empty() {
+            return attachment.filename.clone();
+        }
+        match render_filename_template(&self.filename_template, issue_key, attachment) {
+            Ok(name) => name,
+            Err(e) => {
+                log::warn!("Filename template ignored for {}: {e}", attachment.filename);
+                attachment.filename.clone()
+            }
+        }
+    }
+
+    /// Makes sure downloads can be saved under `base_dir`: creates it when
+    /// missing, then writes and removes a small probe file.
+    pub fn check_writable(&self) -> Result<(), String> {
+        let dir = &self.base_dir;
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("{} doesn't exist and can't be created: {e}", dir.display()))?;
+        let probe = dir.join(format!(".jira_write_test.{}.tmp", std::process::id()));
+        std::fs::write(&probe, b"ok").map_err(|e| format!("{} isn't writable: {e}", dir.display()))?;
+        let _ = std::fs::remove_file(&probe);
+        Ok(())
     }
 
     pub fn issue_dir(&self, issue_key: &str) -> PathBuf {
-        self.base_dir.join(issue_key)
+        extended_length(self.base_dir.join(issue_key))
     }
 
-    #[allow(dead_code)]
     pub fn control_file_path(&self, issue_key: &str) -> PathBuf {
         self.issue_dir(issue_key).join(".jira_control.json")
     }
 
     pub fn save_control_file(&self, ctrl: &ControlFile) -> Result<(), String> {
+        let _guard = CONTROL_FILE_LOCK.lock().unwrap();
+        self.write_control_file(ctrl)
+    }
+
+    fn write_control_file(&self, ctrl: &ControlFile) -> Result<(), String> {
         let dir = self.issue_dir(&ctrl.issue_key);
         std::fs::create_dir_all(&dir)
             .map_err(|e| format!("Failed to create issue dir: {e}"))?;
-        let path = dir.join(".jira_control.json");
-        let data = serde_json::to_string_pretty(ctrl)
-            .map_err(|e| format!("Serialize error: {e}"))?;
-        std::fs::write(&path, data)
-            .map_err(|e| format!("Write error: {e}"))?;
-        Ok(())
+        write_control_file_in(&dir, ctrl)
+    }
+
+    /// Drops a platform shortcut to the issue's web page into the issue folder:
+    /// `.url` on Windows, `.webloc` on macOS, `.desktop` elsewhere.
+    pub fn write_issue_shortcut(&self, issue_key: &str, url: &str) -> Result<(), String> {
+        let dir = self.issue_dir(issue_key);
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create issue dir: {e}"))?;
+
+        #[cfg(target_os = "windows")]
+        let (name, data) = (
+            format!("{issue_key}.url"),
+            format!("[InternetShortcut]\r\nURL={url}\r\n"),
+        );
+        #[cfg(target_os = "macos")]
+        let (name, data) = (
+            format!("{issue_key}.webloc"),
+            format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+                 <!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \
+                 \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n\
+                 <plist version=\"1.0\">\n<dict>\n\t<key>URL</key>\n\t<string>{}</string>\n</dict>\n</plist>\n",
+                url.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+            ),
+        );
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        let (name, data) = (
+            format!("{issue_key}.desktop"),
+            format!("[Desktop Entry]\nType=Link\nName={issue_key}\nURL={url}\nIcon=text-html\n"),
+        );
+
+        std::fs::write(dir.join(name), data).map_err(|e| format!("Write error: {e}"))
+    }
+
+    /// Load-modify-save of an existing control file, as one locked step.
+    pub fn update_control_file<T>(
+        &self,
+        issue_key: &str,
+        update: impl FnOnce(&mut ControlFile) -> T,
+    ) -> Result<T, String> {
+        let _guard = CONTROL_FILE_LOCK.lock().unwrap();
+
+        let mut ctrl = self
+            .load_control_file(issue_key)
+            .ok_or_else(|| format!("No control file for {issue_key}"))?;
+        let result = update(&mut ctrl);
+        self.write_control_file(&ctrl)?;
+        Ok(result)
+    }
+
+    /// Like [`Self::update_control_file`], but starts from `create()` when the
+    /// issue has no control file yet.
+    pub fn upsert_control_file<T>(
+        &self,
+        issue_key: &str,
+        create: impl FnOnce() -> ControlFile,
+        update: impl FnOnce(&mut ControlFile) -> T,
+    ) -> Result<T, String> {
+        let _guard = CONTROL_FILE_LOCK.lock().unwrap();
+
+        let mut ctrl = self.load_control_file(issue_key).unwrap_or_else(create);
+        let result = update(&mut ctrl);
+        self.write_control_file(&ctrl)?;
+        Ok(result)
     }
 
-    #[allow(dead_code)]
     pub fn load_control_file(&self, issue_key: &str) -> Option<ControlFile> {
-        let path = self.control_file_path(issue_key);
-        if !path.exists() {
+        if !self.control_file_path(issue_key).exists() {
             return None;
         }
-        let data = std::fs::read_to_string(&path).ok()?;
-        serde_json::from_str(&data).ok()
+        let dir = self.issue_dir(issue_key);
+        match read_control_file(&dir) {
+            Ok(ctrl) => Some(ctrl),
+            Err(e) => {
+                log::warn!("Unreadable control file in {}: {e}", normal_path(&dir).display());
+                None
+            }
+        }
     }
 
-    pub fn attachment_exists(&self, issue_key: &str, attachment: &Attachment) -> bool {
-        let date_str = attachment.created.format("%Y-%m-%d").to_string();
-        self.issue_dir(issue_key)
-            .join(&date_str)
-            .join(&attachment.filename)
-            .exists()
+    /// Where an attachment is saved under the given layout.
+    pub fn attachment_dir(&self, issue_key: &str, attachment: &Attachment, layout: FolderLayout) -> PathBuf {
+        match layout_subfolder(layout, attachment) {
+            Some(sub) => self.issue_dir(issue_key).join(sub),
+            None => self.issue_dir(issue_key),
+        }
     }
 
-    pub fn save_attachment(
+    /// Where an earlier download of the attachment sits. The path recorded for
+    /// its id in `saved_files` (from the issue's control file) wins; otherwise
+    /// every layout is searched under its templated name or the plain one
+    /// saved before a template was set, skipping files recorded for another
+    /// attachment or whose size doesn't match. Names are tried sanitized
+    /// first, then as JIRA has them, for files saved before sanitizing.
+    pub fn existing_attachment(
         &self,
         issue_key: &str,
         attachment: &Attachment,
-        data: &bytes::Bytes,
+        saved_files: &BTreeMap<String, String>,
+    ) -> Option<PathBuf> {
+        let issue_dir = self.issue_dir(issue_key);
+        if let Some(path) = saved_files.get(&attachment.id).map(|rel| issue_dir.join(rel)) {
+            if path.exists() {
+                return Some(path);
+            }
+        }
+        let claimed: Vec<PathBuf> = saved_files
+            .iter()
+            .filter(|(id, _)| **id != attachment.id)
+            .map(|(_, rel)| issue_dir.join(rel))
+            .collect();
+
+        let mut names: Vec<String> = Vec::new();
+        for name in stored_names(&self.saved_filename(issue_key, attachment), &attachment.filename) {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        FolderLayout::ALL
+            .iter()
+            .flat_map(|&layout| {
+                let dir = self.attachment_dir(issue_key, attachment, layout);
+                names.iter().map(move |name| dir.join(name))
+            })
+            .filter(|path| !claimed.contains(path))
+            .find(|path| match path.metadata() {
+                Ok(meta) => attachment.size == 0 || meta.len() == attachment.size,
+                Err(_) => false,
+            })
+    }
+
+    /// The file a fresh download at `path` was renamed away from, when it has
+    /// exactly the same content: the attachment's own name in the same
+    /// folder, which `resolve_conflict` found taken. A file recorded for
+    /// another attachment is never a match, even with identical bytes.
+    pub fn identical_earlier_copy(&self, issue_key: &str, attachment: &Attachment, path: &Path) -> Option<PathBuf> {
+        let original = path
+            .parent()?
+            .join(sanitize_filename(&self.saved_filename(issue_key, attachment)));
+        if original == path || !original.is_file() {
+            return None;
+        }
+        let issue_dir = self.issue_dir(issue_key);
+        let claimed = self.load_control_file(issue_key).is_some_and(|ctrl| {
+            ctrl.saved_files
+                .iter()
+                .any(|(id, rel)| *id != attachment.id && issue_dir.join(rel) == original)
+        });
+        let size = std::fs::metadata(path).ok()?.len();
+        if claimed || std::fs::metadata(&original).ok()?.len() != size {
+            return None;
+        }
+        let ours = Checksum::of_file(path, ChecksumAlgorithm::default()).ok()?;
+        let theirs = Checksum::of_file(&original, ChecksumAlgorithm::default()).ok()?;
+        (ours == theirs).then_some(original)
+    }
+
+    /// The path recorded for the attachment id by an earlier download, if any.
+    pub fn recorded_file(&self, issue_key: &str, attachment_id: &str) -> Option<PathBuf> {
+        let rel = self.load_control_file(issue_key)?.saved_files.remove(attachment_id)?;
+        Some(self.issue_dir(issue_key).join(rel))
+    }
+
+    /// Remembers where the attachment was saved, so it is found again even
+    /// when another attachment of the issue has the same name, and the layout
+    /// it was saved under.
+    pub fn record_saved_file(
+        &self,
+        issue_key: &str,
+        attachment: &Attachment,
+        path: &Path,
+        layout: FolderLayout,
+    ) -> Result<(), String> {
+        // Either side may carry the `\\?\` prefix for long paths, so compare
+        // the plain forms.
+        let issue_dir = normal_path(&self.issue_dir(issue_key));
+        let path = normal_path(path);
+        let Ok(rel) = path.strip_prefix(&issue_dir) else {
+            return Err(format!(
+                "{} is outside the issue folder {}, not recorded",
+                path.display(),
+                issue_dir.display()
+            ));
+        };
+        let rel = rel
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        self.update_control_file(issue_key, |ctrl| {
+            ctrl.saved_files.insert(attachment.id.clone(), rel);
+            ctrl.layout = Some(layout);
+        })
+    }
+
+    /// Creates the attachment's folder and reserves a free file name in it.
+    pub fn attachment_target(
+        &self,
+        issue_key: &str,
+        attachment: &Attachment,
+        layout: FolderLayout,
     ) -> Result<PathBuf, String> {
-        let date_str = attachment.created.format("%Y-%m-%d").to_string();
-        let date_dir = self.issue_dir(issue_key).join(&date_str);
-        std::fs::create_dir_all(&date_dir)
-            .map_err(|e| format!("Failed to create date dir: {e}"))?;
+        let dir = self.attachment_dir(issue_key, attachment, layout);
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create attachment dir: {e}"))?;
+        let name = checked_filename(&self.saved_filename(issue_key, attachment))?;
+        reserve_target(&dir, &name)
+    }
 
-        let target_path = resolve_conflict(&date_dir, &attachment.filename);
-        std::fs::write(&target_path, data.as_ref())
-            .map_err(|e| format!("Failed to write file: {e}"))?;
-        Ok(target_path)
+    /// Thumbnails go to `<issue>/thumbnails/` so they never mix with originals.
+    pub fn thumbnail_target(&self, issue_key: &str, attachment: &Attachment) -> Result<PathBuf, String> {
+        let dir = self.issue_dir(issue_key).join("thumbnails");
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create thumbnails dir: {e}"))?;
+        let name = checked_filename(&attachment.filename)?;
+        reserve_target(&dir, &name)
     }
 
-    /// Scan base_dir for folders that contain .jira_control.json
+    /// Every attachment file saved for an issue, across date folders and the
+    /// flat layout. Bookkeeping files and thumbnails are left out.
+    pub fn local_files(&self, issue_key: &str) -> Vec<LocalFile> {
+        let mut files = Vec::new();
+        collect_local_files(&self.issue_dir(issue_key), issue_key, None, &mut files);
+        files
+    }
+
+    /// Scan base_dir for folders that contain .jira_control.json. Sizes start out empty.
     pub fn scan_incidents(&self) -> Vec<IncidentFolder> {
         let mut result = Vec::new();
         let read_dir = match std::fs::read_dir(&self.base_dir) {
@@ -120,15 +487,20 @@ impl StorageManager {
             if !ctrl_path.exists() {
                 continue;
             }
-            if let Ok(data) = std::fs::read_to_string(&ctrl_path) {
-                if let Ok(ctrl) = serde_json::from_str::<ControlFile>(&data) {
-                    let size = dir_size(&path);
+            // Unreadable ones are listed by `scan_unmanaged` for repair.
+            match read_control_file(&path) {
+                Ok(ctrl) => {
+                    // Sizing is left to `sizing::SizingJob` — it can take minutes on big stores.
                     result.push(IncidentFolder {
                         path,
+                        root: self.base_dir.clone(),
                         control: ctrl,
-                        folder_size: size,
+                        folder_size: 0,
+                        file_count: 0,
+                        size_complete: false,
                     });
                 }
+                Err(e) => log::warn!("Skipping {}: unreadable control file: {e}", path.display()),
             }
         }
 
@@ -136,10 +508,79 @@ impl StorageManager {
         result
     }
 
+    /// Folders named like an issue key (e.g. `PROJ-123`) that lack a valid control file.
+    pub fn scan_unmanaged(&self) -> Vec<UnmanagedFolder> {
+        let mut result = Vec::new();
+        let Ok(read_dir) = std::fs::read_dir(&self.base_dir) else {
+            return result;
+        };
+
+        for entry in read_dir.flatten() {
+            let path = entry.path();
+            if !path.is_dir() {
+                continue;
+            }
+            let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            // Only exact key names, so the control file lands in this very folder.
+            if parse_issue_key(name).as_deref() != Some(name) {
+                continue;
+            }
+            let ctrl_path = path.join(".jira_control.json");
+            let corrupt = match std::fs::read_to_string(&ctrl_path) {
+                Ok(data) => {
+                    if serde_json::from_str::<ControlFile>(&data).is_ok() {
+                        continue;
+                    }
+                    true
+                }
+                Err(_) => false,
+            };
+            result.push(UnmanagedFolder {
+                root: self.base_dir.clone(),
+                issue_key: name.to_string(),
+                corrupt,
+            });
+        }
+
+        result.sort_by(|a, b| a.issue_key.cmp(&b.issue_key));
+        result
+    }
+
+    /// Reads one incident folder the same way `scan_incidents` does.
+    pub fn incident_folder(&self, issue_key: &str) -> Option<IncidentFolder> {
+        let path = self.issue_dir(issue_key);
+        let control = self.load_control_file(issue_key)?;
+        let (size, count) = dir_stats(&path);
+        Some(IncidentFolder {
+            path: normal_path(&path),
+            root: self.base_dir.clone(),
+            control,
+            folder_size: size,
+            file_count: count,
+            size_complete: true,
+        })
+    }
+
+    /// Scans every root in turn; each incident remembers which root it came from.
+    pub fn scan_roots(roots: &[PathBuf]) -> Vec<IncidentFolder> {
+        let mut result: Vec<IncidentFolder> = roots
+            .iter()
+            .flat_map(|root| StorageManager::new(root.clone()).scan_incidents())
+            .collect();
+        result.sort_by(|a, b| a.control.issue_key.cmp(&b.control.issue_key));
+        result
+    }
+
     /// Returns the latest date subfolder (YYYY-MM-DD) inside the issue dir,
-    /// or the issue dir itself if no date subfolders exist yet.
-    pub fn latest_date_folder(&self, issue_key: &str) -> PathBuf {
+    /// or the issue dir itself if no date subfolders exist (including the
+    /// collapsed single-date layout) or the layout isn't date-based.
+    pub fn latest_date_folder(&self, issue_key: &str, layout: FolderLayout) -> PathBuf {
         let issue_dir = self.issue_dir(issue_key);
+        if layout != FolderLayout::Date {
+            return issue_dir;
+        }
         let mut date_dirs: Vec<PathBuf> = Vec::new();
 
         if let Ok(rd) = std::fs::read_dir(&issue_dir) {
@@ -150,10 +591,7 @@ impl StorageManager {
                 }
                 // Accept YYYY-MM-DD named dirs only
                 if let Some(name) = p.file_name().and_then(|n| n.to_str()) {
-                    if name.len() == 10
-                        && name.as_bytes().get(4) == Some(&b'-')
-                        && name.as_bytes().get(7) == Some(&b'-')
-                    {
+                    if is_date_folder_name(name) {
                         date_dirs.push(p);
                     }
                 }
@@ -166,24 +604,134 @@ impl StorageManager {
 
     pub fn open_path(path: &Path) {
         #[cfg(target_os = "windows")]
-        let _ = std::process::Command::new("explorer").arg(path).spawn();
+        let _ = std::process::Command::new("explorer").arg(normal_path(path)).spawn();
         #[cfg(target_os = "macos")]
         let _ = std::process::Command::new("open").arg(path).spawn();
         #[cfg(target_os = "linux")]
         let _ = std::process::Command::new("xdg-open").arg(path).spawn();
     }
 
+    /// Sends the issue's folder to the recycle bin / trash. Fails, leaving the
+    /// folder in place, where that isn't available; the caller has to ask
+    /// before falling back to [`Self::delete_folder_permanently`].
     pub fn delete_folder(&self, issue_key: &str) -> Result<(), String> {
         let dir = self.issue_dir(issue_key);
-        if dir.exists() {
-            std::fs::remove_dir_all(&dir)
-                .map_err(|e| format!("Failed to delete folder: {e}"))?;
+        if !dir.exists() {
+            return Ok(());
         }
-        Ok(())
+        trash::delete(normal_path(&dir)).map_err(|e| format!("Recycle bin unavailable: {e}"))
     }
 
-    pub fn open_folder(&self, issue_key: &str) {
+    /// Removes the issue's folder for good, bypassing the recycle bin.
+    pub fn delete_folder_permanently(&self, issue_key: &str) -> Result<(), String> {
         let dir = self.issue_dir(issue_key);
+        if !dir.exists() {
+            return Ok(());
+        }
+        std::fs::remove_dir_all(&dir).map_err(|e| format!("Failed to delete folder: {e}"))
+    }
+
+    /// Moves the issue's folder into `archive_dir`, suffixing the name with a
+    /// timestamp if the archive already holds a folder for this key. Falls
+    /// back to copy-and-delete when the archive is on another volume.
+    /// Returns the archived folder's path.
+    pub fn archive_folder(&self, issue_key: &str, archive_dir: &Path) -> Result<PathBuf, String> {
+        if self.base_dir == archive_dir {
+            return Err(format!("{issue_key} is already in the archive"));
+        }
+        let dir = self.issue_dir(issue_key);
+        if !dir.exists() {
+            return Err(format!("No folder for {issue_key}"));
+        }
+        std::fs::create_dir_all(archive_dir)
+            .map_err(|e| format!("Failed to create archive dir: {e}"))?;
+
+        let mut target = extended_length(archive_dir.join(issue_key));
+        if target.exists() {
+            let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+            target = extended_length(archive_dir.join(format!("{issue_key}_{stamp}")));
+        }
+
+        match std::fs::rename(&dir, &target) {
+            Ok(()) => {}
+            Err(e) if is_cross_device(&e) => {
+                if let Err(e) = copy_dir(&dir, &target) {
+                    let _ = std::fs::remove_dir_all(&target);
+                    return Err(e);
+                }
+                std::fs::remove_dir_all(&dir)
+                    .map_err(|e| format!("Archived, but failed to remove the original: {e}"))?;
+            }
+            Err(e) => return Err(format!("Failed to move folder: {e}")),
+        }
+        Ok(normal_path(&target))
+    }
+
+    /// Moves an issue's folder to a new key after JIRA moved the issue to
+    /// another project. Merges into an existing new-key folder, renaming on
+    /// clashes. The old control file's records (selections, saved files,
+    /// checksums, tags, flagged orphans) carry over, with entries already
+    /// under the new key winning, and follow any file renamed by the merge.
+    pub fn relocate_issue(&self, old_key: &str, new_key: &str) -> Result<(), String> {
+        let old_dir = self.issue_dir(old_key);
+        let new_dir = self.issue_dir(new_key);
+        if !old_dir.exists() {
+            return Ok(());
+        }
+
+        let old_ctrl = self.load_control_file(old_key);
+        let mut renamed = BTreeMap::new();
+        if new_dir.exists() {
+            move_contents(&old_dir, &new_dir, old_key, "", &mut renamed)?;
+            std::fs::remove_dir_all(&old_dir)
+                .map_err(|e| format!("Failed to remove {}: {e}", old_dir.display()))?;
+        } else {
+            std::fs::rename(&old_dir, &new_dir)
+                .map_err(|e| format!("Failed to move folder: {e}"))?;
+        }
+
+        let Some(old) = old_ctrl else {
+            return self
+                .upsert_control_file(new_key, || ControlFile::new(new_key, "", ""), |_| ())
+                .map(|_| ());
+        };
+        let moved = |rel: String| renamed.get(&rel).cloned().unwrap_or(rel);
+        self.upsert_control_file(
+            new_key,
+            || ControlFile {
+                saved_files: BTreeMap::new(),
+                flagged_orphans: Vec::new(),
+                ..old.clone()
+            },
+            |ctrl| {
+                ctrl.issue_key = new_key.to_string();
+                for (id, selected) in old.selections.clone() {
+                    ctrl.selections.entry(id).or_insert(selected);
+                }
+                for (id, rel) in old.saved_files.clone() {
+                    ctrl.saved_files.entry(id).or_insert_with(|| moved(rel));
+                }
+                for (id, checksum) in old.checksums.clone() {
+                    ctrl.checksums.entry(id).or_insert(checksum);
+                }
+                for tag in old.tags.clone() {
+                    if !ctrl.tags.contains(&tag) {
+                        ctrl.tags.push(tag);
+                    }
+                }
+                ctrl.tags.sort();
+                for rel in old.flagged_orphans.clone() {
+                    let rel = moved(rel);
+                    if !ctrl.flagged_orphans.contains(&rel) {
+                        ctrl.flagged_orphans.push(rel);
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn open_folder(&self, issue_key: &str) {
+        let dir = normal_path(&self.issue_dir(issue_key));
         if dir.exists() {
             #[cfg(target_os = "windows")]
             {
@@ -203,9 +751,443 @@ impl StorageManager {
     }
 }
 
+fn collect_local_files(dir: &Path, issue_key: &str, subfolder: Option<&str>, out: &mut Vec<LocalFile>) {
+    let Ok(rd) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in rd.flatten() {
+        let p = entry.path();
+        let Some(name) = p.file_name().and_then(|n| n.to_str()).map(str::to_string) else {
+            continue;
+        };
+        if p.is_dir() {
+            // Only descend one level, into date or file-type folders.
+            if subfolder.is_none() && (is_date_folder_name(&name) || TYPE_FOLDERS.contains(&name.as_str())) {
+                collect_local_files(&p, issue_key, Some(&name), out);
+            }
+        } else if !is_bookkeeping_file(&p, issue_key, subfolder.is_none()) {
+            let size = p.metadata().map(|m| m.len()).unwrap_or(0);
+            out.push(LocalFile {
+                path: p,
+                name,
+                size,
+                subfolder: subfolder.map(str::to_string),
+            });
+        }
+    }
+}
+
+/// Reads the control file in `dir`, upgrading and re-saving it when it comes
+/// from an older version. Files from a newer version are returned as they are.
+fn read_control_file(dir: &Path) -> Result<ControlFile, String> {
+    let mut ctrl = parse_control_file(dir)?;
+    if ctrl.migrate() {
+        save_migrated_control_file(dir, &ctrl.issue_key);
+    } else if ctrl.is_from_newer_version() {
+        log::warn!(
+            "{}: control file has schema {} from a newer version; treating it as read-only",
+            ctrl.issue_key,
+            ctrl.schema_version
+        );
+    }
+    Ok(ctrl)
+}
+
+fn parse_control_file(dir: &Path) -> Result<ControlFile, String> {
+    let data = std::fs::read_to_string(dir.join(".jira_control.json"))
+        .map_err(|e| format!("Read error: {e}"))?;
+    serde_json::from_str(&data).map_err(|e| format!("Parse error: {e}"))
+}
+
+/// Re-saves an upgraded control file, re-reading it under the lock so a write
+/// that landed since the first read isn't lost. Skipped while the lock is
+/// held: that is either another writer, or an update of this very file that
+/// saves the upgraded version itself. Either way the next read upgrades it.
+fn save_migrated_control_file(dir: &Path, issue_key: &str) {
+    let Ok(_guard) = CONTROL_FILE_LOCK.try_lock() else {
+        log::debug!("{issue_key}: control file busy, upgrade saved later");
+        return;
+    };
+    let result = parse_control_file(dir).and_then(|mut ctrl| {
+        ctrl.migrate();
+        write_control_file_in(dir, &ctrl)
+    });
+    match result {
+        Ok(()) => log::info!("{issue_key}: control file upgraded to schema {CONTROL_SCHEMA_VERSION}"),
+        Err(e) => log::warn!("{issue_key}: could not save upgraded control file: {e}"),
+    }
+}
+
+/// Writes a temp file next to the control file and renames it into place,
+/// so a crash mid-write never leaves a truncated control file behind.
+fn write_control_file_in(dir: &Path, ctrl: &ControlFile) -> Result<(), String> {
+    if ctrl.is_from_newer_version() {
+        return Err(format!(
+            "control file was written by a newer version of the app (schema {}); left unchanged",
+            ctrl.schema_version
+        ));
+    }
+    let path = dir.join(".jira_control.json");
+    let tmp = dir.join(format!(".jira_control.json.{}.tmp", std::process::id()));
+    let data = serde_json::to_string_pretty(ctrl)
+        .map_err(|e| format!("Serialize error: {e}"))?;
+    std::fs::write(&tmp, data)
+        .map_err(|e| format!("Write error: {e}"))?;
+    if let Err(e) = std::fs::rename(&tmp, &path) {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(format!("Write error: {e}"));
+    }
+    Ok(())
+}
+
+/// Subfolders of the by-type layout.
+const TYPE_FOLDERS: [&str; 3] = ["images", "logs", "other"];
+
+/// The folder below the issue folder an attachment goes into, if any.
+pub fn layout_subfolder(layout: FolderLayout, attachment: &Attachment) -> Option<String> {
+    match layout {
+        FolderLayout::Flat => None,
+        FolderLayout::Date => Some(attachment.created.format("%Y-%m-%d").to_string()),
+        FolderLayout::ByType => Some(
+            match attachment.kind() {
+                AttachmentKind::Image => TYPE_FOLDERS[0],
+                AttachmentKind::Log => TYPE_FOLDERS[1],
+                _ => TYPE_FOLDERS[2],
+            }
+            .to_string(),
+        ),
+    }
+}
+
+/// The configured layout, except that a date layout collapses to flat when
+/// `collapse_single_date` is set and every attachment shares one date.
+pub fn effective_layout<'a>(
+    layout: FolderLayout,
+    collapse_single_date: bool,
+    attachments: impl IntoIterator<Item = &'a Attachment>,
+) -> FolderLayout {
+    if layout == FolderLayout::Date && collapse_single_date && spans_single_date(attachments) {
+        FolderLayout::Flat
+    } else {
+        layout
+    }
+}
+
+fn is_date_folder_name(name: &str) -> bool {
+    name.len() == 10 && name.as_bytes().get(4) == Some(&b'-') && name.as_bytes().get(7) == Some(&b'-')
+}
+
+/// Attachment list exported from the Incident tab.
+pub const MANIFEST_FILE: &str = "jira_manifest.json";
+
+/// Files the app itself writes next to attachments: control files, the
+/// issue's shortcut and manifest when `path` is directly in the issue folder
+/// (`in_root`), and the `.part` file of a download in progress. Attachments
+/// that merely share an extension with those are not matched.
+pub fn is_bookkeeping_file(path: &Path, issue_key: &str, in_root: bool) -> bool {
+    let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+        return false;
+    };
+    name.starts_with(".jira_control.json")
+        || (in_root && (name == MANIFEST_FILE || is_own_issue_file(name, issue_key)))
+        || is_in_progress_part(path)
+}
+
+/// The files the app keeps in `issue_key`'s folder for itself: the control
+/// file (and its temp copies) and the issue shortcut. These are deleted
+/// rather than moved on relocation.
+fn is_own_issue_file(name: &str, issue_key: &str) -> bool {
+    let control = name == ".jira_control.json"
+        || (name.starts_with(".jira_control.json.") && name.ends_with(".tmp"));
+    let shortcut = ["url", "webloc", "desktop"]
+        .iter()
+        .any(|ext| name == format!("{issue_key}.{ext}"));
+    control || shortcut
+}
+
+/// Moves everything under `from` into `to`, leaving only `issue_key`'s own
+/// control file and shortcut behind so the destination's copies win.
+/// `prefix` is `from`'s path below the issue folder ("" at the top). Files
+/// renamed to avoid a clash are added to `renamed` as old → new relative path.
+fn move_contents(
+    from: &Path,
+    to: &Path,
+    issue_key: &str,
+    prefix: &str,
+    renamed: &mut BTreeMap<String, String>,
+) -> Result<(), String> {
+    let rd = std::fs::read_dir(from).map_err(|e| format!("Failed to read {}: {e}", from.display()))?;
+    for entry in rd.flatten() {
+        let p = entry.path();
+        let Some(name) = p.file_name().and_then(|n| n.to_str()).map(str::to_string) else {
+            continue;
+        };
+        let rel = format!("{prefix}{name}");
+        if p.is_dir() {
+            let target = to.join(&name);
+            if target.exists() {
+                move_contents(&p, &target, issue_key, &format!("{rel}/"), renamed)?;
+            } else {
+                std::fs::rename(&p, &target).map_err(|e| format!("Failed to move {name}: {e}"))?;
+            }
+        } else if !(prefix.is_empty() && is_own_issue_file(&name, issue_key)) {
+            let target = resolve_conflict(to, &name);
+            std::fs::rename(&p, &target).map_err(|e| format!("Failed to move {name}: {e}"))?;
+            if let Some(new_name) = target.file_name().and_then(|n| n.to_str()).filter(|n| *n != name) {
+                renamed.insert(rel, format!("{prefix}{new_name}"));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `rename` failed because source and target are on different volumes.
+fn is_cross_device(e: &std::io::Error) -> bool {
+    // ERROR_NOT_SAME_DEVICE on Windows, EXDEV elsewhere.
+    let code = if cfg!(windows) { 17 } else { 18 };
+    e.raw_os_error() == Some(code)
+}
+
+/// Recursive copy for moves across volumes, where `rename` fails. Any entry
+/// that can't be read fails the whole copy, so the original is never removed
+/// with files missing from the copy.
+fn copy_dir(from: &Path, to: &Path) -> Result<(), String> {
+    std::fs::create_dir_all(to).map_err(|e| format!("Failed to create {}: {e}", to.display()))?;
+    let rd = std::fs::read_dir(from).map_err(|e| format!("Failed to read {}: {e}", from.display()))?;
+    for entry in rd {
+        let entry = entry.map_err(|e| format!("Failed to read {}: {e}", from.display()))?;
+        let p = entry.path();
+        let target = to.join(entry.file_name());
+        if p.is_dir() {
+            copy_dir(&p, &target)?;
+        } else {
+            std::fs::copy(&p, &target)
+                .map_err(|e| format!("Failed to copy {}: {e}", p.display()))?;
+        }
+    }
+    Ok(())
+}
+
+/// True when every attachment was uploaded on the same calendar day.
+pub fn spans_single_date<'a>(attachments: impl IntoIterator<Item = &'a Attachment>) -> bool {
+    let mut dates = attachments
+        .into_iter()
+        .map(|a| a.created.date_naive());
+    match dates.next() {
+        Some(first) => dates.all(|d| d == first),
+        None => true,
+    }
+}
+
+/// Free bytes on the volume holding `path`. The folder itself may not exist
+/// yet, so the nearest existing ancestor is queried.
+pub fn free_space(path: &Path) -> Option<u64> {
+    let existing = path.ancestors().find(|p| p.exists())?;
+    fs2::available_space(existing).ok()
+}
+
+/// Names a file saved under `saved_name` or JIRA's `filename` may have on
+/// disk: both sanitized, then both unchanged where that is still a plain file
+/// name (older versions didn't sanitize). May contain duplicates.
+pub fn stored_names(saved_name: &str, filename: &str) -> Vec<String> {
+    let mut names = vec![sanitize_filename(saved_name), sanitize_filename(filename)];
+    for raw in [saved_name, filename] {
+        let mut parts = Path::new(raw).components();
+        if matches!(
+            (parts.next(), parts.next()),
+            (Some(std::path::Component::Normal(part)), None) if part.to_str() == Some(raw)
+        ) {
+            names.push(raw.to_string());
+        }
+    }
+    names
+}
+
+/// Longest single path component most filesystems accept.
+const MAX_NAME_LEN: usize = 255;
+
+/// Sanitized name, or a readable error if it can't be stored at any depth.
+fn checked_filename(name: &str) -> Result<String, String> {
+    let clean = sanitize_filename(name);
+    let len = clean.encode_utf16().count();
+    if len > MAX_NAME_LEN {
+        return Err(format!(
+            "File name is too long to save ({len} characters; the limit is {MAX_NAME_LEN})"
+        ));
+    }
+    Ok(clean)
+}
+
+/// Tokens a filename template may use.
+const FILENAME_TOKENS: [&str; 5] = ["key", "date", "name", "ext", "id"];
+
+/// Fills in `{key}`, `{date}` (upload date), `{name}` (without extension),
+/// `{ext}` (without the dot) and `{id}`. Unknown tokens and unbalanced
+/// braces are errors.
+pub fn render_filename_template(
+    template: &str,
+    issue_key: &str,
+    attachment: &Attachment,
+) -> Result<String, String> {
+    let path = Path::new(&attachment.filename);
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or(&attachment.filename);
+    let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("");
+    let rendered = expand_template(template, |token| match token {
+        "key" => Some(issue_key.to_string()),
+        "date" => Some(attachment.created.format("%Y-%m-%d").to_string()),
+        "name" => Some(stem.to_string()),
+        "ext" => Some(ext.to_string()),
+        "id" => Some(attachment.id.clone()),
+        _ => None,
+    })?;
+    // "{name}.{ext}" leaves a trailing dot for files without an extension.
+    let rendered = rendered.trim_end_matches('.');
+    if rendered.trim().is_empty() {
+        return Err("template renders an empty name".to_string());
+    }
+    Ok(rendered.to_string())
+}
+
+/// Checks a template without an attachment at hand, for Settings.
+pub fn validate_filename_template(template: &str) -> Result<(), String> {
+    expand_template(template, |token| {
+        FILENAME_TOKENS.contains(&token).then(String::new)
+    })
+    .map(|_| ())
+}
+
+fn expand_template(template: &str, lookup: impl Fn(&str) -> Option<String>) -> Result<String, String> {
+    let mut out = String::new();
+    let mut rest = template;
+    while let Some(open) = rest.find(['{', '}']) {
+        if rest[open..].starts_with('}') {
+            return Err("unmatched '}'".to_string());
+        }
+        out.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let close = after.find('}').ok_or("unclosed '{'")?;
+        let token = &after[..close];
+        out.push_str(&lookup(token).ok_or_else(|| format!("unknown token {{{token}}}"))?);
+        rest = &after[close + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Windows rejects paths over 260 characters unless they use the `\\?\`
+/// extended-length form, so long absolute paths are converted to it. Short
+/// paths are left alone so they still compare equal to scanned ones.
+pub fn extended_length(path: PathBuf) -> PathBuf {
+    #[cfg(windows)]
+    {
+        const LEGACY_LIMIT: usize = 240;
+        let text = path.to_string_lossy();
+        if text.len() >= LEGACY_LIMIT && path.is_absolute() && !text.starts_with(r"\\?\") {
+            // Extended paths are passed through verbatim, so separators must be backslashes.
+            let text = text.replace('/', "\\");
+            return match text.strip_prefix(r"\\") {
+                Some(unc) => PathBuf::from(format!(r"\\?\UNC\{unc}")),
+                None => PathBuf::from(format!(r"\\?\{text}")),
+            };
+        }
+    }
+    path
+}
+
+/// Strips the `\\?\` prefix again, for display and for comparing with scanned paths.
+pub fn normal_path(path: &Path) -> PathBuf {
+    let text = path.to_string_lossy();
+    if let Some(unc) = text.strip_prefix(r"\\?\UNC\") {
+        PathBuf::from(format!(r"\\{unc}"))
+    } else if let Some(rest) = text.strip_prefix(r"\\?\") {
+        PathBuf::from(rest)
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// Makes a JIRA filename safe to create on Windows (and therefore anywhere):
+/// illegal characters become `_`, trailing dots/spaces go, and reserved
+/// device names such as `CON` or `LPT1.txt` get a `_` prefix.
+pub fn sanitize_filename(name: &str) -> String {
+    const RESERVED: [&str; 22] = [
+        "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7",
+        "COM8", "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+    ];
+
+    let mut clean: String = name
+        .chars()
+        .map(|c| match c {
+            '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    clean.truncate(clean.trim_end_matches(['.', ' ']).len());
+    if clean.is_empty() {
+        return "_".to_string();
+    }
+
+    let stem = clean.split('.').next().unwrap_or("").trim_end();
+    if RESERVED.iter().any(|r| r.eq_ignore_ascii_case(stem)) {
+        clean.insert(0, '_');
+    }
+    clean
+}
+
+/// Stamps a saved file with its JIRA upload time: the modified time
+/// everywhere, and the created time too on Windows.
+pub fn set_upload_time(path: &Path, uploaded: DateTime<Utc>) -> Result<(), String> {
+    let time = std::time::SystemTime::from(uploaded);
+    let times = std::fs::FileTimes::new().set_modified(time);
+    #[cfg(windows)]
+    let times = {
+        use std::os::windows::fs::FileTimesExt;
+        times.set_created(time)
+    };
+    std::fs::OpenOptions::new()
+        .write(true)
+        .open(path)
+        .and_then(|file| file.set_times(times))
+        .map_err(|e| format!("Failed to set file time: {e}"))
+}
+
+/// Picks a free name in `dir` and creates its empty `.part` file, which the
+/// download then writes into; a name with a download in progress isn't free.
+fn reserve_target(dir: &Path, filename: &str) -> Result<PathBuf, String> {
+    let mut reserved = RESERVED_TARGETS.lock().unwrap();
+    let target = extended_length(resolve_conflict(dir, filename));
+    std::fs::File::create(part_path(&target))
+        .map_err(|e| format!("Failed to create file: {e}"))?;
+    reserved.insert(normal_path(&target));
+    Ok(target)
+}
+
+/// Ends the reservation made for `target` once its `.part` file is renamed
+/// into place or removed.
+pub fn release_target(target: &Path) {
+    RESERVED_TARGETS.lock().unwrap().remove(&normal_path(target));
+}
+
+/// `path` is `<target>.part` for a target still being downloaded.
+fn is_in_progress_part(path: &Path) -> bool {
+    let Some(target) = path.to_str().and_then(|p| p.strip_suffix(".part")) else {
+        return false;
+    };
+    RESERVED_TARGETS.lock().unwrap().contains(&normal_path(Path::new(target)))
+}
+
+/// The in-progress name a download is streamed into: `<target>.part`.
+pub fn part_path(target: &Path) -> PathBuf {
+    let mut name = target.as_os_str().to_owned();
+    name.push(".part");
+    PathBuf::from(name)
+}
+
 fn resolve_conflict(dir: &Path, filename: &str) -> PathBuf {
+    let taken = |path: &Path| path.exists() || part_path(path).exists();
     let path = dir.join(filename);
-    if !path.exists() {
+    if !taken(&path) {
         return path;
     }
 
@@ -223,24 +1205,150 @@ fn resolve_conflict(dir: &Path, filename: &str) -> PathBuf {
     let mut counter = 2u32;
     loop {
         let candidate = dir.join(format!("{stem}_{counter}{ext}"));
-        if !candidate.exists() {
+        if !taken(&candidate) {
             return candidate;
         }
         counter += 1;
     }
 }
 
-fn dir_size(path: &Path) -> u64 {
+/// Returns (total bytes, file count) for everything under `path`. The
+/// control file's bytes are included in the total, but it isn't counted
+/// as a file.
+fn dir_stats(path: &Path) -> (u64, u64) {
     let mut total = 0u64;
+    let mut count = 0u64;
     if let Ok(rd) = std::fs::read_dir(path) {
         for entry in rd.flatten() {
             let p = entry.path();
             if p.is_file() {
                 total += p.metadata().map(|m| m.len()).unwrap_or(0);
+                if p.file_name().and_then(|n| n.to_str()) != Some(".jira_control.json") {
+                    count += 1;
+                }
             } else if p.is_dir() {
-                total += dir_size(&p);
+                let (sub_total, sub_count) = dir_stats(&p);
+                total += sub_total;
+                count += sub_count;
             }
         }
     }
-    total
+    (total, count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_named_attachments_resolve_to_their_own_files() {
+        let base = std::env::temp_dir().join(format!("jira_storage_test_{}", std::process::id()));
+        let storage = StorageManager::new(base.clone());
+        storage.save_control_file(&ControlFile::new("ABC-1", "Crash", "Open")).unwrap();
+
+        // Saved the way the downloader does it: reserve a free name, write the
+        // file, then record where it went.
+        let first = Attachment::for_test("1", "log.txt", 5);
+        let second = Attachment::for_test("2", "log.txt", 6);
+        let save = |a: &Attachment, data: &[u8]| {
+            let target = storage.attachment_target("ABC-1", a, FolderLayout::Flat).unwrap();
+            std::fs::write(&target, data).unwrap();
+            std::fs::remove_file(part_path(&target)).unwrap();
+            release_target(&target);
+            storage.record_saved_file("ABC-1", a, &target, FolderLayout::Flat).unwrap();
+            target
+        };
+        let first_path = save(&first, b"first");
+        let second_path = save(&second, b"second");
+        assert_eq!(second_path.file_name().unwrap(), "log_2.txt");
+
+        let saved_files = storage.load_control_file("ABC-1").unwrap().saved_files;
+        let found = |a: &Attachment, saved: &BTreeMap<String, String>| {
+            storage.existing_attachment("ABC-1", a, saved)
+        };
+        assert_eq!(found(&first, &saved_files), Some(first_path.clone()));
+        assert_eq!(found(&second, &saved_files), Some(second_path.clone()));
+        assert_ne!(first_path, second_path);
+
+        // Not yet recorded: the name is taken by the other attachment, so
+        // the second one isn't on disk even when the sizes match.
+        let only_first = BTreeMap::from([("1".to_string(), "log.txt".to_string())]);
+        let same_size = Attachment::for_test("2", "log.txt", 5);
+        assert_eq!(found(&same_size, &only_first), None);
+        assert_eq!(found(&first, &only_first), Some(first_path));
+
+        let _ = std::fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn only_the_apps_own_files_count_as_bookkeeping() {
+        let base = std::env::temp_dir().join(format!("jira_bookkeeping_test_{}", std::process::id()));
+        let storage = StorageManager::new(base.clone());
+        let dir = storage.issue_dir("ABC-1");
+        let bookkeeping = |rel: &str| {
+            let path = dir.join(rel);
+            is_bookkeeping_file(&path, "ABC-1", path.parent() == Some(dir.as_path()))
+        };
+        assert!(bookkeeping(".jira_control.json"));
+        assert!(bookkeeping("ABC-1.url"));
+        assert!(bookkeeping(MANIFEST_FILE));
+        // Attachments that only look like the app's files.
+        assert!(!bookkeeping("intranet.url"));
+        assert!(!bookkeeping("2024-03-02/ABC-1.desktop"));
+        assert!(!bookkeeping("2024-03-02/jira_manifest.json"));
+        assert!(!bookkeeping("backup.part"));
+
+        let target = storage
+            .attachment_target("ABC-1", &Attachment::for_test("1", "backup", 1), FolderLayout::Flat)
+            .unwrap();
+        assert!(bookkeeping("backup.part"));
+        release_target(&target);
+        assert!(!bookkeeping("backup.part"));
+
+        let _ = std::fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn raw_names_count_only_when_they_are_plain_file_names() {
+        let names = stored_names("notes.", "notes.");
+        assert!(names.contains(&"notes".to_string()));
+        assert!(names.contains(&"notes.".to_string()));
+
+        let names = stored_names("../x.txt", "dir/x.txt");
+        assert!(names.iter().all(|n| !n.contains('/')), "{names:?}");
+    }
+
+    #[test]
+    fn closed_statuses_by_name_when_none_configured() {
+        for status in ["Done", "closed", "Resolved", "Closed - Won't Fix", "Auto-Resolved"] {
+            assert!(status_is_closed(status, &[]), "{status}");
+        }
+        for status in ["Open", "In Progress", "Waiting for customer", ""] {
+            assert!(!status_is_closed(status, &[]), "{status}");
+        }
+    }
+
+    #[test]
+    fn configured_closed_statuses_replace_the_name_check() {
+        let closed = vec!["Done".to_string(), " Cancelled ".to_string()];
+        assert!(status_is_closed("done", &closed));
+        assert!(status_is_closed("Cancelled ", &closed));
+        // Only the configured names count once any are set.
+        assert!(!status_is_closed("Closed", &closed));
+        assert!(!status_is_closed("Resolved", &closed));
+    }
+
+    #[test]
+    fn single_date_means_one_calendar_day() {
+        let on = |day: u32, hour: u32| Attachment {
+            created: chrono::TimeZone::with_ymd_and_hms(&Utc, 2024, 3, day, hour, 0, 0).unwrap(),
+            ..Attachment::for_test("1", "a.txt", 1)
+        };
+        let none: [Attachment; 0] = [];
+        assert!(spans_single_date(&none));
+        assert!(spans_single_date(&[on(1, 0)]));
+        assert!(spans_single_date(&[on(1, 0), on(1, 23)]));
+        assert!(!spans_single_date(&[on(1, 23), on(2, 0)]));
+        assert!(!spans_single_date(&[on(1, 9), on(1, 10), on(3, 9)]));
+    }
 }
@@ -0,0 +1,93 @@
+//! Taskbar button progress (the green/red fill) on Windows via `ITaskbarList3`.
+//! On other platforms, or when COM is unavailable, every call is a no-op.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TaskbarProgress {
+    Idle,
+    Normal(f32),
+    Error,
+}
+
+pub struct Taskbar {
+    last: TaskbarProgress,
+    #[cfg(windows)]
+    list: Option<windows::Win32::UI::Shell::ITaskbarList3>,
+    #[cfg(windows)]
+    init_failed: bool,
+}
+
+impl Taskbar {
+    pub fn new() -> Self {
+        Self {
+            last: TaskbarProgress::Idle,
+            #[cfg(windows)]
+            list: None,
+            #[cfg(windows)]
+            init_failed: false,
+        }
+    }
+
+    /// Applies `progress` to the window's taskbar button if it changed since the last call.
+    pub fn set(&mut self, frame: &eframe::Frame, progress: TaskbarProgress) {
+        if progress == self.last {
+            return;
+        }
+        self.last = progress;
+
+        #[cfg(windows)]
+        self.apply(frame, progress);
+        #[cfg(not(windows))]
+        let _ = frame;
+    }
+
+    #[cfg(windows)]
+    fn apply(&mut self, frame: &eframe::Frame, progress: TaskbarProgress) {
+        use raw_window_handle::{HasWindowHandle, RawWindowHandle};
+        use windows::Win32::Foundation::HWND;
+        use windows::Win32::UI::Shell::{TBPF_ERROR, TBPF_NOPROGRESS, TBPF_NORMAL};
+
+        let Some(list) = self.list() else {
+            return;
+        };
+        let hwnd = match frame.window_handle().map(|h| h.as_raw()) {
+            Ok(RawWindowHandle::Win32(h)) => HWND(h.hwnd.get() as *mut _),
+            _ => return,
+        };
+
+        // Failures are ignored — the taskbar is a nicety, not something to report.
+        unsafe {
+            let _ = match progress {
+                TaskbarProgress::Idle => list.SetProgressState(hwnd, TBPF_NOPROGRESS),
+                TaskbarProgress::Normal(frac) => list
+                    .SetProgressState(hwnd, TBPF_NORMAL)
+                    .and_then(|_| {
+                        list.SetProgressValue(hwnd, (frac.clamp(0.0, 1.0) * 1000.0) as u64, 1000)
+                    }),
+                TaskbarProgress::Error => list
+                    .SetProgressState(hwnd, TBPF_ERROR)
+                    .and_then(|_| list.SetProgressValue(hwnd, 1000, 1000)),
+            };
+        }
+    }
+
+    #[cfg(windows)]
+    fn list(&mut self) -> Option<&windows::Win32::UI::Shell::ITaskbarList3> {
+        use windows::Win32::System::Com::{
+            CoCreateInstance, CoInitializeEx, CLSCTX_INPROC_SERVER, COINIT_APARTMENTTHREADED,
+        };
+        use windows::Win32::UI::Shell::{ITaskbarList3, TaskbarList};
+
+        if self.list.is_none() && !self.init_failed {
+            let created = unsafe {
+                let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+                CoCreateInstance::<_, ITaskbarList3>(&TaskbarList, None, CLSCTX_INPROC_SERVER)
+                    .and_then(|list| list.HrInit().map(|_| list))
+            };
+            match created {
+                Ok(list) => self.list = Some(list),
+                Err(_) => self.init_failed = true,
+            }
+        }
+        self.list.as_ref()
+    }
+}
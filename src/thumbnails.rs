@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::Semaphore;
+
+use crate::config::AppConfig;
+use crate::jira::{Attachment, AttachmentKind, JiraClient};
+
+/// Edge length previews are scaled down to, in pixels.
+const PREVIEW_SIZE: u32 = 48;
+/// Images without a JIRA thumbnail are only previewed from the original up to this size.
+const MAX_ORIGINAL_BYTES: u64 = 2 * 1_048_576;
+/// Previews fetched at once; a long list of images queues behind these.
+const MAX_CONCURRENT_FETCHES: usize = 4;
+
+/// Decoded previews (or `None` for a failed one) waiting for the UI thread.
+type Decoded = Arc<Mutex<Vec<(String, Option<egui::ColorImage>)>>>;
+
+enum Slot {
+    Loading,
+    Ready(egui::TextureHandle),
+    Failed,
+}
+
+/// Preview textures for image attachments, keyed by attachment id and
+/// loaded in the background the first time a row asks for one.
+pub struct ThumbnailCache {
+    runtime: Arc<tokio::runtime::Runtime>,
+    slots: HashMap<String, Slot>,
+    incoming: Decoded,
+    fetch_slots: Arc<Semaphore>,
+}
+
+impl ThumbnailCache {
+    pub fn new(runtime: Arc<tokio::runtime::Runtime>) -> Self {
+        Self {
+            runtime,
+            slots: HashMap::new(),
+            incoming: Arc::new(Mutex::new(Vec::new())),
+            fetch_slots: Arc::new(Semaphore::new(MAX_CONCURRENT_FETCHES)),
+        }
+    }
+
+    /// Drops every preview, e.g. after switching to another server. Fetches
+    /// still in flight report into the old queue and are ignored.
+    pub fn clear(&mut self) {
+        self.slots.clear();
+        self.incoming = Arc::new(Mutex::new(Vec::new()));
+    }
+
+    /// The preview if it's ready; otherwise starts fetching it and returns `None`.
+    pub fn get(
+        &mut self,
+        ctx: &egui::Context,
+        attachment: &Attachment,
+        config: &AppConfig,
+    ) -> Option<&egui::TextureHandle> {
+        let finished: Vec<_> = self.incoming.lock().unwrap().drain(..).collect();
+        for (id, image) in finished {
+            let slot = match image {
+                Some(image) => Slot::Ready(ctx.load_texture(
+                    format!("thumb-{id}"),
+                    image,
+                    egui::TextureOptions::LINEAR,
+                )),
+                None => Slot::Failed,
+            };
+            self.slots.insert(id, slot);
+        }
+
+        if !self.slots.contains_key(&attachment.id) {
+            let url = match &attachment.thumbnail {
+                Some(thumb) => thumb.clone(),
+                None if attachment.kind() == AttachmentKind::Image
+                    && attachment.size <= MAX_ORIGINAL_BYTES =>
+                {
+                    attachment.content.clone()
+                }
+                None => return None,
+            };
+            self.slots.insert(attachment.id.clone(), Slot::Loading);
+            self.spawn_fetch(attachment.id.clone(), url, config.clone(), ctx.clone());
+        }
+
+        match self.slots.get(&attachment.id) {
+            Some(Slot::Ready(texture)) => Some(texture),
+            _ => None,
+        }
+    }
+
+    fn spawn_fetch(&self, id: String, url: String, config: AppConfig, ctx: egui::Context) {
+        let incoming = Arc::clone(&self.incoming);
+        let slots = Arc::clone(&self.fetch_slots);
+        self.runtime.spawn(async move {
+            let Ok(_permit) = slots.acquire_owned().await else {
+                return;
+            };
+            let client = JiraClient::new(config);
+            let image = client.fetch_bytes(&url).await.ok().and_then(|bytes| decode(&bytes));
+            incoming.lock().unwrap().push((id, image));
+            ctx.request_repaint();
+        });
+    }
+}
+
+fn decode(bytes: &[u8]) -> Option<egui::ColorImage> {
+    let image = image::load_from_memory(bytes).ok()?;
+    let rgba = image.thumbnail(PREVIEW_SIZE, PREVIEW_SIZE).to_rgba8();
+    let size = [rgba.width() as usize, rgba.height() as usize];
+    Some(egui::ColorImage::from_rgba_unmultiplied(size, rgba.as_raw()))
+}
@@ -0,0 +1,98 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::config::config_dir;
+
+const CHECK_INTERVAL_HOURS: i64 = 24;
+
+#[derive(Debug, Clone)]
+pub struct UpdateInfo {
+    pub version: String,
+    pub url: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct UpdateCache {
+    checked_at: DateTime<Utc>,
+    source: String,
+    latest_version: String,
+    release_url: String,
+}
+
+#[derive(Deserialize)]
+struct ReleaseResponse {
+    tag_name: String,
+    #[serde(default)]
+    html_url: String,
+}
+
+/// Returns a newer release if one exists. Only the release URL is requested —
+/// nothing about the user or their JIRA setup is sent. The answer is cached
+/// so the network is hit at most once a day.
+pub async fn check_for_update(source: &str) -> Result<Option<UpdateInfo>, String> {
+    let cache_path = config_dir().join("update_check.json");
+
+    let cached = std::fs::read_to_string(&cache_path)
+        .ok()
+        .and_then(|data| serde_json::from_str::<UpdateCache>(&data).ok())
+        .filter(|c| {
+            c.source == source
+                && Utc::now().signed_duration_since(c.checked_at).num_hours() < CHECK_INTERVAL_HOURS
+        });
+
+    let cache = match cached {
+        Some(c) => c,
+        None => {
+            let release = fetch_latest_release(source).await?;
+            let cache = UpdateCache {
+                checked_at: Utc::now(),
+                source: source.to_string(),
+                latest_version: release.tag_name,
+                release_url: release.html_url,
+            };
+            if let Ok(data) = serde_json::to_string_pretty(&cache) {
+                let _ = std::fs::create_dir_all(config_dir());
+                let _ = std::fs::write(&cache_path, data);
+            }
+            cache
+        }
+    };
+
+    if is_newer(&cache.latest_version, env!("CARGO_PKG_VERSION")) {
+        Ok(Some(UpdateInfo {
+            version: cache.latest_version,
+            url: cache.release_url,
+        }))
+    } else {
+        Ok(None)
+    }
+}
+
+async fn fetch_latest_release(source: &str) -> Result<ReleaseResponse, String> {
+    let resp = reqwest::Client::new()
+        .get(source)
+        .header(reqwest::header::USER_AGENT, crate::config::DEFAULT_USER_AGENT)
+        .header(reqwest::header::ACCEPT, "application/json")
+        .send()
+        .await
+        .map_err(|e| format!("Update check failed: {e}"))?;
+
+    if !resp.status().is_success() {
+        return Err(format!("Update check failed: HTTP {}", resp.status()));
+    }
+    resp.json::<ReleaseResponse>()
+        .await
+        .map_err(|e| format!("Update check parse error: {e}"))
+}
+
+/// Compares dotted version strings numerically, ignoring a leading "v".
+fn is_newer(candidate: &str, current: &str) -> bool {
+    fn parts(v: &str) -> Vec<u64> {
+        v.trim()
+            .trim_start_matches(['v', 'V'])
+            .split(['.', '-', '+'])
+            .map_while(|p| p.parse().ok())
+            .collect()
+    }
+    parts(candidate) > parts(current)
+}
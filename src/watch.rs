@@ -0,0 +1,92 @@
+//! Long-lived periodic background loops that survive laptop sleep.
+//!
+//! `tokio::time::sleep` runs on a monotonic clock that may not advance while the
+//! machine is suspended, so a loop can wake up with a dead network and spin on
+//! errors. `run_periodic` compares wall-clock time between ticks; a jump much
+//! larger than the interval means we slept, and the loop re-probes the server
+//! (with backoff) before resuming normal work.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use crate::config::AppConfig;
+use crate::jira::JiraClient;
+
+/// Extra wall-clock time beyond the interval that still counts as "awake".
+const WAKE_SLACK: Duration = Duration::from_secs(60);
+const RECONNECT_MAX_BACKOFF: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConnectionState {
+    Online,
+    Reconnecting,
+    Offline(String),
+}
+
+/// Calls `tick` every `interval` until `stop` is set. After a detected sleep the
+/// connection is re-established first, with `state` reflecting progress.
+pub async fn run_periodic<F, Fut>(
+    interval: Duration,
+    config: AppConfig,
+    state: Arc<Mutex<ConnectionState>>,
+    stop: Arc<AtomicBool>,
+    ctx: egui::Context,
+    mut tick: F,
+) where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = ()>,
+{
+    let mut last = SystemTime::now();
+    loop {
+        tokio::time::sleep(interval).await;
+        if stop.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let now = SystemTime::now();
+        let gap = now.duration_since(last).unwrap_or_default();
+        if gap > interval + WAKE_SLACK {
+            reconnect(&config, &state, &stop, &ctx).await;
+            if stop.load(Ordering::Relaxed) {
+                return;
+            }
+        }
+
+        tick().await;
+        last = SystemTime::now();
+    }
+}
+
+/// Probes the server until it answers, backing off between attempts.
+pub async fn reconnect(
+    config: &AppConfig,
+    state: &Arc<Mutex<ConnectionState>>,
+    stop: &Arc<AtomicBool>,
+    ctx: &egui::Context,
+) {
+    *state.lock().unwrap() = ConnectionState::Reconnecting;
+    ctx.request_repaint();
+
+    let client = JiraClient::new(config.clone());
+    let mut backoff = Duration::from_secs(5);
+    loop {
+        match client.test_connection().await {
+            Ok(_) => {
+                *state.lock().unwrap() = ConnectionState::Online;
+                ctx.request_repaint();
+                return;
+            }
+            Err(e) => {
+                *state.lock().unwrap() = ConnectionState::Offline(e);
+                ctx.request_repaint();
+            }
+        }
+        if stop.load(Ordering::Relaxed) {
+            return;
+        }
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(RECONNECT_MAX_BACKOFF);
+    }
+}